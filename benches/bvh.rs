@@ -0,0 +1,126 @@
+// Benchmarks for building and traversing the BVHs.
+//
+// Run with `cargo bench --no-default-features --features serde` on machines
+// without SFML.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tracy::{
+    hittable::{
+        bvh::{BvhNode, FlatBvh},
+        sphere::Sphere,
+        Hittable, HittableList,
+    },
+    material::lambertian::Lambertian,
+    random_float, random_float_between,
+    ray::Ray,
+    rng, Color, Point3, Vec3,
+};
+
+// The random scene from the end of "Ray Tracing in One Weekend": a grid of
+// small spheres, each nudged to a random spot in its cell, on a huge one.
+fn random_scene() -> HittableList {
+    rng::seed_stream(1);
+    let material = Lambertian::new(Color::from(0.5));
+    let mut world = HittableList::default();
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        material.clone(),
+    ));
+
+    for a in -11..11 {
+        for b in -11..11 {
+            let center = Point3::new(
+                a as f64 + 0.9 * random_float(),
+                0.2,
+                b as f64 + 0.9 * random_float(),
+            );
+            world.add(Sphere::new(center, 0.2, material.clone()));
+        }
+    }
+
+    world
+}
+
+// Small spheres scattered through a 100 unit cube.
+fn random_spheres(count: usize) -> HittableList {
+    rng::seed_stream(3);
+    let material = Lambertian::new(Color::from(0.5));
+    let mut world = HittableList::default();
+    for _ in 0..count {
+        world.add(Sphere::new(
+            Vec3::random_between(-50.0, 50.0),
+            random_float_between(0.05, 0.5),
+            material.clone(),
+        ));
+    }
+
+    world
+}
+
+// Rays from the book's camera position towards random points of the grid.
+fn camera_rays(count: usize) -> Vec<Ray> {
+    rng::seed_stream(2);
+    let origin = Point3::new(13.0, 2.0, 3.0);
+    (0..count)
+        .map(|_| {
+            let target = Point3::new(
+                random_float_between(-11.0, 11.0),
+                random_float_between(0.0, 1.0),
+                random_float_between(-11.0, 11.0),
+            );
+            Ray::new(origin, target - origin, None)
+        })
+        .collect()
+}
+
+fn trace(world: &dyn Hittable, rays: &[Ray]) -> usize {
+    rays.iter()
+        .filter(|ray| world.hit(ray, 0.001, f64::MAX).is_some())
+        .count()
+}
+
+fn traversal(c: &mut Criterion) {
+    let rays = camera_rays(10_000);
+    let median = BvhNode::new(random_scene(), 0.0, 1.0);
+    let sah = BvhNode::new_sah(random_scene(), 0.0, 1.0);
+
+    let mut group = c.benchmark_group("traverse random scene");
+    group.bench_function("median", |b| b.iter(|| trace(&median, black_box(&rays))));
+    group.bench_function("sah", |b| b.iter(|| trace(&sah, black_box(&rays))));
+    group.finish();
+}
+
+fn flat_traversal(c: &mut Criterion) {
+    let rays = camera_rays(10_000);
+    let tree = BvhNode::new_sah(random_scene(), 0.0, 1.0);
+    let flat = FlatBvh::from(BvhNode::new_sah(random_scene(), 0.0, 1.0));
+
+    let mut group = c.benchmark_group("traverse random scene sah");
+    group.bench_function("tree", |b| b.iter(|| trace(&tree, black_box(&rays))));
+    group.bench_function("flat", |b| b.iter(|| trace(&flat, black_box(&rays))));
+    group.finish();
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("build 100k spheres");
+    group.sample_size(10);
+    group.bench_function("sequential", |b| {
+        b.iter_batched(
+            || random_spheres(100_000),
+            |list| BvhNode::new_sah(list, 0.0, 1.0),
+            BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("parallel", |b| {
+        b.iter_batched(
+            || random_spheres(100_000).objects,
+            |objects| BvhNode::build_parallel(objects, 0.0, 1.0),
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, traversal, flat_traversal, build);
+criterion_main!(benches);
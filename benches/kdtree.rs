@@ -0,0 +1,67 @@
+// Benchmarks for the k-d tree photon mapping looks photons up in, against
+// scanning all of them.
+//
+// Run with `cargo bench --no-default-features --features serde --bench
+// kdtree` on machines without SFML.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tracy::{math::kdtree::KdTree, rng, Point3, Vec3};
+
+// Points scattered through a 100 unit cube, about as many as the photons
+// of a photon map.
+fn random_points(count: usize) -> Vec<Point3> {
+    rng::seed_stream(4);
+    (0..count)
+        .map(|_| Vec3::random_between(-50.0, 50.0))
+        .collect()
+}
+
+// The squared distances of the `k` points closest to `query`.
+fn linear_k_nearest(points: &[Point3], query: Point3, k: usize) -> Vec<f64> {
+    let mut distances: Vec<f64> = points
+        .iter()
+        .map(|&p| Vec3::distance_squared(query, p))
+        .collect();
+    distances.select_nth_unstable_by(k, f64::total_cmp);
+    distances.truncate(k);
+    distances
+}
+
+fn build(c: &mut Criterion) {
+    c.bench_function("build 100k points", |b| {
+        b.iter_batched(
+            || random_points(100_000),
+            KdTree::build,
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn k_nearest(c: &mut Criterion) {
+    let points = random_points(100_000);
+    let tree = KdTree::build(points.clone());
+    rng::seed_stream(5);
+    let queries: Vec<Point3> = (0..100)
+        .map(|_| Vec3::random_between(-50.0, 50.0))
+        .collect();
+
+    let mut group = c.benchmark_group("50 nearest of 100k points");
+    group.bench_function("kd tree", |b| {
+        b.iter(|| {
+            for &query in &queries {
+                black_box(tree.k_nearest(query, 50));
+            }
+        })
+    });
+    group.bench_function("linear scan", |b| {
+        b.iter(|| {
+            for &query in &queries {
+                black_box(linear_k_nearest(&points, query, 50));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, build, k_nearest);
+criterion_main!(benches);
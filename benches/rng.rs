@@ -0,0 +1,36 @@
+// Benchmarks for the generators the renderer can draw its random numbers
+// from, each drawing 10M floats in [0, 1).
+//
+// Run with `cargo bench --no-default-features --features serde --bench rng`
+// on machines without SFML.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use tracy::rng::pcg::Pcg32;
+
+const COUNT: usize = 10_000_000;
+
+fn sum_floats(rng: &mut impl Rng) -> f64 {
+    (0..COUNT).map(|_| rng.gen_range(0.0..1.0)).sum()
+}
+
+fn floats(c: &mut Criterion) {
+    let mut group = c.benchmark_group("10M floats");
+    group.sample_size(10);
+    group.bench_function("SmallRng", |b| {
+        let mut rng = SmallRng::seed_from_u64(1);
+        b.iter(|| black_box(sum_floats(&mut rng)))
+    });
+    group.bench_function("Pcg32", |b| {
+        let mut rng = Pcg32::seed_from_u64(1);
+        b.iter(|| black_box(sum_floats(&mut rng)))
+    });
+    group.bench_function("thread_rng", |b| {
+        let mut rng = rand::thread_rng();
+        b.iter(|| black_box(sum_floats(&mut rng)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, floats);
+criterion_main!(benches);
@@ -0,0 +1,117 @@
+// Renders a marble sphere next to a block of wood under the sky to
+// marble_wood.png, with the procedural MarbleTexture and WoodTexture.
+//
+// Run with `cargo run --release --example marble_wood`, adding
+// `--no-default-features --features serde` on machines without SFML.
+
+use std::{path::Path, sync::Arc, sync::Mutex};
+
+use tracy::{
+    background::rayleigh::RayleighSky,
+    camera::Camera,
+    config::RenderConfig,
+    hittable::{
+        aabox::AaBox,
+        light_list::LightList,
+        sphere::Sphere,
+        transform::{RotateY, Translate},
+        HittableList,
+    },
+    material::lambertian::Lambertian,
+    output::save_png,
+    render::render_with_progress,
+    rng,
+    texture::{marble::MarbleTexture, wood::WoodTexture},
+    tonemap::{tone_map, ToneMap},
+    Color, Point3, Vec3,
+};
+
+// The block's rings are centered on it, as it's built around the origin and
+// moved into place.
+fn marble_wood_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let ground = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground));
+
+    let marble = MarbleTexture::new(
+        12.0,
+        7,
+        Color::new(0.25, 0.22, 0.2),
+        Color::new(0.9, 0.88, 0.85),
+    );
+    world.add(Sphere::new(
+        Point3::new(-1.2, 0.8, 0.0),
+        0.8,
+        Lambertian::from_texture(Arc::new(marble)),
+    ));
+
+    let wood = WoodTexture::new(
+        2.0,
+        12.0,
+        4,
+        Color::new(0.35, 0.18, 0.07),
+        Color::new(0.75, 0.5, 0.28),
+    );
+    let block = AaBox::new(
+        Point3::new(-0.6, 0.0, -0.6),
+        Point3::new(0.6, 1.2, 0.6),
+        Lambertian::from_texture(Arc::new(wood)),
+    );
+    world.add(Translate::new(
+        RotateY::new(block, 30.0),
+        Vec3::new(1.2, 0.0, 0.0),
+    ));
+
+    world
+}
+
+fn main() {
+    let config = RenderConfig {
+        width: 600,
+        height: 400,
+        samples: 100,
+        max_depth: 50,
+    };
+    let camera = Camera::new(
+        Point3::new(0.0, 2.5, 6.0),
+        Point3::new(0.0, 0.7, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        config.aspect_ratio(),
+        0.0,
+        10.0,
+        None,
+    );
+    let world = marble_wood_scene();
+    let sky = RayleighSky::default();
+    let mut lights = LightList::default();
+    lights.add(Arc::new(sky.sun_light(3.0)));
+
+    rng::set_global_seed(1);
+    let pixels = Mutex::new(vec![
+        Color::default();
+        (config.width * config.height) as usize
+    ]);
+    render_with_progress(&config, &world, &lights, &camera, &sky, |x, y, color| {
+        pixels.lock().unwrap()[(y * config.width + x) as usize] = color;
+    });
+
+    let bytes: Vec<u8> = pixels
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flat_map(|color| {
+            let mapped = tone_map(color, ToneMap::default());
+            [mapped.x(), mapped.y(), mapped.z()].map(|c| (255.99 * c) as u8)
+        })
+        .collect();
+    let path = Path::new("marble_wood.png");
+    match save_png(&bytes, config.width, config.height, path) {
+        Ok(()) => println!("Saved {}", path.display()),
+        Err(err) => {
+            eprintln!("Unable to save {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
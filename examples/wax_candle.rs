@@ -0,0 +1,116 @@
+// Renders a wax candle to wax_candle.png: a block of wax with its flame
+// above it, between a wax ball and a diffuse ball of the same color. The
+// wax glows where the flame shines into it and looks soft, while the
+// diffuse ball is lit on one side only. scenes/wax_candle.json is the same
+// scene for --scene.
+//
+// Run with `cargo run --release --example wax_candle`, adding
+// `--no-default-features --features serde` on machines without SFML.
+
+use std::{path::Path, sync::Arc, sync::Mutex};
+
+use tracy::{
+    background::constant::ConstantBackground,
+    camera::Camera,
+    config::RenderConfig,
+    hittable::{aabox::AaBox, light_list::LightList, plane::Plane, sphere::Sphere, HittableList},
+    material::{diffuse_light::DiffuseLight, lambertian::Lambertian, sss::SubSurfaceScattering},
+    output::save_png,
+    render::render_with_progress,
+    rng,
+    tonemap::{tone_map, ToneMap},
+    Color, Point3, Vec3,
+};
+
+fn flame() -> Sphere<DiffuseLight> {
+    Sphere::new(
+        Point3::new(0.0, 1.78, 0.0),
+        0.12,
+        DiffuseLight::new(Color::new(12.0, 8.0, 3.0)),
+    )
+}
+
+fn wax_candle_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    world.add(Plane::new(
+        Point3::from(0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Lambertian::new(Color::new(0.3, 0.25, 0.2)),
+    ));
+
+    let color = Color::new(0.9, 0.8, 0.6);
+    let wax = SubSurfaceScattering::new(color, 0.08);
+    world.add(AaBox::new(
+        Point3::new(-0.3, 0.0, -0.3),
+        Point3::new(0.3, 1.6, 0.3),
+        wax.clone(),
+    ));
+    world.add(Sphere::new(Point3::new(0.9, 0.35, -0.4), 0.35, wax));
+    world.add(Sphere::new(
+        Point3::new(-0.9, 0.35, -0.4),
+        0.35,
+        Lambertian::new(color),
+    ));
+    world.add(flame());
+
+    world
+}
+
+fn main() {
+    // Paths wander through the wax for many bounces before they leave it.
+    let config = RenderConfig {
+        width: 300,
+        height: 400,
+        samples: 200,
+        max_depth: 500,
+    };
+    let camera = Camera::new(
+        Point3::new(0.0, 1.6, -4.5),
+        Point3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0,
+        config.aspect_ratio(),
+        0.0,
+        10.0,
+        None,
+    );
+    let world = wax_candle_scene();
+    let mut lights = LightList::default();
+    lights.add(Arc::new(flame()));
+    let background = ConstantBackground(Color::new(0.01, 0.01, 0.015));
+
+    rng::set_global_seed(1);
+    let pixels = Mutex::new(vec![
+        Color::default();
+        (config.width * config.height) as usize
+    ]);
+    render_with_progress(
+        &config,
+        &world,
+        &lights,
+        &camera,
+        &background,
+        |x, y, color| {
+            pixels.lock().unwrap()[(y * config.width + x) as usize] = color;
+        },
+    );
+
+    let bytes: Vec<u8> = pixels
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flat_map(|color| {
+            let mapped = tone_map(color, ToneMap::default());
+            [mapped.x(), mapped.y(), mapped.z()].map(|c| (255.99 * c) as u8)
+        })
+        .collect();
+    let path = Path::new("wax_candle.png");
+    match save_png(&bytes, config.width, config.height, path) {
+        Ok(()) => println!("Saved {}", path.display()),
+        Err(err) => {
+            eprintln!("Unable to save {}: {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{ray::Ray, Point3};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3,
+}
+
+impl Aabb {
+    // Builds the box spanned by two opposite corners, given in any order.
+    pub fn new(a: Point3, b: Point3) -> Self {
+        Self {
+            min: Point3::min_components(a, b),
+            max: Point3::max_components(a, b),
+        }
+    }
+
+    pub fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> bool {
+        self.hit_interval(ray, t_min, t_max).is_some()
+    }
+
+    // The part of [t_min, t_max] during which the ray is inside the box, if
+    // any.
+    pub fn hit_interval(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<(f64, f64)> {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+
+        // Slab method: intersect the ray with the pair of planes bounding
+        // each axis and narrow down the overlapping t interval.
+        for a in 0..3 {
+            // A ray parallel to the slab never crosses its planes, so it
+            // either lies between them for all t or misses the box. Handling
+            // this explicitly avoids 0 * inf = NaN below.
+            if ray.direction[a] == 0.0 {
+                if ray.origin[a] < self.min[a] || ray.origin[a] > self.max[a] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv_d = 1.0 / ray.direction[a];
+            let mut t0 = (self.min[a] - ray.origin[a]) * inv_d;
+            let mut t1 = (self.max[a] - ray.origin[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            t_min = if t0 > t_min { t0 } else { t_min };
+            t_max = if t1 < t_max { t1 } else { t_max };
+            if t_max <= t_min {
+                return None;
+            }
+        }
+
+        Some((t_min, t_max))
+    }
+
+    // Returns a copy of the box where any axis thinner than a small delta is
+    // widened, so flat primitives (triangles, rectangles, ...) still have a
+    // volume the slab test can hit.
+    pub fn padded(&self) -> Self {
+        const DELTA: f64 = 1e-4;
+        let mut padded = *self;
+        for a in 0..3 {
+            if padded.max[a] - padded.min[a] < DELTA {
+                padded.min[a] -= DELTA / 2.0;
+                padded.max[a] += DELTA / 2.0;
+            }
+        }
+
+        padded
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let extent = self.max - self.min;
+        if extent.x() > extent.y() && extent.x() > extent.z() {
+            0
+        } else if extent.y() > extent.z() {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let extent = self.max - self.min;
+        2.0 * (extent.x() * extent.y() + extent.y() * extent.z() + extent.z() * extent.x())
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        (self.min + self.max) * 0.5
+    }
+}
+
+pub fn surrounding_box(a: Aabb, b: Aabb) -> Aabb {
+    Aabb {
+        min: Point3::min_components(a.min, b.min),
+        max: Point3::max_components(a.max, b.max),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::{sphere::Sphere, Hittable},
+        material::lambertian::Lambertian,
+        Color, Vec3,
+    };
+
+    fn unit_box() -> Aabb {
+        Aabb::new(Point3::new(1.0, 1.0, 1.0), Point3::new(-1.0, -1.0, -1.0))
+    }
+
+    #[test]
+    fn rays_along_each_axis_hit_or_miss() {
+        let bbox = unit_box();
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut origin = Point3::new(0.0, 0.0, 0.0);
+                origin[axis] = -5.0 * sign;
+                let mut direction = Vec3::new(0.0, 0.0, 0.0);
+                direction[axis] = sign;
+
+                // The other two direction components are exactly zero.
+                let towards = Ray::new(origin, direction, None);
+                assert!(bbox.hit(&towards, 0.001, f64::INFINITY), "{axis} {sign}");
+                assert_eq!(
+                    bbox.hit_interval(&towards, 0.001, f64::INFINITY),
+                    Some((4.0, 6.0))
+                );
+                let away = Ray::new(origin, -direction, None);
+                assert!(!bbox.hit(&away, 0.001, f64::INFINITY), "{axis} {sign}");
+                assert!(!bbox.hit(&towards, 0.001, 3.0), "{axis} {sign}");
+
+                // Off to the side of the box along another axis.
+                let mut beside = origin;
+                beside[(axis + 1) % 3] = 1.5;
+                let beside = Ray::new(beside, direction, None);
+                assert!(!bbox.hit(&beside, 0.001, f64::INFINITY), "{axis} {sign}");
+            }
+        }
+    }
+
+    #[test]
+    fn rays_starting_inside_hit_until_they_leave() {
+        let bbox = unit_box();
+        let ray = Ray::new(Point3::new(0.5, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0), None);
+        assert_eq!(
+            bbox.hit_interval(&ray, 0.0, f64::INFINITY),
+            Some((0.0, 0.5))
+        );
+    }
+
+    #[test]
+    fn surrounding_boxes_contain_both() {
+        let a = Aabb::new(Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0));
+        let b = Aabb::new(Point3::new(-2.0, 0.5, 0.5), Point3::new(0.5, 3.0, 0.7));
+        let both = surrounding_box(a, b);
+        assert_eq!(both.min, Point3::new(-2.0, 0.0, 0.0));
+        assert_eq!(both.max, Point3::new(1.0, 3.0, 1.0));
+
+        let sphere = Sphere::new(
+            Point3::new(1.0, 2.0, 3.0),
+            0.5,
+            Lambertian::new(Color::from(0.5)),
+        );
+        let bbox = sphere.bounding_box(0.0, 1.0).unwrap();
+        assert_eq!(bbox.min, Point3::new(0.5, 1.5, 2.5));
+        assert_eq!(bbox.max, Point3::new(1.5, 2.5, 3.5));
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{Color, Vec3};
+
+use super::Background;
+
+// The same radiance from every direction, e.g. black for scenes lit only by
+// their lights. This is the usual ambient term, but not a flat extra color
+// added to every hit: it is an environment map of uniform intensity, so it
+// lights surfaces only through the directions that see the sky. An
+// unoccluded white Lambertian surface under color c reflects c, and
+// corners and the undersides of objects get less, like under an overcast
+// sky.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantBackground(pub Color);
+
+impl Background for ConstantBackground {
+    fn sample(&self, _direction: Vec3) -> Color {
+        self.0
+    }
+}
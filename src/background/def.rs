@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use crate::{hittable::sun::SunLight, Color, Vec3};
+
+use super::{
+    constant::ConstantBackground,
+    gradient::GradientSky,
+    hdr::{HdrEnvironment, HdrError},
+    rayleigh::RayleighSky,
+    Background,
+};
+
+// Plain description of a built-in background, for scene files. A scene
+// without one gets the default Rayleigh sky.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum BackgroundDef {
+    // Uniform ambient light, e.g. "color": [0.1, 0.1, 0.1], or black for
+    // scenes lit only by their lights.
+    Constant {
+        color: Color,
+    },
+    Gradient {
+        top: Color,
+        bottom: Color,
+    },
+    // The sun is sampled as a light too, `sun_irradiance` bright above
+    // the atmosphere, unless that is 0.
+    Rayleigh {
+        sun_direction: Vec3,
+        sun_intensity: f64,
+        #[cfg_attr(feature = "serde", serde(default = "default_sun_irradiance"))]
+        sun_irradiance: f64,
+    },
+    // An equirectangular Radiance .hdr image (e.g. from Poly Haven), which
+    // also shows up in reflections.
+    Hdr {
+        path: PathBuf,
+    },
+}
+
+fn default_sun_irradiance() -> f64 {
+    3.0
+}
+
+impl Default for BackgroundDef {
+    fn default() -> Self {
+        let sky = RayleighSky::default();
+        BackgroundDef::Rayleigh {
+            sun_direction: sky.sun_direction,
+            sun_intensity: sky.sun_intensity,
+            sun_irradiance: default_sun_irradiance(),
+        }
+    }
+}
+
+impl BackgroundDef {
+    // Fails only if an HDR image can't be loaded.
+    pub fn build(&self) -> Result<Box<dyn Background>, HdrError> {
+        Ok(match self {
+            BackgroundDef::Constant { color } => Box::new(ConstantBackground(*color)),
+            BackgroundDef::Gradient { top, bottom } => Box::new(GradientSky::new(*top, *bottom)),
+            BackgroundDef::Rayleigh {
+                sun_direction,
+                sun_intensity,
+                ..
+            } => Box::new(RayleighSky::new(*sun_direction, *sun_intensity)),
+            BackgroundDef::Hdr { path } => Box::new(HdrEnvironment::new(path)?),
+        })
+    }
+
+    // The light the background casts that scenes need to sample, if any.
+    pub fn sun(&self) -> Option<SunLight> {
+        match self {
+            BackgroundDef::Rayleigh {
+                sun_direction,
+                sun_intensity,
+                sun_irradiance,
+            } if *sun_irradiance > 0.0 => {
+                let sky = RayleighSky::new(*sun_direction, *sun_intensity);
+                Some(sky.sun_light(*sun_irradiance))
+            }
+            _ => None,
+        }
+    }
+}
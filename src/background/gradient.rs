@@ -0,0 +1,39 @@
+use crate::{Color, Vec3};
+
+use super::Background;
+
+// A sky blending from `bottom` straight down to `top` straight up. The
+// default is the classic white-to-blue sky.
+#[derive(Debug, Clone, Copy)]
+pub struct GradientSky {
+    pub top: Color,
+    pub bottom: Color,
+}
+
+impl GradientSky {
+    pub fn new(top: Color, bottom: Color) -> Self {
+        Self { top, bottom }
+    }
+}
+
+impl Default for GradientSky {
+    fn default() -> Self {
+        Self::new(Color::new(0.5, 0.7, 1.0), Color::new(1.0, 1.0, 1.0))
+    }
+}
+
+impl Background for GradientSky {
+    fn sample(&self, direction: Vec3) -> Color {
+        // unit_direction is a vector of length 1 that points in the direction
+        // of the ray. The x and y components are between -1 and 1. If we add 1
+        // to the y component, then the y component will be between 0 and 2. We
+        // multiply this by 0.5, so the y component will be between 0 and 1.
+        // This gives us a value that can be used as a lerp parameter.
+        // Which means we can use it to interpolate between the two colors.
+        let unit_direction = direction.unit_vector();
+        let t = 0.5 * (unit_direction.y() + 1.0);
+
+        // Linear interpolation between the bottom and top colors.
+        Color::lerp(self.bottom, self.top, t)
+    }
+}
@@ -0,0 +1,263 @@
+use std::{error::Error, fmt, fs, io, path::Path};
+
+use crate::{hittable::sphere::sphere_uv, Color, Vec3};
+
+use super::Background;
+
+#[derive(Debug)]
+pub enum HdrError {
+    Io(io::Error),
+    Format(String),
+}
+
+impl fmt::Display for HdrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HdrError::Io(err) => write!(f, "unable to read HDR image: {}", err),
+            HdrError::Format(message) => write!(f, "invalid HDR image: {}", message),
+        }
+    }
+}
+
+impl Error for HdrError {}
+
+impl From<io::Error> for HdrError {
+    fn from(err: io::Error) -> Self {
+        HdrError::Io(err)
+    }
+}
+
+// An equirectangular high dynamic range image surrounding the scene. It is
+// mapped onto directions the same way textures are mapped onto a sphere,
+// so an HDRI shows up as if painted on the inside of a huge sphere.
+pub struct HdrEnvironment {
+    // Linear radiance, rows top to bottom.
+    pub pixels: Vec<Color>,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl HdrEnvironment {
+    // Loads a Radiance RGBE (.hdr) file, either flat or run-length encoded.
+    pub fn new(path: &Path) -> Result<Self, HdrError> {
+        let data = fs::read(path)?;
+        let mut reader = Reader {
+            data: &data,
+            pos: 0,
+        };
+
+        let magic = reader.line()?;
+        if !magic.starts_with("#?") {
+            return Err(format_error("missing #? signature"));
+        }
+        loop {
+            let line = reader.line()?;
+            if line.is_empty() {
+                break;
+            }
+            let format = line.strip_prefix("FORMAT=");
+            if let Some(format) = format.filter(|&f| f != "32-bit_rle_rgbe") {
+                return Err(format_error(&format!("unsupported format {}", format)));
+            }
+        }
+
+        // Only the standard orientation (rows top to bottom, columns left to
+        // right) is supported.
+        let resolution = reader.line()?;
+        let (height, width) = match resolution.split_whitespace().collect::<Vec<_>>()[..] {
+            ["-Y", h, "+X", w] => (h.parse().ok(), w.parse().ok()),
+            _ => (None, None),
+        };
+        let (Some(height), Some(width)) = (height, width) else {
+            return Err(format_error(&format!(
+                "unsupported resolution '{}'",
+                resolution
+            )));
+        };
+
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        let mut scanline = vec![[0u8; 4]; width as usize];
+        for _ in 0..height {
+            reader.scanline(&mut scanline)?;
+            pixels.extend(scanline.iter().map(|&rgbe| rgbe_to_color(rgbe)));
+        }
+
+        Ok(Self {
+            pixels,
+            width,
+            height,
+        })
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        // Wrap around horizontally, clamp vertically.
+        let x = x.rem_euclid(self.width as i64) as usize;
+        let y = y.clamp(0, self.height as i64 - 1) as usize;
+        self.pixels[y * self.width as usize + x]
+    }
+}
+
+impl Background for HdrEnvironment {
+    fn sample(&self, direction: Vec3) -> Color {
+        let (u, v) = sphere_uv(direction.unit_vector());
+
+        // Bilinear filtering between the four nearest texels, whose centers
+        // sit at half-integer coordinates.
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v) * self.height as f64 - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = Color::lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), tx);
+        let bottom = Color::lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), tx);
+        Color::lerp(top, bottom, ty)
+    }
+}
+
+fn format_error(message: &str) -> HdrError {
+    HdrError::Format(message.to_string())
+}
+
+// Each pixel stores three 8-bit mantissas sharing one exponent byte.
+fn rgbe_to_color([r, g, b, e]: [u8; 4]) -> Color {
+    if e == 0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+
+    let scale = 2f64.powi(e as i32 - (128 + 8));
+    Color::new(r as f64 * scale, g as f64 * scale, b as f64 * scale)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self) -> Result<u8, HdrError> {
+        let byte = *self
+            .data
+            .get(self.pos)
+            .ok_or_else(|| format_error("unexpected end of file"))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn line(&mut self) -> Result<String, HdrError> {
+        let mut line = Vec::new();
+        loop {
+            match self.byte()? {
+                b'\n' => break,
+                byte => line.push(byte),
+            }
+        }
+
+        Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+    }
+
+    fn scanline(&mut self, scanline: &mut [[u8; 4]]) -> Result<(), HdrError> {
+        let width = scanline.len();
+        let header = [self.byte()?, self.byte()?, self.byte()?, self.byte()?];
+
+        // Run-length encoded scanlines start with 2, 2 and the width, and
+        // store each of the four components separately. Anything else is a
+        // flat scanline, whose first pixel we just read.
+        let encoded = (8..0x8000).contains(&width) && header[0] == 2 && header[1] == 2;
+        if !encoded {
+            scanline[0] = header;
+            for pixel in scanline.iter_mut().skip(1) {
+                *pixel = [self.byte()?, self.byte()?, self.byte()?, self.byte()?];
+            }
+            return Ok(());
+        }
+        if ((header[2] as usize) << 8 | header[3] as usize) != width {
+            return Err(format_error("scanline width mismatch"));
+        }
+
+        for channel in 0..4 {
+            let mut x = 0;
+            while x < width {
+                let count = self.byte()? as usize;
+                if count > 128 {
+                    // A run of one repeated value.
+                    let count = count - 128;
+                    if x + count > width {
+                        return Err(format_error("run overflows scanline"));
+                    }
+                    let value = self.byte()?;
+                    for pixel in &mut scanline[x..x + count] {
+                        pixel[channel] = value;
+                    }
+                    x += count;
+                } else {
+                    // A sequence of literal values.
+                    if count == 0 || x + count > width {
+                        return Err(format_error("bad literal run"));
+                    }
+                    for pixel in &mut scanline[x..x + count] {
+                        pixel[channel] = self.byte()?;
+                    }
+                    x += count;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &[u8] = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n";
+
+    fn load(name: &str, data: &[u8]) -> Result<HdrEnvironment, HdrError> {
+        let path = std::env::temp_dir().join(name);
+        fs::write(&path, data).unwrap();
+        HdrEnvironment::new(&path)
+    }
+
+    #[test]
+    fn run_length_encoded_scanlines_are_decoded() {
+        let mut data = HEADER.to_vec();
+        data.extend(b"-Y 1 +X 8\n");
+        data.extend([2, 2, 0, 8]);
+        // Red is one run, green a run followed by literals.
+        data.extend([128 + 8, 128]);
+        data.extend([128 + 4, 64, 4, 16, 32, 48, 64]);
+        data.extend([128 + 8, 32]);
+        data.extend([128 + 8, 129]);
+
+        let hdr = load("tracy-rle.hdr", &data).unwrap();
+        assert_eq!((hdr.width, hdr.height), (8, 1));
+        assert_eq!(hdr.pixels[0], Color::new(1.0, 0.5, 0.25));
+        assert_eq!(hdr.pixels[3], Color::new(1.0, 0.5, 0.25));
+        assert_eq!(hdr.pixels[4], Color::new(1.0, 0.125, 0.25));
+        assert_eq!(hdr.pixels[7], Color::new(1.0, 0.5, 0.25));
+    }
+
+    #[test]
+    fn malformed_files_are_rejected() {
+        let missing = HdrEnvironment::new(&std::env::temp_dir().join("tracy-missing.hdr"));
+        assert!(matches!(missing, Err(HdrError::Io(_))));
+
+        let unsigned = load("tracy-unsigned.hdr", b"RADIANCE\n\n-Y 1 +X 1\n\0\0\0\0");
+        assert!(matches!(unsigned, Err(HdrError::Format(_))));
+        let mut flipped = HEADER.to_vec();
+        flipped.extend(b"+Y 1 +X 1\n\0\0\0\0");
+        assert!(matches!(
+            load("tracy-flipped.hdr", &flipped),
+            Err(HdrError::Format(_))
+        ));
+        let mut short = HEADER.to_vec();
+        short.extend(b"-Y 2 +X 1\n\0\0\0\0");
+        assert!(matches!(
+            load("tracy-short.hdr", &short),
+            Err(HdrError::Format(_))
+        ));
+    }
+}
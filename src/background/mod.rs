@@ -0,0 +1,16 @@
+use crate::{Color, Vec3};
+
+pub mod constant;
+pub mod def;
+pub mod gradient;
+pub mod hdr;
+pub mod rayleigh;
+
+// Radiance arriving from infinitely far away, seen by rays that leave the
+// scene without hitting anything. In the rendering equation it is the
+// light coming in from the environment: a surface lit only by the
+// background gathers `sample` over the directions that escape, weighted
+// by its BRDF and the cosine like light from any other source.
+pub trait Background: Send + Sync {
+    fn sample(&self, direction: Vec3) -> Color;
+}
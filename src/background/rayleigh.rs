@@ -0,0 +1,250 @@
+use std::f64::consts::PI;
+
+use crate::{hittable::sun::SunLight, Color, Point3, Vec3};
+
+use super::Background;
+
+// Radii of the earth and the top of its atmosphere in meters.
+const EARTH_RADIUS: f64 = 6360e3;
+const ATMOSPHERE_RADIUS: f64 = 6420e3;
+
+// Height over which the density of air drops by a factor of e.
+const SCALE_HEIGHT: f64 = 7994.0;
+
+// Rayleigh scattering coefficients of air at sea level per meter, for red,
+// green and blue light (680, 550 and 440 nm). Scattering grows with the
+// inverse fourth power of the wavelength, which is why the sky is blue and
+// sunsets are red.
+const BETA: [f64; 3] = [5.8e-6, 13.5e-6, 33.1e-6];
+
+// Samples along the view ray, and along each ray from there to the sun.
+const VIEW_SAMPLES: usize = 16;
+const SUN_SAMPLES: usize = 8;
+
+// Angular radius of the sun's disk.
+const SUN_ANGULAR_RADIUS: f64 = 0.0047;
+
+// A clear sky lit by the sun, from Nishita et al., "Display of the Earth
+// Taking into Account Atmospheric Scattering" (1993), with Rayleigh
+// scattering only. The light scattered towards the viewer once is summed
+// along the view ray through the atmosphere, each sample lit by sunlight
+// dimmed on its way there and back out. Looking up the path is short and
+// mostly blue light is scattered into it; towards the horizon it's long,
+// and much of the blue is scattered out again before it arrives.
+//
+// The viewer stands on the ground; below the horizon the sky keeps the
+// horizon's color, as if the ground were far away. The sun's disk is drawn
+// at `sun_intensity` times the light reaching the viewer, so it turns from
+// yellowish white overhead to red on the horizon. That is only bright
+// enough to show, not to light the scene: at its true brightness paths
+// that hit it by chance would be fireflies.
+#[derive(Debug, Clone, Copy)]
+pub struct RayleighSky {
+    // Towards the sun, normalized.
+    pub sun_direction: Vec3,
+    // Brightness of the incoming sunlight the sky scatters.
+    pub sun_intensity: f64,
+}
+
+impl RayleighSky {
+    pub fn new(sun_direction: Vec3, sun_intensity: f64) -> Self {
+        Self {
+            sun_direction: sun_direction.unit_vector(),
+            sun_intensity,
+        }
+    }
+
+    // The sun `elevation_degrees` above the horizon, towards -z at 0
+    // azimuth and turning towards +x.
+    pub fn from_angles(elevation_degrees: f64, azimuth_degrees: f64, sun_intensity: f64) -> Self {
+        let (elevation, azimuth) = (elevation_degrees.to_radians(), azimuth_degrees.to_radians());
+        Self::new(
+            Vec3::new(
+                elevation.cos() * azimuth.sin(),
+                elevation.sin(),
+                -elevation.cos() * azimuth.cos(),
+            ),
+            sun_intensity,
+        )
+    }
+}
+
+impl RayleighSky {
+    // The sun as a light for a `LightList`, since the disk the sky draws
+    // is too dim to light anything. `irradiance` is the sunlight arriving
+    // above the atmosphere, which the air between tints yellowish at midday
+    // and red at sunset, as it does the disk.
+    pub fn sun_light(&self, irradiance: f64) -> SunLight {
+        let optical_depth =
+            optical_depth_to_sun(viewer(), self.sun_direction).unwrap_or(f64::INFINITY);
+        SunLight::new(
+            self.sun_direction,
+            SUN_ANGULAR_RADIUS,
+            transmittance(optical_depth) * irradiance,
+        )
+    }
+}
+
+impl Default for RayleighSky {
+    // A sun high in the afternoon sky.
+    fn default() -> Self {
+        Self::from_angles(50.0, 30.0, 20.0)
+    }
+}
+
+impl Background for RayleighSky {
+    fn sample(&self, direction: Vec3) -> Color {
+        let direction = below_horizon_to_horizon(direction.unit_vector());
+        let origin = viewer();
+
+        let length = distance_to_space(origin, direction);
+        let step = length / VIEW_SAMPLES as f64;
+        let mut optical_depth = 0.0;
+        let mut in_scattered = Color::new(0.0, 0.0, 0.0);
+        for i in 0..VIEW_SAMPLES {
+            let p = origin + direction * ((i as f64 + 0.5) * step);
+            let density = air_density(p) * step;
+            optical_depth += density;
+
+            // Sunlight behind the earth doesn't reach the sample.
+            let Some(sun_depth) = optical_depth_to_sun(p, self.sun_direction) else {
+                continue;
+            };
+            in_scattered += transmittance(optical_depth + sun_depth) * density;
+        }
+
+        let mu = direction.dot(self.sun_direction);
+        let phase = 3.0 / (16.0 * PI) * (1.0 + mu * mu);
+        let mut radiance = in_scattered * Color::from(BETA) * (phase * self.sun_intensity);
+
+        if mu > SUN_ANGULAR_RADIUS.cos() {
+            radiance += transmittance(optical_depth) * self.sun_intensity;
+        }
+        radiance
+    }
+}
+
+// Where the sky is seen from, a meter above the ground.
+fn viewer() -> Point3 {
+    Point3::new(0.0, EARTH_RADIUS + 1.0, 0.0)
+}
+
+// Flattens unit vectors pointing below the horizon onto it. Straight down
+// there is no nearest point of the horizon, and any of them will do.
+fn below_horizon_to_horizon(direction: Vec3) -> Vec3 {
+    if direction.y() >= 0.0 {
+        return direction;
+    }
+    let horizontal = Vec3::new(direction.x(), 0.0, direction.z());
+    if horizontal.length_squared() < 1e-12 {
+        return Vec3::new(1.0, 0.0, 0.0);
+    }
+    horizontal.unit_vector()
+}
+
+// Share of each of red, green and blue light left after passing through
+// `optical_depth` meters of air at sea level density.
+fn transmittance(optical_depth: f64) -> Color {
+    Color::new(
+        (-BETA[0] * optical_depth).exp(),
+        (-BETA[1] * optical_depth).exp(),
+        (-BETA[2] * optical_depth).exp(),
+    )
+}
+
+// Density of air at `p` relative to sea level.
+fn air_density(p: Point3) -> f64 {
+    let height = p.length() - EARTH_RADIUS;
+    (-height / SCALE_HEIGHT).exp()
+}
+
+// Air between `p` and the sun, in meters at sea level density, or None if
+// the earth is in the way.
+fn optical_depth_to_sun(p: Point3, sun_direction: Vec3) -> Option<f64> {
+    let length = distance_to_space(p, sun_direction);
+    let step = length / SUN_SAMPLES as f64;
+    let mut optical_depth = 0.0;
+    for i in 0..SUN_SAMPLES {
+        let q = p + sun_direction * ((i as f64 + 0.5) * step);
+        if q.length() < EARTH_RADIUS {
+            return None;
+        }
+        optical_depth += air_density(q) * step;
+    }
+    Some(optical_depth)
+}
+
+// Distance from `origin`, inside the atmosphere, to its top along the unit
+// vector `direction`.
+fn distance_to_space(origin: Point3, direction: Vec3) -> f64 {
+    let half_b = origin.dot(direction);
+    let c = origin.length_squared() - ATMOSPHERE_RADIUS * ATMOSPHERE_RADIUS;
+    -half_b + (half_b * half_b - c).max(0.0).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_vec3_approx_eq;
+
+    // Blue over red, which the sky scatters the most and the least of.
+    fn blueness(color: Color) -> f64 {
+        color.z() / color.x()
+    }
+
+    #[test]
+    fn zenith_is_bluer_than_the_horizon() {
+        let sky = RayleighSky::default();
+        let zenith = sky.sample(Vec3::new(0.0, 1.0, 0.0));
+        for horizon in [
+            Vec3::new(1.0, 0.02, 0.0),
+            Vec3::new(-1.0, 0.02, 0.0),
+            Vec3::new(0.0, 0.02, 1.0),
+            Vec3::new(0.0, 0.02, -1.0),
+        ] {
+            let horizon = sky.sample(horizon);
+            assert!(
+                blueness(zenith) > blueness(horizon),
+                "zenith {} horizon {}",
+                zenith,
+                horizon
+            );
+        }
+    }
+
+    #[test]
+    fn sun_is_redder_at_sunset_than_at_midday() {
+        let midday = RayleighSky::from_angles(70.0, 0.0, 20.0);
+        let sunset = RayleighSky::from_angles(1.0, 0.0, 20.0);
+        let midday_sun = midday.sample(midday.sun_direction);
+        let sunset_sun = sunset.sample(sunset.sun_direction);
+
+        // Yellowish white overhead: red and green stay close.
+        assert!(midday_sun.y() / midday_sun.x() > 0.8);
+        assert!(midday_sun.z() < midday_sun.x());
+        assert!(blueness(sunset_sun) < blueness(midday_sun));
+        assert!(sunset_sun.y() / sunset_sun.x() < midday_sun.y() / midday_sun.x());
+    }
+
+    #[test]
+    fn sunlight_is_tinted_like_the_sun() {
+        let midday = RayleighSky::from_angles(70.0, 0.0, 20.0).sun_light(3.0);
+        let sunset = RayleighSky::from_angles(1.0, 0.0, 20.0).sun_light(3.0);
+        let night = RayleighSky::from_angles(-10.0, 0.0, 20.0).sun_light(3.0);
+
+        assert!(midday.irradiance.x() > 2.5 && midday.irradiance.x() < 3.0);
+        assert!(blueness(sunset.irradiance) < blueness(midday.irradiance));
+        assert_vec3_approx_eq!(night.irradiance, Color::from(0.0), 1e-12);
+    }
+
+    #[test]
+    fn below_the_horizon_looks_like_the_horizon() {
+        let sky = RayleighSky::default();
+        let down = sky.sample(Vec3::new(0.0, -1.0, 0.0));
+        assert!(down.x().is_finite() && down.y().is_finite() && down.z().is_finite());
+        assert_vec3_approx_eq!(down, sky.sample(Vec3::new(1.0, 0.0, 0.0)), 1e-12);
+
+        let below = sky.sample(Vec3::new(0.3, -0.5, -1.0));
+        assert_vec3_approx_eq!(below, sky.sample(Vec3::new(0.3, 0.0, -1.0)), 1e-12);
+    }
+}
@@ -1,76 +0,0 @@
-use std::f64::consts::PI;
-
-use crate::{random_float_between, ray::Ray, Point3, Vec3};
-
-pub struct Camera {
-    origin: Point3,
-    lower_left_corner: Point3,
-    horizontal: Vec3,
-    vertical: Vec3,
-    u: Vec3,
-    v: Vec3,
-    lens_radius: f64,
-    shutter_time: (f64, f64),
-}
-
-impl Camera {
-    pub fn new(
-        lookfrom: Point3,
-        lookat: Point3,
-        vup: Vec3,
-        vfov: f64,
-        aspect_ratio: f64,
-        aperture: f64,
-        focus_dist: f64,
-        shutter_time: Option<(f64, f64)>,
-    ) -> Self {
-        let theta = Self::degrees_to_radians(vfov);
-        let h = f64::tan(theta / 2.0);
-        let viewport_height = 2.0 * h;
-        let viewport_width = aspect_ratio * viewport_height;
-
-        let w = (lookfrom - lookat).unit_vector();
-        let u = vup.cross(w).unit_vector();
-        let v = w.cross(u);
-
-        let origin = lookfrom;
-        let horizontal = u * viewport_width * focus_dist;
-        let vertical = v * viewport_height * focus_dist;
-        let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - w * focus_dist;
-
-        let lens_radius = aperture / 2.0;
-
-        Self {
-            origin,
-            lower_left_corner,
-            horizontal,
-            vertical,
-            u,
-            v,
-            lens_radius,
-            shutter_time: if let Some(t) = shutter_time {
-                t
-            } else {
-                (0.0, 0.0)
-            },
-        }
-    }
-
-    fn degrees_to_radians(degrees: f64) -> f64 {
-        degrees * PI / 180.0
-    }
-
-    pub fn get_ray(&self, s: f64, t: f64) -> Ray {
-        let rd = Vec3::random_in_unit_disk() * self.lens_radius;
-        let offset = self.u * rd.x() + self.v * rd.y();
-
-        Ray::new(
-            self.origin + offset,
-            self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin - offset,
-            Some(random_float_between(
-                self.shutter_time.0,
-                self.shutter_time.1,
-            )),
-        )
-    }
-}
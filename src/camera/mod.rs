@@ -0,0 +1,224 @@
+use std::f64::consts::PI;
+
+use crate::{random_float_between, ray::Ray, sampling, Point3, Vec3};
+
+pub mod orthographic;
+pub mod spherical;
+
+// Anything that can turn a position on the image plane into a primary ray.
+// `s` and `t` run from 0 to 1 across the viewport, left to right and bottom
+// to top.
+pub trait RayGenerator: Send + Sync {
+    fn get_ray(&self, s: f64, t: f64) -> Ray;
+}
+
+// Shape of the lens opening. Out-of-focus highlights take on this shape.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ApertureShape {
+    Circle,
+    Polygon { sides: u32, rotation_degrees: f64 },
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Camera {
+    origin: Point3,
+    lower_left_corner: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    u: Vec3,
+    v: Vec3,
+    lens_radius: f64,
+    aperture_shape: ApertureShape,
+    shutter_time: (f64, f64),
+}
+
+impl Camera {
+    pub fn new(
+        lookfrom: Point3,
+        lookat: Point3,
+        vup: Vec3,
+        vfov: f64,
+        aspect_ratio: f64,
+        aperture: f64,
+        focus_dist: f64,
+        shutter_time: Option<(f64, f64)>,
+    ) -> Self {
+        let theta = Self::degrees_to_radians(vfov);
+        let h = f64::tan(theta / 2.0);
+        let viewport_height = 2.0 * h;
+        let viewport_width = aspect_ratio * viewport_height;
+
+        let w = (lookfrom - lookat).unit_vector();
+        let u = vup.cross(w).unit_vector();
+        let v = w.cross(u);
+
+        let origin = lookfrom;
+        let horizontal = u * viewport_width * focus_dist;
+        let vertical = v * viewport_height * focus_dist;
+        let lower_left_corner = origin - horizontal / 2.0 - vertical / 2.0 - w * focus_dist;
+
+        let lens_radius = aperture / 2.0;
+
+        Self {
+            origin,
+            lower_left_corner,
+            horizontal,
+            vertical,
+            u,
+            v,
+            lens_radius,
+            aperture_shape: ApertureShape::Circle,
+            shutter_time: if let Some(t) = shutter_time {
+                t
+            } else {
+                (0.0, 0.0)
+            },
+        }
+    }
+
+    pub fn with_aperture_shape(mut self, shape: ApertureShape) -> Self {
+        self.aperture_shape = shape;
+        self
+    }
+
+    fn degrees_to_radians(degrees: f64) -> f64 {
+        degrees * PI / 180.0
+    }
+}
+
+// A pinhole camera at the origin looking down -z, with a vertical field of
+// view of 90 degrees and a 16:9 image.
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            90.0,
+            16.0 / 9.0,
+            0.0,
+            1.0,
+            None,
+        )
+    }
+}
+
+impl RayGenerator for Camera {
+    fn get_ray(&self, s: f64, t: f64) -> Ray {
+        let rd = match self.aperture_shape {
+            ApertureShape::Circle => sampling::uniform_disk(),
+            ApertureShape::Polygon {
+                sides,
+                rotation_degrees,
+            } => sampling::uniform_polygon(sides, rotation_degrees),
+        } * self.lens_radius;
+        let offset = self.u * rd.x() + self.v * rd.y();
+
+        Ray::new(
+            self.origin + offset,
+            self.lower_left_corner + self.horizontal * s + self.vertical * t - self.origin - offset,
+            Some(random_float_between(
+                self.shutter_time.0,
+                self.shutter_time.1,
+            )),
+        )
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, rng};
+
+    fn assert_same_camera(a: &Camera, b: &Camera) {
+        for (a, b) in [
+            (a.origin, b.origin),
+            (a.lower_left_corner, b.lower_left_corner),
+            (a.horizontal, b.horizontal),
+            (a.vertical, b.vertical),
+            (a.u, b.u),
+            (a.v, b.v),
+        ] {
+            assert_vec3_approx_eq!(a, b, 1e-12);
+        }
+        assert!((a.lens_radius - b.lens_radius).abs() < 1e-12);
+        assert!((a.shutter_time.0 - b.shutter_time.0).abs() < 1e-12);
+        assert!((a.shutter_time.1 - b.shutter_time.1).abs() < 1e-12);
+        match (a.aperture_shape, b.aperture_shape) {
+            (ApertureShape::Circle, ApertureShape::Circle) => {}
+            (
+                ApertureShape::Polygon {
+                    sides,
+                    rotation_degrees,
+                },
+                ApertureShape::Polygon {
+                    sides: other_sides,
+                    rotation_degrees: other_rotation,
+                },
+            ) => {
+                assert_eq!(sides, other_sides);
+                assert!((rotation_degrees - other_rotation).abs() < 1e-12);
+            }
+            (a, b) => panic!("aperture {:?} came back as {:?}", a, b),
+        }
+    }
+
+    #[test]
+    fn cameras_round_trip_through_json() {
+        let cameras = [
+            Camera::default(),
+            Camera::new(
+                Point3::new(13.0, 2.0, 3.0),
+                Point3::new(0.0, 0.5, 0.0),
+                Vec3::new(0.1, 1.0, 0.0),
+                20.0,
+                3.0 / 2.0,
+                0.1,
+                10.0,
+                Some((0.25, 0.75)),
+            )
+            .with_aperture_shape(ApertureShape::Polygon {
+                sides: 6,
+                rotation_degrees: 15.0,
+            }),
+        ];
+
+        for camera in cameras {
+            let json = serde_json::to_string(&camera).unwrap();
+            let parsed: Camera = serde_json::from_str(&json).unwrap();
+            assert_same_camera(&parsed, &camera);
+
+            // And it takes the same pictures.
+            for seed in 0..10 {
+                rng::seed_thread(seed);
+                let a = camera.get_ray(0.3, 0.8);
+                rng::seed_thread(seed);
+                let b = parsed.get_ray(0.3, 0.8);
+                assert_vec3_approx_eq!(a.origin, b.origin, 1e-12);
+                assert_vec3_approx_eq!(a.direction, b.direction, 1e-12);
+                assert!((a.time - b.time).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn rays_and_vectors_round_trip_through_json() {
+        let ray = Ray::new(
+            Point3::new(1.0, -2.5, 3.0),
+            Vec3::new(0.0, 0.6, -0.8),
+            Some(0.4),
+        );
+        let json = serde_json::to_string(&ray).unwrap();
+        let parsed: Ray = serde_json::from_str(&json).unwrap();
+
+        assert_vec3_approx_eq!(parsed.origin, ray.origin, 1e-12);
+        assert_vec3_approx_eq!(parsed.direction, ray.direction, 1e-12);
+        assert_eq!((parsed.time, parsed.channel), (ray.time, ray.channel));
+        // Vectors are plain [x, y, z] arrays.
+        assert_eq!(
+            serde_json::to_string(&Vec3::new(1.0, 2.5, -3.0)).unwrap(),
+            "[1.0,2.5,-3.0]"
+        );
+    }
+}
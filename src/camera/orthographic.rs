@@ -0,0 +1,93 @@
+use crate::{random_float_between, ray::Ray, Point3, Vec3};
+
+use super::RayGenerator;
+
+// Parallel projection: every ray travels along the view direction and only
+// its origin moves across the image. The frustum bounds are measured in the
+// camera's frame, with `left`/`right` along u, `bottom`/`top` along v and
+// `near`/`far` as distances from lookfrom along the view direction. Rays
+// start on the near plane; `far` is kept for reference only, as the
+// integrator doesn't clip rays yet.
+pub struct OrthographicCamera {
+    pub left: f64,
+    pub right: f64,
+    pub bottom: f64,
+    pub top: f64,
+    pub near: f64,
+    pub far: f64,
+    origin: Point3,
+    w: Vec3,
+    u: Vec3,
+    v: Vec3,
+    shutter_time: (f64, f64),
+}
+
+impl OrthographicCamera {
+    pub fn new(
+        lookfrom: Point3,
+        lookat: Point3,
+        vup: Vec3,
+        (left, right, bottom, top): (f64, f64, f64, f64),
+        (near, far): (f64, f64),
+        shutter_time: Option<(f64, f64)>,
+    ) -> Self {
+        let w = (lookfrom - lookat).unit_vector();
+        let u = vup.cross(w).unit_vector();
+        let v = w.cross(u);
+
+        Self {
+            left,
+            right,
+            bottom,
+            top,
+            near,
+            far,
+            origin: lookfrom,
+            w,
+            u,
+            v,
+            shutter_time: shutter_time.unwrap_or((0.0, 0.0)),
+        }
+    }
+
+    // A view volume centered on the view axis, `height` units tall and
+    // `aspect_ratio` times as wide.
+    pub fn centered(
+        lookfrom: Point3,
+        lookat: Point3,
+        vup: Vec3,
+        height: f64,
+        aspect_ratio: f64,
+        shutter_time: Option<(f64, f64)>,
+    ) -> Self {
+        let half_height = height / 2.0;
+        let half_width = half_height * aspect_ratio;
+        let far = Vec3::distance(lookat, lookfrom) * 2.0;
+
+        Self::new(
+            lookfrom,
+            lookat,
+            vup,
+            (-half_width, half_width, -half_height, half_height),
+            (0.0, far),
+            shutter_time,
+        )
+    }
+}
+
+impl RayGenerator for OrthographicCamera {
+    fn get_ray(&self, s: f64, t: f64) -> Ray {
+        let x = self.left + (self.right - self.left) * s;
+        let y = self.bottom + (self.top - self.bottom) * t;
+        let origin = self.origin + self.u * x + self.v * y - self.w * self.near;
+
+        Ray::new(
+            origin,
+            -self.w,
+            Some(random_float_between(
+                self.shutter_time.0,
+                self.shutter_time.1,
+            )),
+        )
+    }
+}
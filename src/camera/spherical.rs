@@ -0,0 +1,171 @@
+use std::f64::consts::PI;
+
+use crate::{ray::Ray, texture::texture2d::Texture2D, Point3, Vec3};
+
+use super::RayGenerator;
+
+// A 360° panorama camera producing equirectangular images: s sweeps the
+// full circle of longitude and t the latitude from straight down (t = 0)
+// to straight up (t = 1), so the horizon lies at t = 0.5. The center of
+// the image looks along the horizontal projection of -z.
+pub struct SphericalCamera {
+    pub lookfrom: Point3,
+    pub up: Vec3,
+    // Size of the panorama; equirectangular images are twice as wide as
+    // they are tall.
+    pub width: u32,
+    pub height: u32,
+    forward: Vec3,
+    right: Vec3,
+}
+
+impl SphericalCamera {
+    pub fn new(lookfrom: Point3, up: Vec3, width: u32, height: u32) -> Self {
+        let up = up.unit_vector();
+        // Any direction not parallel to up works as a seed for the frame.
+        let seed = if up.z().abs() < 0.9 {
+            Vec3::new(0.0, 0.0, -1.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let forward = (seed - up * seed.dot(up)).unit_vector();
+        let right = forward.cross(up);
+
+        Self {
+            lookfrom,
+            up,
+            width,
+            height,
+            forward,
+            right,
+        }
+    }
+
+    pub fn direction(&self, s: f64, t: f64) -> Vec3 {
+        let phi = 2.0 * PI * (s - 0.5);
+        let theta = PI * (1.0 - t);
+        let horizontal = self.forward * phi.cos() + self.right * phi.sin();
+
+        self.up * theta.cos() + horizontal * theta.sin()
+    }
+
+    // Inverse of `direction`: the (s, t) image position a direction maps to.
+    pub fn image_coords(&self, direction: Vec3) -> (f64, f64) {
+        let d = direction.unit_vector();
+        let theta = d.dot(self.up).clamp(-1.0, 1.0).acos();
+        let phi = d.dot(self.right).atan2(d.dot(self.forward));
+
+        (phi / (2.0 * PI) + 0.5, 1.0 - theta / PI)
+    }
+
+    // Ray through the center of pixel (x, y) of the panorama, with y = 0 the
+    // top row.
+    pub fn pixel_ray(&self, x: u32, y: u32) -> Ray {
+        let s = (x as f64 + 0.5) / self.width as f64;
+        let t = 1.0 - (y as f64 + 0.5) / self.height as f64;
+        self.get_ray(s, t)
+    }
+
+    // Resamples an equirectangular render from this camera into six cube
+    // faces of `face_size` pixels, in the usual +x, -x, +y, -y, +z, -z
+    // order and orientation of cube map textures.
+    pub fn to_cubemap(&self, equirect: &Texture2D, face_size: u32) -> [Texture2D; 6] {
+        let faces: [fn(f64, f64) -> Vec3; 6] = [
+            |a, b| Vec3::new(1.0, -b, -a),
+            |a, b| Vec3::new(-1.0, -b, a),
+            |a, b| Vec3::new(a, 1.0, b),
+            |a, b| Vec3::new(a, -1.0, -b),
+            |a, b| Vec3::new(a, -b, 1.0),
+            |a, b| Vec3::new(-a, -b, -1.0),
+        ];
+
+        faces.map(|face_direction| {
+            let mut face = Texture2D::new(face_size, face_size);
+            for y in 0..face_size {
+                for x in 0..face_size {
+                    let a = 2.0 * (x as f64 + 0.5) / face_size as f64 - 1.0;
+                    let b = 2.0 * (y as f64 + 0.5) / face_size as f64 - 1.0;
+                    let (s, t) = self.image_coords(face_direction(a, b));
+                    face.set(x, y, equirect.sample(s, t));
+                }
+            }
+            face
+        })
+    }
+}
+
+impl RayGenerator for SphericalCamera {
+    fn get_ray(&self, s: f64, t: f64) -> Ray {
+        Ray::new(self.lookfrom, self.direction(s, t), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_vec3_approx_eq,
+        hittable::{plane::Plane, Hittable},
+        material::lambertian::Lambertian,
+        Color,
+    };
+
+    fn camera() -> SphericalCamera {
+        SphericalCamera::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 64, 32)
+    }
+
+    #[test]
+    fn the_horizon_is_a_straight_line_halfway_up() {
+        let camera = camera();
+        let ground = Plane::new(
+            Point3::from(0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Color::from(0.5)),
+        );
+
+        // Every pixel of the bottom half sees the ground, and none of the
+        // top half does.
+        for y in 0..camera.height {
+            for x in 0..camera.width {
+                let hit = ground.hit(&camera.pixel_ray(x, y), 0.001, f64::INFINITY);
+                assert_eq!(hit.is_some(), y >= camera.height / 2, "{x} {y}");
+            }
+        }
+        for s in [0.0, 0.25, 0.6, 1.0] {
+            assert!(camera.direction(s, 0.5).y().abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn image_coords_undo_direction() {
+        let camera = camera();
+        assert_vec3_approx_eq!(camera.direction(0.5, 0.5), Vec3::new(0.0, 0.0, -1.0), 1e-12);
+        assert_vec3_approx_eq!(camera.direction(0.3, 1.0), Vec3::new(0.0, 1.0, 0.0), 1e-12);
+
+        for (s, t) in [(0.5, 0.5), (0.1, 0.2), (0.9, 0.7), (0.75, 0.1)] {
+            let (s2, t2) = camera.image_coords(camera.direction(s, t));
+            assert!((s - s2).abs() < 1e-12 && (t - t2).abs() < 1e-12, "{s} {t}");
+        }
+    }
+
+    #[test]
+    fn cube_faces_show_their_part_of_the_panorama() {
+        // The sky is red and the ground blue.
+        let (sky, ground) = (Color::new(1.0, 0.0, 0.0), Color::new(0.0, 0.0, 1.0));
+        let mut equirect = Texture2D::new(64, 32);
+        for y in 0..32 {
+            for x in 0..64 {
+                equirect.set(x, y, if y < 16 { sky } else { ground });
+            }
+        }
+
+        let [px, _, py, ny, _, nz] = camera().to_cubemap(&equirect, 8);
+        assert!(py.pixels.iter().all(|&c| c == sky));
+        assert!(ny.pixels.iter().all(|&c| c == ground));
+        // The side faces have the horizon across their middle.
+        for face in [px, nz] {
+            assert_eq!(face.get(3, 0), sky);
+            assert_eq!(face.get(3, 7), ground);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+// Saves the tiles of a render as they finish, so an interrupted render can
+// pick up where it stopped with --resume.
+//
+// The file is a header naming the seed and the settings of the render,
+// followed by one record per finished tile with its index and pixels, all
+// encoded with bincode. Records are read with the file's size as their
+// limit, so a damaged length can't make loading allocate more than the
+// file holds, and tiles that don't fit the image aren't resumed.
+//
+// Records are appended and flushed one at a time, so a render killed while
+// writing one leaves a truncated record at the end, which loading ignores.
+// Checkpoints need the serde feature.
+
+#[cfg(feature = "serde")]
+use std::io::BufReader;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+#[cfg(feature = "serde")]
+use bincode::Options;
+use tracy::render::{tiles, TILE_SIZE};
+
+use crate::Pixel;
+
+#[cfg(feature = "serde")]
+const MAGIC: [u8; 8] = *b"TRACYCKP";
+#[cfg(feature = "serde")]
+const VERSION: u32 = 2;
+
+// The finished tiles of an interrupted render.
+pub struct Checkpoint {
+    pub seed: u64,
+    // Description of everything that changes the image besides the seed.
+    // Tiles of a render with other settings can't be reused.
+    pub settings: String,
+    // Index of each tile in the render's tile list, and its pixels.
+    pub tiles: Vec<(u32, Vec<Pixel>)>,
+}
+
+impl Checkpoint {
+    // Whether every saved tile is one of the tiles of a width x height
+    // image, with exactly the pixels that belong to it.
+    pub fn fits(&self, width: u32, height: u32) -> bool {
+        let image_tiles = tiles(width, height, TILE_SIZE);
+        self.tiles.iter().all(|(index, pixels)| {
+            let Some(tile) = image_tiles.get(*index as usize) else {
+                return false;
+            };
+            // Pixel rows are counted from the top, tile rows from the
+            // bottom.
+            let top = height - tile.y - tile.height;
+            pixels.len() == (tile.width * tile.height) as usize
+                && pixels.iter().all(|pixel| {
+                    (tile.x..tile.x + tile.width).contains(&pixel.x)
+                        && (top..top + tile.height).contains(&pixel.y)
+                })
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Header {
+    magic: [u8; 8],
+    version: u32,
+    seed: u64,
+    settings: String,
+}
+
+// Fixed size integers, and no record longer than `limit` bytes.
+#[cfg(feature = "serde")]
+fn encoding(limit: u64) -> impl Options + Copy {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_limit(limit)
+}
+
+#[cfg(feature = "serde")]
+fn to_io_error(err: bincode::ErrorKind) -> io::Error {
+    match err {
+        bincode::ErrorKind::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::InvalidData, err),
+    }
+}
+
+// 64 bit FNV-1a hash of `bytes`, for telling scene files apart in the
+// settings. Unlike std's DefaultHasher, it stays the same across Rust
+// versions, so a new toolchain doesn't throw checkpoints away.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+pub struct CheckpointWriter {
+    out: BufWriter<File>,
+}
+
+impl CheckpointWriter {
+    // Creates the file at `path`, replacing any previous one.
+    #[cfg(feature = "serde")]
+    pub fn create(path: &Path, seed: u64, settings: &str) -> io::Result<Self> {
+        let mut out = BufWriter::new(File::create(path)?);
+        let header = Header {
+            magic: MAGIC,
+            version: VERSION,
+            seed,
+            settings: settings.to_string(),
+        };
+        encoding(u64::MAX)
+            .serialize_into(&mut out, &header)
+            .map_err(|err| to_io_error(*err))?;
+        out.flush()?;
+
+        Ok(Self { out })
+    }
+
+    #[cfg(not(feature = "serde"))]
+    pub fn create(_path: &Path, _seed: u64, _settings: &str) -> io::Result<Self> {
+        Err(unsupported())
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn write_tile(&mut self, index: u32, pixels: &[Pixel]) -> io::Result<()> {
+        encoding(u64::MAX)
+            .serialize_into(&mut self.out, &(index, pixels))
+            .map_err(|err| to_io_error(*err))?;
+        self.out.flush()
+    }
+
+    #[cfg(not(feature = "serde"))]
+    pub fn write_tile(&mut self, _index: u32, _pixels: &[Pixel]) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+#[cfg(feature = "serde")]
+pub fn load_checkpoint(path: &Path) -> io::Result<Checkpoint> {
+    let file = File::open(path)?;
+    let encoding = encoding(file.metadata()?.len());
+    let mut input = BufReader::new(file);
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let header: Header = encoding
+        .deserialize_from(&mut input)
+        .map_err(|_| invalid("not a Tracy checkpoint"))?;
+    if header.magic != MAGIC {
+        return Err(invalid("not a Tracy checkpoint"));
+    }
+    if header.version != VERSION {
+        return Err(invalid("checkpoint written by another version of Tracy"));
+    }
+
+    let mut tiles = Vec::new();
+    loop {
+        match encoding
+            .deserialize_from(&mut input)
+            .map_err(|err| to_io_error(*err))
+        {
+            Ok(tile) => tiles.push(tile),
+            // The end of the file, or a record cut short by an interrupt.
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(Checkpoint {
+        seed: header.seed,
+        settings: header.settings,
+        tiles,
+    })
+}
+
+#[cfg(not(feature = "serde"))]
+pub fn load_checkpoint(_path: &Path) -> io::Result<Checkpoint> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "serde"))]
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "checkpoints need the serde feature",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracy::{Color, Vec3};
+
+    // The pixels of the tile at `index` of a width x height image.
+    fn tile_pixels(width: u32, height: u32, index: usize) -> Vec<Pixel> {
+        let tile = tiles(width, height, TILE_SIZE)[index];
+        let top = height - tile.y - tile.height;
+        (top..top + tile.height)
+            .flat_map(|y| (tile.x..tile.x + tile.width).map(move |x| (x, y)))
+            .map(|(x, y)| Pixel {
+                x,
+                y,
+                color: Color::from(0.5),
+                sample_count: 1,
+                depth: 1.0,
+                normal: Vec3::new(0.0, 1.0, 0.0),
+            })
+            .collect()
+    }
+
+    fn checkpoint(tiles: Vec<(u32, Vec<Pixel>)>) -> Checkpoint {
+        Checkpoint {
+            seed: 1,
+            settings: String::new(),
+            tiles,
+        }
+    }
+
+    #[test]
+    fn fnv1a_matches_the_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn only_tiles_of_the_image_fit() {
+        // Three tiles wide and two high, with cropped ones on the right
+        // and at the top.
+        let (width, height) = (150, 100);
+        let whole = checkpoint(vec![
+            (0, tile_pixels(width, height, 0)),
+            (5, tile_pixels(width, height, 5)),
+        ]);
+        assert!(whole.fits(width, height));
+        assert!(!whole.fits(width, 2 * height));
+
+        assert!(!checkpoint(vec![(6, tile_pixels(width, height, 5))]).fits(width, height));
+        assert!(!checkpoint(vec![(4, tile_pixels(width, height, 5))]).fits(width, height));
+
+        let mut short = tile_pixels(width, height, 1);
+        short.pop();
+        assert!(!checkpoint(vec![(1, short)]).fits(width, height));
+    }
+}
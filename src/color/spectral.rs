@@ -0,0 +1,261 @@
+use std::{ops::Range, sync::OnceLock};
+
+use crate::{
+    background::Background,
+    hittable::{light_list::LightList, Hittable},
+    pdf::{cosine::CosinePdf, hittable::HittablePdf, mixture::MixturePdf},
+    random_float,
+    ray::{lobe_normal, sample_light, Ray},
+    stats::RENDER_STATS,
+    Color, Vec3,
+};
+
+// Number of bins `SpectralColor` stores by default.
+pub const N_WAVELENGTHS: usize = 8;
+
+// Visible wavelengths in nanometers that spectra cover and paths sample.
+pub const WAVELENGTHS: Range<f64> = 380.0..720.0;
+
+// Paths traced per camera ray, at evenly spaced wavelengths. A single
+// wavelength per camera ray shows up as colored noise; spreading a few
+// over the spectrum averages most of it out within the sample.
+const PATHS_PER_RAY: usize = 3;
+
+// A spectrum sampled in N equal bins over `WAVELENGTHS`, with values
+// linearly interpolated between the bin centers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectralColor<const N: usize = N_WAVELENGTHS>(pub [f64; N]);
+
+impl<const N: usize> SpectralColor<N> {
+    // Center of bin `i` in nanometers.
+    pub fn wavelength(i: usize) -> f64 {
+        let width = (WAVELENGTHS.end - WAVELENGTHS.start) / N as f64;
+        WAVELENGTHS.start + (i as f64 + 0.5) * width
+    }
+
+    // A smooth spectrum with roughly the given RGB color: short wavelengths
+    // take the blue, the middle the green and long the red, blending into
+    // each other around 490 and 590 nm. The three parts add up to 1, so
+    // white becomes a flat spectrum. Other colors only survive the trip
+    // back to RGB approximately, as many spectra share an RGB color.
+    pub fn from_rgb(rgb: Color) -> Self {
+        let mut bins = [0.0; N];
+        for (i, bin) in bins.iter_mut().enumerate() {
+            let lambda = Self::wavelength(i);
+            let blue = 1.0 - smoothstep(470.0, 510.0, lambda);
+            let red = smoothstep(570.0, 610.0, lambda);
+            let green = 1.0 - blue - red;
+            *bin = red * rgb.x() + green * rgb.y() + blue * rgb.z();
+        }
+        Self(bins)
+    }
+
+    // Value at `lambda` nanometers. Outside the bin centers the nearest bin
+    // is used.
+    pub fn at(&self, lambda: f64) -> f64 {
+        let width = (WAVELENGTHS.end - WAVELENGTHS.start) / N as f64;
+        let x = ((lambda - WAVELENGTHS.start) / width - 0.5).clamp(0.0, (N - 1) as f64);
+        let i = (x as usize).min(N.saturating_sub(2));
+        let t = x - i as f64;
+        match self.0.get(i + 1) {
+            Some(next) => self.0[i] * (1.0 - t) + next * t,
+            None => self.0[i],
+        }
+    }
+
+    // CIE XYZ of the spectrum, scaled so a flat spectrum of 1 has Y = 1.
+    pub fn to_xyz(&self) -> Vec3 {
+        // Integrated at 1 nm steps rather than over the bins, which are too
+        // coarse for the narrow lobes of the matching functions.
+        let steps = (WAVELENGTHS.end - WAVELENGTHS.start) as usize;
+        let sum: Vec3 = (0..steps)
+            .map(|i| {
+                let lambda = WAVELENGTHS.start + i as f64 + 0.5;
+                cie_xyz(lambda) * self.at(lambda)
+            })
+            .sum();
+        sum / y_integral()
+    }
+
+    // Linear sRGB of the spectrum, white balanced so a flat spectrum is
+    // white.
+    pub fn to_rgb(&self) -> Color {
+        xyz_to_rgb(self.to_xyz())
+    }
+}
+
+fn smoothstep(edge0: f64, edge1: f64, x: f64) -> f64 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+// The CIE 1931 2° color matching functions at `lambda` nanometers, from
+// Wyman, Sloan and Shirley's multi-lobe Gaussian fit of the tabulated
+// data, which is within a few percent of the tables everywhere.
+pub fn cie_xyz(lambda: f64) -> Vec3 {
+    // Gaussian with different widths left and right of its peak.
+    let g = |mu: f64, sigma_left: f64, sigma_right: f64| {
+        let sigma = if lambda < mu { sigma_left } else { sigma_right };
+        let t = (lambda - mu) / sigma;
+        f64::exp(-0.5 * t * t)
+    };
+
+    Vec3::new(
+        1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2),
+        0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1),
+        1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8),
+    )
+}
+
+// Integral of the Y matching function over `WAVELENGTHS`.
+fn y_integral() -> f64 {
+    static INTEGRAL: OnceLock<f64> = OnceLock::new();
+    *INTEGRAL.get_or_init(|| {
+        let steps = (WAVELENGTHS.end - WAVELENGTHS.start) as usize;
+        (0..steps)
+            .map(|i| cie_xyz(WAVELENGTHS.start + i as f64 + 0.5).y())
+            .sum()
+    })
+}
+
+// XYZ to linear sRGB, divided by the RGB of a flat spectrum. sRGB's white
+// is D65, so without that a flat spectrum would come out slightly pink.
+fn xyz_to_rgb(xyz: Vec3) -> Color {
+    fn to_srgb(xyz: Vec3) -> Color {
+        let [x, y, z] = <[f64; 3]>::from(xyz);
+        Color::new(
+            3.2406 * x - 1.5372 * y - 0.4986 * z,
+            -0.9689 * x + 1.8758 * y + 0.0415 * z,
+            0.0557 * x - 0.2040 * y + 1.0570 * z,
+        )
+    }
+
+    static WHITE: OnceLock<Color> = OnceLock::new();
+    let white = *WHITE
+        .get_or_init(|| to_srgb(SpectralColor::<N_WAVELENGTHS>([1.0; N_WAVELENGTHS]).to_xyz()));
+    let rgb = to_srgb(xyz);
+    Color::new(
+        rgb.x() / white.x(),
+        rgb.y() / white.y(),
+        rgb.z() / white.z(),
+    )
+}
+
+// A ray carrying light of a single wavelength in nanometers, which stays
+// the same along the whole path. Materials see it through
+// `Material::scatter_spectral`, which lets glass bend each wavelength
+// differently; the RGB colors of everything else are turned into spectra
+// with `SpectralColor::from_rgb` and read at the wavelength.
+pub struct SpectralRay {
+    pub ray: Ray,
+    pub wavelength: f64,
+}
+
+impl SpectralRay {
+    pub fn new(ray: Ray, wavelength: f64) -> Self {
+        Self { ray, wavelength }
+    }
+
+    // Radiance arriving along the ray at its wavelength. The same path
+    // tracer as RGB rendering, with next-event estimation, the balance
+    // heuristic and Russian roulette, only with a single channel.
+    pub fn radiance(
+        &self,
+        world: &dyn Hittable,
+        lights: &LightList,
+        background: &dyn Background,
+        depth: i32,
+    ) -> f64 {
+        const MIN_BOUNCES: i32 = 3;
+        let at = |color: Color| SpectralColor::<N_WAVELENGTHS>::from_rgb(color).at(self.wavelength);
+
+        let mut ray = Ray::new(self.ray.origin, self.ray.direction, Some(self.ray.time));
+        let mut radiance = 0.0;
+        let mut throughput = 1.0;
+        let (mut rays_cast, mut hits, mut bounces, mut shadow_rays) = (0, 0, 0, 0);
+        let mut last_bounce: Option<MixturePdf<CosinePdf, HittablePdf>> = None;
+
+        for bounce in 0..depth {
+            rays_cast += 1;
+            let weight = match &last_bounce {
+                Some(mixture) => mixture.share_of_p0(ray.direction),
+                None => 1.0,
+            };
+            let Some(hit) = world.hit(&ray, 0.001, f64::MAX) else {
+                radiance += throughput
+                    * at(background.sample(ray.direction)
+                        + lights.distant_radiance(ray.direction) * weight);
+                break;
+            };
+            hits += 1;
+
+            radiance += throughput * at(hit.material.emitted(hit.u, hit.v, hit.p)) * weight;
+
+            let Some(scatter) = hit.material.scatter_spectral(&ray, &hit, self.wavelength) else {
+                break;
+            };
+            bounces += 1;
+            let material_pdf = hit.material.scattering_pdf(&ray, &hit, &scatter.ray);
+            let attenuation = at(scatter.attenuation);
+
+            last_bounce = None;
+            if !scatter.is_specular && !lights.is_empty() && material_pdf > 0.0 {
+                let mixture = MixturePdf::new(
+                    CosinePdf::new(lobe_normal(&hit, &scatter.ray)),
+                    HittablePdf::new(lights, hit.p),
+                    0.5,
+                );
+                rays_cast += 1;
+                shadow_rays += 1;
+                radiance +=
+                    throughput * attenuation * at(sample_light(world, &ray, &hit, &mixture));
+                last_bounce = Some(mixture);
+            }
+
+            throughput *= match scatter.pdf {
+                None => attenuation,
+                Some(pdf) if pdf > 0.0 => attenuation * (material_pdf / pdf),
+                Some(_) => break,
+            };
+
+            if bounce >= MIN_BOUNCES {
+                let survival = throughput.min(1.0);
+                if random_float() >= survival {
+                    break;
+                }
+                throughput /= survival;
+            }
+
+            ray = scatter.ray;
+        }
+
+        RENDER_STATS.record_path(rays_cast, hits, bounces, shadow_rays);
+        radiance
+    }
+}
+
+// Linear sRGB arriving along `ray`, from PATHS_PER_RAY paths at stratified
+// random wavelengths. Each path's radiance is weighted by the matching
+// functions at its wavelength over the density it was picked with.
+pub fn spectral_color(
+    ray: &Ray,
+    world: &dyn Hittable,
+    lights: &LightList,
+    background: &dyn Background,
+    depth: i32,
+) -> Color {
+    let range = WAVELENGTHS.end - WAVELENGTHS.start;
+    let offset = random_float();
+
+    let xyz: Vec3 = (0..PATHS_PER_RAY)
+        .map(|i| {
+            let lambda = WAVELENGTHS.start + range * (i as f64 + offset) / PATHS_PER_RAY as f64;
+            let path =
+                SpectralRay::new(Ray::new(ray.origin, ray.direction, Some(ray.time)), lambda);
+            cie_xyz(lambda) * path.radiance(world, lights, background, depth)
+        })
+        .sum();
+
+    // Each wavelength had density PATHS_PER_RAY / range of being picked.
+    xyz_to_rgb(xyz * (range / PATHS_PER_RAY as f64 / y_integral()))
+}
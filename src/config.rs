@@ -0,0 +1,62 @@
+use std::fmt;
+
+// Settings that control the size and quality of a render.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderConfig {
+    pub width: u32,
+    pub height: u32,
+    // Samples per pixel.
+    pub samples: u32,
+    // Maximum number of bounces per path.
+    pub max_depth: u32,
+}
+
+impl RenderConfig {
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+
+    // Checks that the settings make for an image. Pixel positions are
+    // spread over width - 1 and height - 1 steps, so both need at least
+    // two pixels.
+    pub fn validate(&self) -> Result<(), InvalidConfigError> {
+        let error = |message: String| Err(InvalidConfigError(message));
+        if self.width < 2 || self.height < 2 {
+            return error(format!(
+                "a {}x{} image is too small, width and height need to be at least 2",
+                self.width, self.height
+            ));
+        }
+        if self.samples < 1 {
+            return error("samples need to be at least 1".to_string());
+        }
+        if self.max_depth < 1 {
+            return error("max depth needs to be at least 1".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidConfigError(String);
+
+impl fmt::Display for InvalidConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid render settings: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidConfigError {}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            width: 240,
+            height: 160,
+            samples: 100,
+            max_depth: 50,
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod photon_map;
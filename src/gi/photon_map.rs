@@ -0,0 +1,272 @@
+use std::f64::consts::PI;
+
+use crate::{
+    background::Background,
+    hittable::{light_list::LightList, HitRecord, Hittable},
+    math::{
+        kdtree::{HasPosition, KdTree},
+        onb::Onb,
+    },
+    pdf::{hittable::HittablePdf, Pdf},
+    random_float,
+    ray::Ray,
+    rng, sampling,
+    stats::RENDER_STATS,
+    Color, Point3, Vec3,
+};
+
+// Random stream photons are traced with, apart from the streams of pixels.
+const PHOTON_STREAM: u64 = 1 << 63;
+
+// Bounces after which photons are subject to Russian roulette.
+const MIN_BOUNCES: u32 = 3;
+
+// Light arriving at a diffuse surface: where, from which way (the direction
+// the photon travelled) and how much of the light's flux it carries.
+#[derive(Debug, Clone, Copy)]
+pub struct Photon {
+    pub position: Point3,
+    pub direction: Vec3,
+    pub power: Color,
+}
+
+impl HasPosition for Photon {
+    fn position(&self) -> Point3 {
+        self.position
+    }
+}
+
+// Two-pass global illumination. `build` follows photons from the lights
+// through the scene and keeps those that land on diffuse surfaces after at
+// least one bounce; `radiance` then traces camera rays up to the first
+// diffuse surface, lights it directly with a shadow ray and estimates the
+// rest from the density of the photons gathered around the hit.
+//
+// Caustics, light focused by glass or mirrors onto a diffuse surface, are
+// paths a path tracer rarely finds, but photons follow them naturally, so
+// they come out sharp after a few hundred thousand photons. The price is
+// bias: the estimate is blurred over the gathering radius.
+//
+// Only lights in the `LightList` emit photons, and only shapes implementing
+// `Hittable::sample_surface` can. Light from the background isn't stored in
+// the map, but still reaches surfaces through the shadow rays.
+#[derive(Debug, Clone, Default)]
+pub struct PhotonMap {
+    pub kd_tree: KdTree<Photon>,
+    // Photons gathered per estimate, and the furthest they may be.
+    pub gather_count: usize,
+    pub max_radius: f64,
+}
+
+impl PhotonMap {
+    // Emits `count` photons, split evenly between the lights, and follows
+    // each for up to `max_depth` bounces.
+    pub fn build(world: &dyn Hittable, lights: &LightList, count: usize, max_depth: u32) -> Self {
+        rng::seed_stream(PHOTON_STREAM);
+
+        let mut photons = Vec::new();
+        if !lights.is_empty() && count > 0 {
+            let per_light = count.div_ceil(lights.lights.len());
+            for light in &lights.lights {
+                for _ in 0..per_light {
+                    trace_photon(world, light.as_ref(), per_light, max_depth, &mut photons);
+                }
+            }
+        }
+
+        Self {
+            kd_tree: KdTree::build(photons),
+            gather_count: 50,
+            max_radius: f64::INFINITY,
+        }
+    }
+
+    pub fn with_gather(self, gather_count: usize, max_radius: f64) -> Self {
+        Self {
+            gather_count,
+            max_radius,
+            ..self
+        }
+    }
+
+    pub fn photons(&self) -> &[Photon] {
+        self.kd_tree.items()
+    }
+
+    // Light arriving along `ray`, following mirrors and glass for up to
+    // `depth` bounces until it reaches a diffuse surface.
+    pub fn radiance(
+        &self,
+        ray: &Ray,
+        world: &dyn Hittable,
+        lights: &LightList,
+        background: &dyn Background,
+        depth: i32,
+    ) -> Color {
+        let mut ray = Ray::new(ray.origin, ray.direction, Some(ray.time));
+        let mut radiance = Color::new(0.0, 0.0, 0.0);
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let (mut rays_cast, mut hits, mut bounces, mut shadow_rays) = (0, 0, 0, 0);
+
+        for _ in 0..depth {
+            rays_cast += 1;
+            // Camera rays and specular bounces get here, neither of which
+            // sampled the lights.
+            let Some(hit) = world.hit(&ray, 0.001, f64::MAX) else {
+                radiance += throughput
+                    * (background.sample(ray.direction) + lights.distant_radiance(ray.direction));
+                break;
+            };
+            hits += 1;
+            radiance += throughput * hit.material.emitted(hit.u, hit.v, hit.p);
+
+            let Some(scatter) = hit.material.scatter(&ray, &hit) else {
+                break;
+            };
+            bounces += 1;
+            if scatter.is_specular {
+                throughput = throughput * scatter.attenuation;
+                ray = ray.continued_by(scatter.ray);
+                continue;
+            }
+
+            if !lights.is_empty() {
+                rays_cast += 1;
+                shadow_rays += 1;
+                radiance +=
+                    throughput * scatter.attenuation * direct_light(world, lights, &ray, &hit);
+            }
+            radiance += throughput * scatter.attenuation * self.estimate(&ray, &hit);
+            break;
+        }
+
+        RENDER_STATS.record_path(rays_cast, hits, bounces, shadow_rays);
+        radiance
+    }
+
+    // Light reflected by the photons around `hit` towards the camera, over
+    // the material's attenuation. The flux of the photons in a disc around
+    // the hit, divided by the disc's area, is the irradiance.
+    fn estimate(&self, ray_in: &Ray, hit: &HitRecord) -> Color {
+        let found = self
+            .kd_tree
+            .k_nearest_within(hit.p, self.gather_count, self.max_radius);
+        let Some(&(farthest, _)) = found.last() else {
+            return Color::new(0.0, 0.0, 0.0);
+        };
+        let radius_squared = if found.len() == self.gather_count {
+            farthest
+        } else {
+            self.max_radius * self.max_radius
+        };
+        if !radius_squared.is_finite() || radius_squared <= 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+
+        let flux: Color = found
+            .iter()
+            .filter_map(|&(_, photon)| {
+                // The BRDF over the attenuation is scattering_pdf / cos.
+                let towards_light = -photon.direction;
+                let cosine = towards_light.dot(hit.normal);
+                if cosine <= 0.0 {
+                    return None;
+                }
+                let scattered = Ray::new(hit.p, towards_light, Some(ray_in.time));
+                let pdf = hit.material.scattering_pdf(ray_in, hit, &scattered);
+                Some(photon.power * (pdf / cosine))
+            })
+            .sum();
+
+        flux / (PI * radius_squared)
+    }
+}
+
+// One shadow ray towards a random point on the lights, over the material's
+// attenuation: brdf * cos * emitted / light_pdf, with brdf * cos being the
+// attenuation times scattering_pdf.
+fn direct_light(world: &dyn Hittable, lights: &LightList, ray_in: &Ray, hit: &HitRecord) -> Color {
+    let black = Color::new(0.0, 0.0, 0.0);
+
+    let pdf = HittablePdf::new(lights, hit.p);
+    let direction = pdf.generate();
+    let light_pdf = pdf.value(direction);
+    let shadow_ray = Ray::new(hit.p, direction, Some(ray_in.time));
+    let scattering_pdf = hit.material.scattering_pdf(ray_in, hit, &shadow_ray);
+    if light_pdf <= 0.0 || scattering_pdf <= 0.0 {
+        return black;
+    }
+
+    let emitted = match world.hit(&shadow_ray, 0.001, f64::MAX) {
+        Some(light_hit) => light_hit
+            .material
+            .emitted(light_hit.u, light_hit.v, light_hit.p),
+        None => lights.distant_radiance(direction),
+    };
+    emitted * (scattering_pdf / light_pdf)
+}
+
+// Emits one of `count` photons from a random point on `light` and follows
+// it, storing it at every diffuse surface after the first bounce. Light
+// reaching a surface straight from a light is left to the shadow rays.
+fn trace_photon(
+    world: &dyn Hittable,
+    light: &dyn Hittable,
+    count: usize,
+    max_depth: u32,
+    photons: &mut Vec<Photon>,
+) {
+    let Some((surface, area)) = light.sample_surface() else {
+        return;
+    };
+
+    // Diffuse emission leaves each side of the surface with flux
+    // pi * L * area, in cosine-distributed directions. Directions into
+    // closed lights end on their own inside.
+    let side = if random_float() < 0.5 { 1.0 } else { -1.0 };
+    let normal = surface.normal * side;
+    let emitted = surface.material.emitted(surface.u, surface.v, surface.p);
+    let mut power = emitted * (2.0 * PI * area / count as f64);
+    let direction = Onb::build_from_w(normal).local(sampling::cosine_hemisphere());
+    let mut ray = Ray::new(surface.p, direction, None);
+
+    for bounce in 0..max_depth {
+        let Some(hit) = world.hit(&ray, 0.001, f64::MAX) else {
+            return;
+        };
+        let Some(scatter) = hit.material.scatter(&ray, &hit) else {
+            return;
+        };
+
+        if !scatter.is_specular && bounce > 0 {
+            photons.push(Photon {
+                position: hit.p,
+                direction: ray.direction.unit_vector(),
+                power,
+            });
+        }
+
+        let attenuation = match scatter.pdf {
+            None => scatter.attenuation,
+            Some(pdf) if pdf > 0.0 => {
+                scatter.attenuation * (hit.material.scattering_pdf(&ray, &hit, &scatter.ray) / pdf)
+            }
+            Some(_) => return,
+        };
+        power = power * attenuation;
+
+        if bounce >= MIN_BOUNCES {
+            let survival = attenuation
+                .x()
+                .max(attenuation.y())
+                .max(attenuation.z())
+                .min(1.0);
+            if random_float() >= survival {
+                return;
+            }
+            power /= survival;
+        }
+
+        ray = ray.continued_by(scatter.ray);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::{aabb::Aabb, material::Material, ray::Ray, Point3, Vec3};
+
+use super::{unit_axis, HitRecord, Hittable};
+
+pub struct AaBox<M: Material> {
+    pub p_min: Point3,
+    pub p_max: Point3,
+    pub material: M,
+}
+
+impl<M: Material> AaBox<M> {
+    // The corners may be given in any order.
+    pub fn new(p0: Point3, p1: Point3, material: M) -> Self {
+        let bbox = Aabb::new(p0, p1);
+        Self {
+            p_min: bbox.min,
+            p_max: bbox.max,
+            material,
+        }
+    }
+}
+
+impl<M: Material> Hittable for AaBox<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Slab intersection, remembering which axis the ray enters and
+        // leaves through so we know the face that was hit.
+        let mut t_enter = f64::NEG_INFINITY;
+        let mut t_exit = f64::INFINITY;
+        let mut enter_axis = 0;
+        let mut exit_axis = 0;
+
+        for a in 0..3 {
+            if ray.direction[a] == 0.0 {
+                if ray.origin[a] < self.p_min[a] || ray.origin[a] > self.p_max[a] {
+                    return None;
+                }
+                continue;
+            }
+
+            let inv_d = 1.0 / ray.direction[a];
+            let mut t0 = (self.p_min[a] - ray.origin[a]) * inv_d;
+            let mut t1 = (self.p_max[a] - ray.origin[a]) * inv_d;
+            if inv_d < 0.0 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            if t0 > t_enter {
+                t_enter = t0;
+                enter_axis = a;
+            }
+            if t1 < t_exit {
+                t_exit = t1;
+                exit_axis = a;
+            }
+        }
+
+        if t_enter > t_exit {
+            return None;
+        }
+
+        // Take the entry point if it's in range, otherwise the exit point
+        // (the ray starts inside the box).
+        let (t, axis, outward_sign) = if t_min <= t_enter && t_enter <= t_max {
+            (t_enter, enter_axis, -ray.direction[enter_axis].signum())
+        } else if t_min <= t_exit && t_exit <= t_max {
+            (t_exit, exit_axis, ray.direction[exit_axis].signum())
+        } else {
+            return None;
+        };
+
+        let p = ray.at(t);
+        let mut outward_normal = Vec3::new(0.0, 0.0, 0.0);
+        outward_normal[axis] = outward_sign;
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        // UV spans the face along the two remaining axes.
+        let b = (axis + 1) % 3;
+        let c = (axis + 2) % 3;
+        let u = (p[b] - self.p_min[b]) / (self.p_max[b] - self.p_min[b]);
+        let v = (p[c] - self.p_min[c]) / (self.p_max[c] - self.p_min[c]);
+
+        Some(HitRecord {
+            t,
+            p,
+            material: &self.material,
+            normal,
+            u,
+            v,
+            tangent: unit_axis(b),
+            bitangent: unit_axis(c),
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(Aabb::new(self.p_min, self.p_max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, Color};
+
+    fn unit_box() -> AaBox<Lambertian> {
+        AaBox::new(
+            Point3::new(1.0, 1.0, 1.0),
+            Point3::new(-1.0, -1.0, -1.0),
+            Lambertian::new(Color::from(0.5)),
+        )
+    }
+
+    #[test]
+    fn rays_hit_the_face_they_enter_through() {
+        let aabox = unit_box();
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut origin = Point3::new(0.2, -0.3, 0.1);
+                origin[axis] = 3.0 * sign;
+                let mut direction = Vec3::new(0.0, 0.0, 0.0);
+                direction[axis] = -sign;
+
+                let hit = aabox.hit(&Ray::new(origin, direction, None), 0.001, f64::INFINITY);
+                let hit = hit.unwrap();
+                assert_eq!(hit.t, 2.0);
+                assert_eq!(hit.normal, -direction);
+                assert!(hit.front_face);
+            }
+        }
+    }
+
+    #[test]
+    fn rays_from_inside_hit_the_far_face() {
+        let aabox = unit_box();
+        let ray = Ray::new(Point3::new(0.0, 0.5, 0.0), Vec3::new(0.0, 1.0, 0.0), None);
+        let hit = aabox.hit(&ray, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(hit.t, 0.5);
+        assert_eq!(hit.normal, Vec3::new(0.0, -1.0, 0.0));
+        assert!(!hit.front_face);
+    }
+
+    #[test]
+    fn rays_past_the_box_miss() {
+        let aabox = unit_box();
+        let beside = Ray::new(Point3::new(1.5, 0.0, 3.0), Vec3::new(0.0, 0.0, -1.0), None);
+        assert!(aabox.hit(&beside, 0.001, f64::INFINITY).is_none());
+        let away = Ray::new(Point3::new(0.0, 0.0, 3.0), Vec3::new(0.0, 0.0, 1.0), None);
+        assert!(aabox.hit(&away, 0.001, f64::INFINITY).is_none());
+        let diagonal = Ray::new(Point3::new(0.0, 3.0, 0.0), Vec3::new(1.0, -1.0, 0.0), None);
+        assert!(aabox.hit(&diagonal, 0.001, f64::INFINITY).is_none());
+    }
+}
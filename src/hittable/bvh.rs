@@ -0,0 +1,461 @@
+use std::{cmp::Ordering, sync::Arc};
+
+use crate::{
+    aabb::{surrounding_box, Aabb},
+    ray::Ray,
+};
+
+use super::{HitRecord, Hittable, HittableList};
+
+pub struct BvhNode {
+    left: Child,
+    // None for leaves, which hold a single object or, with the surface
+    // area heuristic, a list of them.
+    right: Option<Child>,
+    bbox: Aabb,
+}
+
+// Single objects are stored directly instead of being wrapped in a leaf.
+enum Child {
+    Node(Box<BvhNode>),
+    Object(Aabb, Arc<dyn Hittable>),
+}
+
+impl Child {
+    fn bbox(&self) -> Aabb {
+        match self {
+            Child::Node(node) => node.bbox,
+            Child::Object(bbox, _) => *bbox,
+        }
+    }
+
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        match self {
+            Child::Node(node) => node.hit(ray, t_min, t_max),
+            Child::Object(_, object) => object.hit(ray, t_min, t_max),
+        }
+    }
+}
+
+// How the objects of a node are divided between its children.
+#[derive(Debug, Clone, Copy)]
+enum Split {
+    // Along the axis in which the centroids are spread the most, at the
+    // median object. Quick to build.
+    Median,
+    // Wherever the surface area heuristic expects rays to be cheapest to
+    // trace, or not at all if a leaf is cheaper.
+    SurfaceArea,
+}
+
+type Objects = Vec<(Aabb, Arc<dyn Hittable>)>;
+
+impl BvhNode {
+    // Relative costs of testing a ray against a node's box and against an
+    // object, for the surface area heuristic.
+    const TRAVERSAL_COST: f64 = 0.125;
+    const INTERSECTION_COST: f64 = 1.0;
+    // Partitions with fewer objects than this are built on the current
+    // thread by `build_parallel`, where splitting the work costs more than
+    // it saves.
+    const PARALLEL_THRESHOLD: usize = 64;
+
+    // Splits nodes at the median object. Panics if any object is unbounded
+    // (e.g. an infinite plane), since it can't be placed in the hierarchy.
+    pub fn new(list: HittableList, time0: f64, time1: f64) -> Self {
+        Self::build(Self::bounded(list, time0, time1), Split::Median, false)
+    }
+
+    // Like `new`, but splits nodes using the surface area heuristic: the
+    // chance of a ray that hits a node also hitting a child is the ratio of
+    // their surface areas, which estimates what each candidate split costs.
+    // Slower to build, but makes for faster traversal, especially when
+    // object sizes vary or cluster.
+    pub fn new_sah(list: HittableList, time0: f64, time1: f64) -> Self {
+        Self::build(Self::bounded(list, time0, time1), Split::SurfaceArea, false)
+    }
+
+    // Builds the same tree as `new_sah`, with the subtrees of large
+    // partitions built in parallel on rayon's thread pool.
+    pub fn build_parallel(objects: Vec<Arc<dyn Hittable>>, time0: f64, time1: f64) -> Self {
+        let objects = Self::bounded(HittableList { objects }, time0, time1);
+        Self::build(objects, Split::SurfaceArea, true)
+    }
+
+    fn bounded(list: HittableList, time0: f64, time1: f64) -> Objects {
+        assert!(
+            !list.objects.is_empty(),
+            "BvhNode needs at least one object"
+        );
+
+        list.objects
+            .into_iter()
+            .map(|object| {
+                let bbox = object
+                    .bounding_box(time0, time1)
+                    .expect("No bounding box in BvhNode constructor");
+                (bbox, object)
+            })
+            .collect()
+    }
+
+    fn build(mut objects: Objects, split: Split, parallel: bool) -> Self {
+        if objects.len() == 1 {
+            let (bbox, object) = objects.pop().unwrap();
+            return Self {
+                left: Child::Object(bbox, object),
+                right: None,
+                bbox,
+            };
+        }
+
+        let right_objects = match split {
+            Split::Median => Some(Self::split_median(&mut objects)),
+            Split::SurfaceArea => Self::split_surface_area(&mut objects),
+        };
+        let Some(right_objects) = right_objects else {
+            let bbox = objects
+                .iter()
+                .map(|(bbox, _)| *bbox)
+                .reduce(surrounding_box)
+                .unwrap();
+            let list = HittableList {
+                objects: objects.into_iter().map(|(_, object)| object).collect(),
+            };
+            return Self {
+                left: Child::Object(bbox, Arc::new(list)),
+                right: None,
+                bbox,
+            };
+        };
+        let (left, right) =
+            if parallel && objects.len() + right_objects.len() > Self::PARALLEL_THRESHOLD {
+                rayon::join(
+                    || Self::build_child(objects, split, true),
+                    || Self::build_child(right_objects, split, true),
+                )
+            } else {
+                (
+                    Self::build_child(objects, split, parallel),
+                    Self::build_child(right_objects, split, parallel),
+                )
+            };
+
+        Self {
+            bbox: surrounding_box(left.bbox(), right.bbox()),
+            left,
+            right: Some(right),
+        }
+    }
+
+    fn build_child(mut objects: Objects, split: Split, parallel: bool) -> Child {
+        if objects.len() == 1 {
+            let (bbox, object) = objects.pop().unwrap();
+            return Child::Object(bbox, object);
+        }
+
+        Child::Node(Box::new(Self::build(objects, split, parallel)))
+    }
+
+    // Leaves the left half in `objects` and returns the right one.
+    fn split_median(objects: &mut Objects) -> Objects {
+        let centroids = objects
+            .iter()
+            .map(|(bbox, _)| {
+                let c = bbox.centroid();
+                Aabb::new(c, c)
+            })
+            .reduce(surrounding_box)
+            .unwrap();
+        let axis = centroids.longest_axis();
+
+        sort_by_centroid(objects, axis);
+        objects.split_off(objects.len() / 2)
+    }
+
+    // Tries every split between neighbouring centroids along each axis and
+    // picks the cheapest one, as `split_median` does. Returns None if
+    // keeping all objects in one leaf is cheaper.
+    fn split_surface_area(objects: &mut Objects) -> Option<Objects> {
+        let n = objects.len();
+        let parent_area = objects
+            .iter()
+            .map(|(bbox, _)| *bbox)
+            .reduce(surrounding_box)
+            .unwrap()
+            .surface_area();
+        if parent_area <= 0.0 {
+            return Some(Self::split_median(objects));
+        }
+
+        // (cost, axis, number of objects on the left)
+        let mut best = (f64::INFINITY, 0, n / 2);
+        for axis in 0..3 {
+            sort_by_centroid(objects, axis);
+
+            // right_areas[i] is the area around objects[i..].
+            let mut right_areas = vec![0.0; n];
+            let mut right_box = objects[n - 1].0;
+            for i in (1..n).rev() {
+                right_box = surrounding_box(right_box, objects[i].0);
+                right_areas[i] = right_box.surface_area();
+            }
+
+            let mut left_box = objects[0].0;
+            for i in 1..n {
+                let left_area = left_box.surface_area();
+                let cost = Self::TRAVERSAL_COST
+                    + (left_area * i as f64 + right_areas[i] * (n - i) as f64) / parent_area
+                        * Self::INTERSECTION_COST;
+                if cost < best.0 {
+                    best = (cost, axis, i);
+                }
+                left_box = surrounding_box(left_box, objects[i].0);
+            }
+        }
+
+        let (cost, axis, left_count) = best;
+        if cost >= n as f64 * Self::INTERSECTION_COST {
+            return None;
+        }
+        sort_by_centroid(objects, axis);
+        Some(objects.split_off(left_count))
+    }
+}
+
+fn sort_by_centroid(objects: &mut Objects, axis: usize) {
+    objects.sort_by(|(a, _), (b, _)| {
+        a.centroid()[axis]
+            .partial_cmp(&b.centroid()[axis])
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+impl Hittable for BvhNode {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        if !self.bbox.hit(ray, t_min, t_max) {
+            return None;
+        }
+
+        let hit_left = self.left.hit(ray, t_min, t_max);
+        let closest_so_far = hit_left.as_ref().map_or(t_max, |hit| hit.t);
+        let hit_right = self
+            .right
+            .as_ref()
+            .and_then(|right| right.hit(ray, t_min, closest_so_far));
+
+        hit_right.or(hit_left)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(self.bbox)
+    }
+}
+
+// A BVH laid out in one array, in depth-first order: the left child of a
+// node comes right after it, and the node records where its right sibling
+// subtree starts. Traversing it touches memory far more predictably than
+// chasing the boxes of a `BvhNode`, and it finds the same hits.
+pub struct FlatBvh {
+    nodes: Vec<FlatBvhNode>,
+    objects: Vec<Arc<dyn Hittable>>,
+    // Most interior nodes on any path from the root, which is as many
+    // right children as traversal ever has to remember at once.
+    depth: usize,
+}
+
+struct FlatBvhNode {
+    bbox: Aabb,
+    kind: FlatBvhKind,
+}
+
+enum FlatBvhKind {
+    // Index of the right child; the left one follows the node.
+    Interior { right: usize },
+    Leaf { object: usize },
+}
+
+impl FlatBvh {
+    // Balanced trees this deep would need billions of objects, so their
+    // traversal stack fits on the Rust stack. The SAH can split off one
+    // object at a time from skewed scenes, though, and deeper trees get a
+    // stack on the heap.
+    const MAX_STACK_DEPTH: usize = 64;
+
+    // `depth` counts the interior nodes above `node`.
+    fn flatten_node(&mut self, node: BvhNode, depth: usize) {
+        let Some(right) = node.right else {
+            // Leaves hold their object as the left child.
+            return self.flatten_child(node.left, depth);
+        };
+
+        let index = self.nodes.len();
+        self.nodes.push(FlatBvhNode {
+            bbox: node.bbox,
+            kind: FlatBvhKind::Interior { right: 0 },
+        });
+        self.depth = self.depth.max(depth + 1);
+        self.flatten_child(node.left, depth + 1);
+        self.nodes[index].kind = FlatBvhKind::Interior {
+            right: self.nodes.len(),
+        };
+        self.flatten_child(right, depth + 1);
+    }
+
+    fn flatten_child(&mut self, child: Child, depth: usize) {
+        match child {
+            Child::Node(node) => self.flatten_node(*node, depth),
+            Child::Object(bbox, object) => {
+                self.nodes.push(FlatBvhNode {
+                    bbox,
+                    kind: FlatBvhKind::Leaf {
+                        object: self.objects.len(),
+                    },
+                });
+                self.objects.push(object);
+            }
+        }
+    }
+}
+
+impl From<BvhNode> for FlatBvh {
+    fn from(root: BvhNode) -> Self {
+        let mut bvh = Self {
+            nodes: Vec::new(),
+            objects: Vec::new(),
+            depth: 0,
+        };
+        bvh.flatten_node(root, 0);
+        bvh
+    }
+}
+
+impl FlatBvh {
+    // Visits nodes in the same order as `BvhNode::hit`, left subtrees
+    // first, so ties between equally close hits resolve the same way.
+    // `stack` needs room for `depth` nodes.
+    fn traverse(
+        &self,
+        ray: &Ray,
+        t_min: f64,
+        t_max: f64,
+        stack: &mut [usize],
+    ) -> Option<HitRecord<'_>> {
+        let mut stack_len = 0;
+        let mut index = 0;
+        let mut closest = None;
+        let mut closest_so_far = t_max;
+
+        loop {
+            let node = &self.nodes[index];
+            let mut descend = None;
+            match node.kind {
+                FlatBvhKind::Interior { right } => {
+                    if node.bbox.hit(ray, t_min, closest_so_far) {
+                        stack[stack_len] = right;
+                        stack_len += 1;
+                        descend = Some(index + 1);
+                    }
+                }
+                // Objects are cheap enough to test directly, as in
+                // `BvhNode`.
+                FlatBvhKind::Leaf { object } => {
+                    if let Some(hit) = self.objects[object].hit(ray, t_min, closest_so_far) {
+                        closest_so_far = hit.t;
+                        closest = Some(hit);
+                    }
+                }
+            }
+
+            index = match descend {
+                Some(next) => next,
+                None if stack_len > 0 => {
+                    stack_len -= 1;
+                    stack[stack_len]
+                }
+                None => break,
+            };
+        }
+
+        closest
+    }
+}
+
+impl Hittable for FlatBvh {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        if self.depth <= Self::MAX_STACK_DEPTH {
+            self.traverse(ray, t_min, t_max, &mut [0; Self::MAX_STACK_DEPTH])
+        } else {
+            self.traverse(ray, t_min, t_max, &mut vec![0; self.depth])
+        }
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(self.nodes[0].bbox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_vec3_approx_eq, hittable::sphere::Sphere, material::lambertian::Lambertian,
+        random_float_between, rng, sampling, Color, Point3, Vec3,
+    };
+
+    fn sphere(center: Point3, radius: f64) -> Sphere<Lambertian> {
+        Sphere::new(center, radius, Lambertian::new(Color::from(0.5)))
+    }
+
+    fn random_spheres(count: usize) -> HittableList {
+        rng::seed_thread(1);
+        let mut list = HittableList::default();
+        for _ in 0..count {
+            let center = Vec3::random_between(-10.0, 10.0);
+            list.add(sphere(center, random_float_between(0.1, 1.0)));
+        }
+        list
+    }
+
+    // Spheres each ten times the size of the one before, in a row along x.
+    // Every split the surface area heuristic finds takes off only the
+    // biggest one, so the tree is as deep as there are spheres.
+    fn growing_spheres(count: usize) -> HittableList {
+        let mut list = HittableList::default();
+        let mut x = 0.0;
+        for i in 0..count {
+            let radius = 10f64.powi(i as i32);
+            list.add(sphere(Point3::new(x + radius, 0.0, 0.0), radius));
+            x += 2.0 * radius;
+        }
+        list
+    }
+
+    #[test]
+    fn flat_bvh_finds_the_same_hits_as_the_tree() {
+        let tree = BvhNode::new_sah(random_spheres(500), 0.0, 1.0);
+        let flat = FlatBvh::from(BvhNode::new_sah(random_spheres(500), 0.0, 1.0));
+
+        rng::seed_thread(2);
+        for _ in 0..2000 {
+            let ray = Ray::new(
+                Vec3::random_between(-15.0, 15.0),
+                sampling::uniform_sphere(),
+                None,
+            );
+            let expected = tree.hit(&ray, 0.001, f64::MAX).map(|hit| (hit.t, hit.p));
+            let found = flat.hit(&ray, 0.001, f64::MAX).map(|hit| (hit.t, hit.p));
+            assert_eq!(found, expected);
+        }
+    }
+
+    #[test]
+    fn flat_bvh_traverses_trees_deeper_than_its_fixed_stack() {
+        let flat = FlatBvh::from(BvhNode::new_sah(growing_spheres(80), 0.0, 1.0));
+        assert!(flat.depth > FlatBvh::MAX_STACK_DEPTH);
+
+        let ray = Ray::new(Point3::new(-1.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), None);
+        let hit = flat.hit(&ray, 0.001, f64::MAX).unwrap();
+        assert_vec3_approx_eq!(hit.p, Point3::new(0.0, 0.0, 0.0), 1e-9);
+    }
+}
@@ -0,0 +1,278 @@
+use std::f64::consts::PI;
+
+use crate::{aabb::Aabb, material::Material, math::onb::Onb, ray::Ray, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+// Cone with its tip at `apex`, opening along `axis` with the given
+// half-angle and cut off `height` from the tip. With `capped` the base is
+// closed by a disk. Hits are found in a frame where the tip is at the
+// origin and the axis is +z.
+pub struct Cone<M: Material> {
+    pub apex: Point3,
+    pub axis: Vec3,
+    pub half_angle_radians: f64,
+    pub height: f64,
+    pub capped: bool,
+    pub material: M,
+    frame: Onb,
+    // Squared tangent of the half-angle, the slope of the surface.
+    slope_squared: f64,
+}
+
+impl<M: Material> Cone<M> {
+    pub fn new(
+        apex: Point3,
+        axis: Vec3,
+        half_angle_radians: f64,
+        height: f64,
+        capped: bool,
+        material: M,
+    ) -> Self {
+        let frame = Onb::build_from_w(axis);
+
+        Self {
+            apex,
+            axis: frame.w,
+            half_angle_radians,
+            height,
+            capped,
+            material,
+            frame,
+            slope_squared: half_angle_radians.tan().powi(2),
+        }
+    }
+
+    pub fn base_radius(&self) -> f64 {
+        self.height * self.half_angle_radians.tan()
+    }
+
+    fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            v.dot(self.frame.u),
+            v.dot(self.frame.v),
+            v.dot(self.frame.w),
+        )
+    }
+}
+
+impl<M: Material> Hittable for Cone<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // The frame is orthonormal, so t is the same in both spaces.
+        let o = self.to_local(ray.origin - self.apex);
+        let d = self.to_local(ray.direction);
+        let k = self.slope_squared;
+
+        // Closest hit so far as (t, local outward normal).
+        let mut closest: Option<(f64, Vec3)> = None;
+        let mut consider = |t: f64, normal: Vec3| {
+            let limit = closest.map_or(t_max, |(t, _)| t);
+            if t >= t_min && t <= limit {
+                closest = Some((t, normal));
+            }
+        };
+
+        // Side: x^2 + y^2 = k z^2 for 0 <= z <= height. The equation
+        // describes a double cone, the range keeps the half in front of
+        // the tip.
+        let a = d.x() * d.x() + d.y() * d.y() - k * d.z() * d.z();
+        let half_b = o.x() * d.x() + o.y() * d.y() - k * o.z() * d.z();
+        let c = o.x() * o.x() + o.y() * o.y() - k * o.z() * o.z();
+        let roots = if a.abs() > 1e-12 {
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                vec![(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            } else {
+                vec![]
+            }
+        } else if half_b.abs() > 1e-12 {
+            // The ray runs parallel to the surface and crosses it once.
+            vec![-c / (2.0 * half_b)]
+        } else {
+            vec![]
+        };
+        for t in roots {
+            let p = o + d * t;
+            if (0.0..=self.height).contains(&p.z()) {
+                // The gradient of the implicit surface. It vanishes at the
+                // tip, where the axis pointing out of the tip is used.
+                let gradient = Vec3::new(p.x(), p.y(), -k * p.z());
+                let normal = if gradient.near_zero() {
+                    Vec3::new(0.0, 0.0, -1.0)
+                } else {
+                    gradient.unit_vector()
+                };
+                consider(t, normal);
+            }
+        }
+
+        // Base cap at z = height, facing away from the tip.
+        if self.capped && d.z().abs() > 1e-12 {
+            let t = (self.height - o.z()) / d.z();
+            let p = o + d * t;
+            let radius = self.base_radius();
+            if p.x() * p.x() + p.y() * p.y() <= radius * radius {
+                consider(t, Vec3::new(0.0, 0.0, 1.0));
+            }
+        }
+
+        let (t, local_normal) = closest?;
+        let local = o + d * t;
+        let outward_normal = self.frame.local(local_normal);
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        // u goes around the axis, v out from the axis relative to the base.
+        let phi = local.y().atan2(local.x()) + PI;
+
+        Some(HitRecord {
+            t,
+            p: ray.at(t),
+            material: &self.material,
+            normal,
+            u: phi / (2.0 * PI),
+            v: local.x().hypot(local.y()) / self.base_radius(),
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 0.0),
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        // The tip plus the base disk, which reaches r * sqrt(1 - axis_i^2)
+        // along each world axis i.
+        let base = self.apex + self.axis * self.height;
+        let radius = self.base_radius();
+        let extent = Vec3::new(
+            radius * (1.0 - self.axis.x() * self.axis.x()).max(0.0).sqrt(),
+            radius * (1.0 - self.axis.y() * self.axis.y()).max(0.0).sqrt(),
+            radius * (1.0 - self.axis.z() * self.axis.z()).max(0.0).sqrt(),
+        );
+
+        Some(Aabb::new(
+            Point3::min_components(self.apex, base - extent),
+            Point3::max_components(self.apex, base + extent),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, Color};
+
+    // A 45° cone with its tip at the origin, opening up to a base of
+    // radius 1 at y = 1.
+    fn cone(capped: bool) -> Cone<Lambertian> {
+        Cone::new(
+            Point3::from(0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            PI / 4.0,
+            1.0,
+            capped,
+            Lambertian::new(Color::from(0.5)),
+        )
+    }
+
+    fn ray(origin: Point3, direction: Vec3) -> Ray {
+        Ray::new(origin, direction, None)
+    }
+
+    #[test]
+    fn rays_hit_the_body() {
+        let cone = cone(true);
+        let hit = cone
+            .hit(
+                &ray(Point3::new(5.0, 0.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)),
+                0.001,
+                f64::INFINITY,
+            )
+            .unwrap();
+
+        assert!((hit.t - 4.5).abs() < 1e-12);
+        assert_vec3_approx_eq!(hit.p, Point3::new(0.5, 0.5, 0.0), 1e-12);
+        let outward = Vec3::new(1.0, -1.0, 0.0).unit_vector();
+        assert_vec3_approx_eq!(hit.normal, outward, 1e-12);
+        assert!(hit.front_face);
+        assert!((hit.v - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn capped_cones_are_closed_at_the_base() {
+        let down = ray(Point3::new(0.2, 5.0, 0.3), Vec3::new(0.0, -1.0, 0.0));
+
+        let capped = cone(true);
+        let hit = capped.hit(&down, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-12);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(0.0, 1.0, 0.0), 1e-12);
+        assert!(hit.front_face);
+
+        // Without the cap the ray enters and hits the inside of the body.
+        let open = cone(false);
+        let hit = open.hit(&down, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.p.y() - f64::hypot(0.2, 0.3)).abs() < 1e-12);
+        assert!(!hit.front_face);
+    }
+
+    #[test]
+    fn the_tip_normal_points_out_along_the_axis() {
+        let up = ray(Point3::new(0.0, -5.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let cone = cone(false);
+        let hit = cone.hit(&up, 0.001, f64::INFINITY).unwrap();
+
+        // The tip is a double root, found only to about the square root of
+        // the rounding error.
+        assert!((hit.t - 5.0).abs() < 1e-6);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(0.0, -1.0, 0.0), 1e-12);
+        assert!(hit.front_face);
+    }
+
+    #[test]
+    fn rays_past_the_tip_or_above_the_base_miss() {
+        let cone = cone(true);
+        // Through the mirrored half of the double cone, behind the tip.
+        let behind = ray(Point3::new(5.0, -0.5, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert!(cone.hit(&behind, 0.001, f64::INFINITY).is_none());
+        let above = ray(Point3::new(5.0, 1.5, 0.0), Vec3::new(-1.0, 0.0, 0.0));
+        assert!(cone.hit(&above, 0.001, f64::INFINITY).is_none());
+        let beside = ray(Point3::new(2.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        assert!(cone.hit(&beside, 0.001, f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn tilted_cones_stay_inside_their_bounding_box() {
+        let cone = Cone::new(
+            Point3::new(1.0, 2.0, 3.0),
+            Vec3::new(1.0, 1.0, -1.0),
+            0.4,
+            2.0,
+            true,
+            Lambertian::new(Color::from(0.5)),
+        );
+        let bbox = cone.bounding_box(0.0, 1.0).unwrap();
+        let target = cone.apex + cone.axis;
+
+        let mut hits = 0;
+        for i in 0..20 {
+            for j in 0..20 {
+                let (u, v) = (i as f64 / 19.0 - 0.5, j as f64 / 19.0 - 0.5);
+                let origin = target + Vec3::new(6.0 * u, 6.0 * v, 10.0);
+                let Some(hit) = cone.hit(&ray(origin, target - origin), 0.001, f64::INFINITY)
+                else {
+                    continue;
+                };
+                hits += 1;
+                for axis in 0..3 {
+                    assert!(bbox.min[axis] - 1e-9 <= hit.p[axis]);
+                    assert!(hit.p[axis] <= bbox.max[axis] + 1e-9);
+                }
+            }
+        }
+        assert!(hits > 0);
+    }
+}
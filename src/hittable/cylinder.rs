@@ -0,0 +1,218 @@
+use std::f64::consts::PI;
+
+use crate::{aabb::Aabb, material::Material, math::onb::Onb, ray::Ray, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+// Cylinder of `radius` around `axis`, from `center` (the middle of the base)
+// up to `height` along the axis, optionally closed by disk caps at both
+// ends. Hits are found in a frame where the axis is +z.
+pub struct Cylinder<M: Material> {
+    pub center: Point3,
+    pub axis: Vec3,
+    pub radius: f64,
+    pub height: f64,
+    pub capped: bool,
+    pub material: M,
+    frame: Onb,
+}
+
+impl<M: Material> Cylinder<M> {
+    pub fn new(
+        center: Point3,
+        axis: Vec3,
+        radius: f64,
+        height: f64,
+        capped: bool,
+        material: M,
+    ) -> Self {
+        let frame = Onb::build_from_w(axis);
+
+        Self {
+            center,
+            axis: frame.w,
+            radius,
+            height,
+            capped,
+            material,
+            frame,
+        }
+    }
+
+    fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(
+            v.dot(self.frame.u),
+            v.dot(self.frame.v),
+            v.dot(self.frame.w),
+        )
+    }
+}
+
+impl<M: Material> Hittable for Cylinder<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // The frame is orthonormal, so t is the same in both spaces.
+        let o = self.to_local(ray.origin - self.center);
+        let d = self.to_local(ray.direction);
+
+        // Closest hit so far as (t, local outward normal).
+        let mut closest: Option<(f64, Vec3)> = None;
+        let mut consider = |t: f64, normal: Vec3| {
+            let limit = closest.map_or(t_max, |(t, _)| t);
+            if t >= t_min && t <= limit {
+                closest = Some((t, normal));
+            }
+        };
+
+        // Side: x^2 + y^2 = r^2, between the two ends.
+        let a = d.x() * d.x() + d.y() * d.y();
+        if a > 1e-12 {
+            let half_b = o.x() * d.x() + o.y() * d.y();
+            let c = o.x() * o.x() + o.y() * o.y() - self.radius * self.radius;
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+                    let p = o + d * t;
+                    if (0.0..=self.height).contains(&p.z()) {
+                        consider(t, Vec3::new(p.x(), p.y(), 0.0) / self.radius);
+                    }
+                }
+            }
+        }
+
+        // Caps: z = 0 facing down and z = height facing up.
+        if self.capped && d.z().abs() > 1e-12 {
+            for (z, normal) in [(0.0, -1.0), (self.height, 1.0)] {
+                let t = (z - o.z()) / d.z();
+                let p = o + d * t;
+                if p.x() * p.x() + p.y() * p.y() <= self.radius * self.radius {
+                    consider(t, Vec3::new(0.0, 0.0, normal));
+                }
+            }
+        }
+
+        let (t, local_normal) = closest?;
+        let local = o + d * t;
+        let outward_normal = self.frame.local(local_normal);
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        // u goes around the axis. v runs up the side, and out from the
+        // center on the caps.
+        let phi = local.y().atan2(local.x()) + PI;
+        let v = if local_normal.z() == 0.0 {
+            local.z() / self.height
+        } else {
+            local.x().hypot(local.y()) / self.radius
+        };
+
+        Some(HitRecord {
+            t,
+            p: ray.at(t),
+            material: &self.material,
+            normal,
+            u: phi / (2.0 * PI),
+            v,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 0.0),
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        // A disk of radius r perpendicular to the axis reaches
+        // r * sqrt(1 - axis_i^2) along each world axis i.
+        let top = self.center + self.axis * self.height;
+        let extent = Vec3::new(
+            self.radius * (1.0 - self.axis.x() * self.axis.x()).max(0.0).sqrt(),
+            self.radius * (1.0 - self.axis.y() * self.axis.y()).max(0.0).sqrt(),
+            self.radius * (1.0 - self.axis.z() * self.axis.z()).max(0.0).sqrt(),
+        );
+
+        Some(Aabb::new(
+            Point3::min_components(self.center, top) - extent,
+            Point3::max_components(self.center, top) + extent,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, Color};
+
+    fn axes() -> [Vec3; 3] {
+        [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ]
+    }
+
+    // Radius 0.5 and height 2 along `axis`, starting at (1, 2, 3).
+    fn cylinder(axis: Vec3, capped: bool) -> Cylinder<Lambertian> {
+        let material = Lambertian::new(Color::from(0.5));
+        Cylinder::new(Point3::new(1.0, 2.0, 3.0), axis, 0.5, 2.0, capped, material)
+    }
+
+    // A direction perpendicular to `axis`.
+    fn across(axis: Vec3) -> Vec3 {
+        Vec3::new(axis.z(), axis.x(), axis.y())
+    }
+
+    #[test]
+    fn rays_across_the_axis_hit_the_side() {
+        for axis in axes() {
+            let cylinder = cylinder(axis, true);
+            let side = across(axis);
+            let origin = cylinder.center + axis * 1.5 + side * 3.0;
+            let hit = cylinder.hit(&Ray::new(origin, -side, None), 0.001, f64::INFINITY);
+            let hit = hit.unwrap();
+            assert!((hit.t - 2.5).abs() < 1e-12, "{axis:?}: {}", hit.t);
+            assert_vec3_approx_eq!(hit.normal, side, 1e-12);
+            assert!(hit.front_face);
+            assert!((hit.v - 0.75).abs() < 1e-12);
+
+            // Beyond the top there is nothing to hit.
+            let above = Ray::new(origin + axis, -side, None);
+            assert!(cylinder.hit(&above, 0.001, f64::INFINITY).is_none());
+        }
+    }
+
+    #[test]
+    fn rays_along_the_axis_hit_the_caps_only_if_capped() {
+        for axis in axes() {
+            let (capped, open) = (cylinder(axis, true), cylinder(axis, false));
+            let origin = capped.center + across(axis) * 0.2 - axis * 2.0;
+            let up = Ray::new(origin, axis, None);
+
+            let hit = capped.hit(&up, 0.001, f64::INFINITY).unwrap();
+            assert!((hit.t - 2.0).abs() < 1e-12, "{axis:?}: {}", hit.t);
+            assert_vec3_approx_eq!(hit.normal, -axis, 1e-12);
+            assert!((hit.v - 0.4).abs() < 1e-12);
+
+            // From inside, the ray leaves through the top cap.
+            let inside = Ray::new(origin + axis * 3.0, axis, None);
+            let hit = capped.hit(&inside, 0.001, f64::INFINITY).unwrap();
+            assert!((hit.t - 1.0).abs() < 1e-12);
+            assert!(!hit.front_face);
+
+            assert!(open.hit(&up, 0.001, f64::INFINITY).is_none());
+        }
+    }
+
+    #[test]
+    fn bounding_boxes_are_tight() {
+        for axis in axes() {
+            let bbox = cylinder(axis, true).bounding_box(0.0, 1.0).unwrap();
+            // One diameter across the axis, the height along it.
+            let across = Vec3::from(1.0) - axis;
+            assert_vec3_approx_eq!(bbox.max - bbox.min, across + axis * 2.0, 1e-12);
+            assert_vec3_approx_eq!(bbox.min, Point3::new(1.0, 2.0, 3.0) - across * 0.5, 1e-12);
+        }
+    }
+}
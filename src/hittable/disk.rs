@@ -0,0 +1,189 @@
+use std::f64::consts::PI;
+
+use crate::{aabb::Aabb, material::Material, math::onb::Onb, ray::Ray, sampling, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+// Flat disk of `radius` around `center`, facing along `normal`. Works as a
+// round area light, or to close off other shapes.
+pub struct Disk<M: Material> {
+    pub center: Point3,
+    pub normal: Vec3,
+    pub radius: f64,
+    pub material: M,
+    // In-plane axes (u, v) for UV coordinates and sampling.
+    frame: Onb,
+}
+
+impl<M: Material> Disk<M> {
+    pub fn new(center: Point3, normal: Vec3, radius: f64, material: M) -> Self {
+        let frame = Onb::build_from_w(normal);
+
+        Self {
+            center,
+            normal: frame.w,
+            radius,
+            material,
+            frame,
+        }
+    }
+}
+
+impl<M: Material> Hittable for Disk<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Solve dot(origin + t * direction - center, normal) = 0 for t.
+        let denom = ray.direction.dot(self.normal);
+        if denom.abs() < 1e-8 {
+            // The ray runs parallel to the disk.
+            return None;
+        }
+
+        let t = (self.center - ray.origin).dot(self.normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let local = p - self.center;
+        if local.length_squared() > self.radius * self.radius {
+            return None;
+        }
+
+        let front_face = denom < 0.0;
+        let normal = if front_face {
+            self.normal
+        } else {
+            -self.normal
+        };
+
+        Some(HitRecord {
+            t,
+            p,
+            material: &self.material,
+            normal,
+            u: (local.dot(self.frame.u) / self.radius + 1.0) / 2.0,
+            v: (local.dot(self.frame.v) / self.radius + 1.0) / 2.0,
+            tangent: self.frame.u,
+            bitangent: self.frame.v,
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        // The rim reaches r * sqrt(1 - normal_i^2) along each world axis i.
+        let extent = Vec3::new(
+            self.radius * (1.0 - self.normal.x() * self.normal.x()).max(0.0).sqrt(),
+            self.radius * (1.0 - self.normal.y() * self.normal.y()).max(0.0).sqrt(),
+            self.radius * (1.0 - self.normal.z() * self.normal.z()).max(0.0).sqrt(),
+        );
+
+        // Flat along the normal when it is axis-aligned, so pad it.
+        Some(Aabb::new(self.center - extent, self.center + extent).padded())
+    }
+
+    // Points are sampled uniformly over the area, converted to a density
+    // over the solid angle seen from `origin`.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let Some(rec) = self.hit(&Ray::new(origin, direction, None), 0.001, f64::INFINITY) else {
+            return 0.0;
+        };
+
+        let area = PI * self.radius * self.radius;
+        let distance_squared = rec.t * rec.t * direction.length_squared();
+        let cosine = (direction.dot(self.normal) / direction.length()).abs();
+
+        distance_squared / (cosine * area)
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let p = sampling::uniform_disk() * self.radius;
+        self.center + self.frame.u * p.x() + self.frame.v * p.y() - origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, rng, Color};
+
+    // A disk of radius 0.8 around (0, 0, -2), facing the origin.
+    fn disk() -> Disk<Lambertian> {
+        Disk::new(
+            Point3::new(0.0, 0.0, -2.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            0.8,
+            Lambertian::new(Color::from(0.5)),
+        )
+    }
+
+    fn towards(target: Point3) -> Ray {
+        Ray::new(Point3::from(0.0), target, None)
+    }
+
+    #[test]
+    fn rays_at_the_center_hit_and_past_the_edge_miss() {
+        let disk = disk();
+
+        let hit = disk
+            .hit(&towards(disk.center), 0.001, f64::INFINITY)
+            .unwrap();
+        assert_eq!(hit.t, 1.0);
+        assert_eq!((hit.u, hit.v), (0.5, 0.5));
+        assert_eq!(hit.normal, Vec3::new(0.0, 0.0, 1.0));
+        assert!(hit.front_face);
+
+        let inside = towards(Point3::new(0.79, 0.0, -2.0));
+        assert!(disk.hit(&inside, 0.001, f64::INFINITY).is_some());
+        let outside = towards(Point3::new(0.0, 0.81, -2.0));
+        assert!(disk.hit(&outside, 0.001, f64::INFINITY).is_none());
+        let parallel = Ray::new(Point3::new(-1.0, 0.0, -2.0), Vec3::new(1.0, 0.0, 0.0), None);
+        assert!(disk.hit(&parallel, 0.001, f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn uvs_span_the_disk() {
+        let disk = disk();
+        for (x, y) in [(0.8, 0.0), (-0.8, 0.0), (0.0, 0.8), (0.0, -0.8), (0.4, 0.3)] {
+            let hit = disk
+                .hit(&towards(Point3::new(x, y, -2.0)), 0.001, f64::INFINITY)
+                .unwrap();
+            assert!((0.0..=1.0).contains(&hit.u) && (0.0..=1.0).contains(&hit.v));
+            // Opposite points land on opposite sides of the center.
+            let mirrored = disk
+                .hit(&towards(Point3::new(-x, -y, -2.0)), 0.001, f64::INFINITY)
+                .unwrap();
+            assert!((hit.u + mirrored.u - 1.0).abs() < 1e-12);
+            assert!((hit.v + mirrored.v - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn bounding_boxes_are_flat_along_the_normal() {
+        let bbox = disk().bounding_box(0.0, 1.0).unwrap();
+        assert_eq!((bbox.min.x(), bbox.max.x()), (-0.8, 0.8));
+        assert_eq!((bbox.min.y(), bbox.max.y()), (-0.8, 0.8));
+        assert!(bbox.min.z() < -2.0 && -2.0 < bbox.max.z());
+        assert!(bbox.max.z() - bbox.min.z() < 1e-3);
+    }
+
+    #[test]
+    fn the_light_pdf_integrates_to_one() {
+        rng::seed_thread(12);
+        let disk = disk();
+        let origin = Point3::new(0.3, -0.2, 0.0);
+
+        for _ in 0..1000 {
+            let direction = disk.random_direction(origin);
+            assert!(disk.pdf_value(origin, direction) > 0.0);
+        }
+
+        let n = 200_000;
+        let integral = (0..n)
+            .map(|_| disk.pdf_value(origin, sampling::uniform_sphere()))
+            .sum::<f64>()
+            * 4.0
+            * PI
+            / n as f64;
+        assert!((integral - 1.0).abs() < 0.02, "integral {}", integral);
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::{aabb::Aabb, material::Material, ray::Ray, Point3};
+
+use super::{
+    bvh::BvhNode, mesh::MeshTriangle, triangle::Triangle, HitRecord, Hittable, HittableList,
+};
+
+// Terrain sampled on a regular grid. `heights` holds `width` samples along x
+// for each of the `depth` rows along z. Sample (i, j) sits at
+// (i * scale_xz, heights[j * width + i] * scale_y, j * scale_xz), so the
+// grid starts at the origin; place it with the transforms.
+pub struct Heightmap {
+    pub width: u32,
+    pub depth: u32,
+    pub heights: Vec<f64>,
+    pub scale_xz: f64,
+    pub scale_y: f64,
+    pub material: Arc<dyn Material>,
+    bvh: BvhNode,
+}
+
+impl Heightmap {
+    // Every grid cell becomes two triangles facing +y. Texture coordinates
+    // run from 0 to 1 across the grid. Panics unless the grid is at least
+    // 2 x 2 and `heights` has width * depth entries.
+    pub fn new(
+        width: u32,
+        depth: u32,
+        heights: Vec<f64>,
+        scale_xz: f64,
+        scale_y: f64,
+        material: impl Material + 'static,
+    ) -> Self {
+        assert!(
+            width >= 2 && depth >= 2,
+            "Heightmap needs at least 2 x 2 samples"
+        );
+        assert_eq!(
+            heights.len(),
+            (width * depth) as usize,
+            "Heightmap needs width * depth heights"
+        );
+
+        let material: Arc<dyn Material> = Arc::new(material);
+        let vertex = |i: u32, j: u32| {
+            let height = heights[(j * width + i) as usize];
+            let position = Point3::new(i as f64 * scale_xz, height * scale_y, j as f64 * scale_xz);
+            let uv = (i as f64 / (width - 1) as f64, j as f64 / (depth - 1) as f64);
+            (position, uv)
+        };
+
+        let mut triangles = HittableList::default();
+        for j in 0..depth - 1 {
+            for i in 0..width - 1 {
+                let a = vertex(i, j);
+                let b = vertex(i + 1, j);
+                let c = vertex(i, j + 1);
+                let d = vertex(i + 1, j + 1);
+                for [v0, v1, v2] in [[a, c, b], [b, c, d]] {
+                    triangles.add(MeshTriangle {
+                        triangle: Triangle::new(v0.0, v1.0, v2.0, material.clone()),
+                        normals: None,
+                        uvs: Some([v0.1, v1.1, v2.1]),
+                    });
+                }
+            }
+        }
+
+        Self {
+            width,
+            depth,
+            heights,
+            scale_xz,
+            scale_y,
+            material,
+            bvh: BvhNode::new(triangles, 0.0, 0.0),
+        }
+    }
+}
+
+impl Hittable for Heightmap {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        self.bvh.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.bvh.bounding_box(time0, time1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{material::lambertian::Lambertian, Color, Vec3};
+
+    // A 10 x 10 grid of cosine waves, between -2 and 2 in height, peaking
+    // at sample (4, 4) and lowest at (9, 4).
+    fn hills() -> Heightmap {
+        let wave = |i: u32| (PI * (i as f64 - 4.0) / 5.0).cos();
+        let heights = (0..10)
+            .flat_map(|j| (0..10).map(move |i| wave(i) * wave(j)))
+            .collect();
+        Heightmap::new(10, 10, heights, 1.0, 2.0, Lambertian::new(Color::from(0.5)))
+    }
+
+    fn down_from(x: f64, y: f64, z: f64) -> Ray {
+        Ray::new(Point3::new(x, y, z), Vec3::new(0.0, -1.0, 0.0), None)
+    }
+
+    #[test]
+    fn a_ray_down_onto_the_peak_hits_it() {
+        let hills = hills();
+        let hit = hills
+            .hit(&down_from(4.0, 10.0, 4.0), 0.001, f64::INFINITY)
+            .unwrap();
+
+        assert!((hit.p.y() - 2.0).abs() < 1e-9);
+        assert!(hit.normal.y() > 0.0);
+        assert!(hit.front_face);
+        assert!((hit.u - 4.0 / 9.0).abs() < 1e-9 && (hit.v - 4.0 / 9.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_ray_starting_below_the_valley_misses() {
+        let hills = hills();
+        assert!(hills
+            .hit(&down_from(9.0, -2.1, 4.0), 0.001, f64::INFINITY)
+            .is_none());
+        // Just above it, the valley is hit.
+        let hit = hills
+            .hit(&down_from(9.0, -1.9, 4.0), 0.001, f64::INFINITY)
+            .unwrap();
+        assert!((hit.p.y() + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_bounding_box_spans_the_grid() {
+        let bbox = hills().bounding_box(0.0, 1.0).unwrap();
+        assert_eq!((bbox.min.x(), bbox.max.x()), (0.0, 9.0));
+        assert_eq!((bbox.min.z(), bbox.max.z()), (0.0, 9.0));
+        assert!((bbox.min.y() + 2.0).abs() < 1e-3 && (bbox.max.y() - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    #[should_panic(expected = "Heightmap needs width * depth heights")]
+    fn heights_have_to_fill_the_grid() {
+        Heightmap::new(
+            3,
+            3,
+            vec![0.0; 8],
+            1.0,
+            1.0,
+            Lambertian::new(Color::from(0.5)),
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::{aabb::Aabb, math::mat4::Mat4, ray::Ray, Point3, Vec3};
+
+use super::{transform::transform_box, HitRecord, Hittable};
+
+// Places an object with a full 4x4 transform, e.g. one built with
+// `Mat4::from_rotation_translation_scale`. Like `Translate` and `RotateY`,
+// it moves the ray into the object's space instead of the object, so many
+// instances can share the same geometry wrapped in an Arc.
+//
+// The ray's direction isn't renormalized in object space, which keeps the
+// hit distances of both spaces the same.
+pub struct GeometryInstance<H: Hittable> {
+    pub inner: H,
+    transform: Mat4,
+    inv_transform: Mat4,
+    // Box of the transformed object over the shutter interval [0, 1].
+    bbox: Option<Aabb>,
+}
+
+impl<H: Hittable> GeometryInstance<H> {
+    pub fn new(inner: H, transform: Mat4) -> Self {
+        let bbox = inner
+            .bounding_box(0.0, 1.0)
+            .map(|bbox| transform_box(bbox, |p| transform.transform_point3(p)));
+
+        Self {
+            inner,
+            transform,
+            inv_transform: transform.inverse(),
+            bbox,
+        }
+    }
+
+    pub fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    // Zero, for primitives without tangents, stays zero.
+    fn transform_tangent(&self, tangent: Vec3) -> Vec3 {
+        if tangent.near_zero() {
+            return tangent;
+        }
+        self.transform.transform_dir(tangent).unit_vector()
+    }
+}
+
+impl<H: Hittable> Hittable for GeometryInstance<H> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let local = Ray::new(
+            self.inv_transform.transform_point3(ray.origin),
+            self.inv_transform.transform_dir(ray.direction),
+            Some(ray.time),
+        );
+        let mut rec = self.inner.hit(&local, t_min, t_max)?;
+        // The normal keeps its side of the surface under the inverse
+        // transpose, mirrored or not, so front_face stays correct.
+        rec.p = self.transform.transform_point3(rec.p);
+        rec.normal = self
+            .inv_transform
+            .transpose()
+            .transform_dir(rec.normal)
+            .unit_vector();
+        // Directions along the surface move with it.
+        rec.tangent = self.transform_tangent(rec.tangent);
+        rec.bitangent = self.transform_tangent(rec.bitangent);
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        self.bbox
+    }
+
+    // Exact for rigid transforms only: scaling changes the solid angle the
+    // object covers, which the inner object's density doesn't know about.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        self.inner.pdf_value(
+            self.inv_transform.transform_point3(origin),
+            self.inv_transform.transform_dir(direction),
+        )
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let local = self.inv_transform.transform_point3(origin);
+        self.transform
+            .transform_dir(self.inner.random_direction(local))
+    }
+}
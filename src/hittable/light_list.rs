@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use crate::{
+    aabb::{surrounding_box, Aabb},
+    random_float,
+    ray::Ray,
+    Color, Point3, Vec3,
+};
+
+use super::{HitRecord, Hittable};
+
+// The emissive objects of a scene, sampled directly by `Ray::color`. They
+// are usually shared with the world through an Arc, but any object with the
+// same shape as a light works too.
+#[derive(Default, Clone)]
+pub struct LightList {
+    pub lights: Vec<Arc<dyn Hittable>>,
+}
+
+impl LightList {
+    pub fn add(&mut self, light: Arc<dyn Hittable>) {
+        self.lights.push(light);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lights.is_empty()
+    }
+}
+
+impl Hittable for LightList {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let mut closest_so_far = t_max;
+        let mut hit_anything = None;
+        for light in self.lights.iter() {
+            if let Some(hit) = light.hit(ray, t_min, closest_so_far) {
+                closest_so_far = hit.t;
+                hit_anything = Some(hit);
+            }
+        }
+
+        hit_anything
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let mut output_box: Option<Aabb> = None;
+        for light in self.lights.iter() {
+            let bbox = light.bounding_box(time0, time1)?;
+            output_box = Some(match output_box {
+                Some(b) => surrounding_box(b, bbox),
+                None => bbox,
+            });
+        }
+
+        output_box
+    }
+
+    // Density over solid angle of sampling `direction` from `origin` by
+    // picking a light uniformly and then a random point on it.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        if self.lights.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .lights
+            .iter()
+            .map(|light| light.pdf_value(origin, direction))
+            .sum();
+
+        sum / self.lights.len() as f64
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let index = (random_float() * self.lights.len() as f64) as usize;
+        self.lights[index.min(self.lights.len() - 1)].random_direction(origin)
+    }
+
+    fn distant_radiance(&self, direction: Vec3) -> Color {
+        self.lights
+            .iter()
+            .map(|light| light.distant_radiance(direction))
+            .sum()
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::{HitRecord, Hittable},
+    material::{def::MaterialDef, henyey_greenstein::phase, Material, ScatterRecord},
+    math::onb::Onb,
+    random_float,
+    ray::Ray,
+    sampling, Color, Vec3,
+};
+
+use super::ConstantMedium;
+
+// Asymmetry of the Henyey-Greenstein phase function. Haze and fog scatter
+// most light only slightly off its way, which is what makes shafts of
+// light stand out when looking towards their source.
+const FORWARD_SCATTERING: f64 = 0.8;
+
+// Phase function of hazy air for light shafts (god rays, crepuscular
+// rays): light coming through a gap lights up the fog it passes, and the
+// shadows of whatever is around the gap stay dark. Use it with
+// `ConstantMedium`, most easily through `fill`, and sample the light the
+// shafts come from directly.
+//
+// The medium absorbs as well as scatters. Of the light taken out of a ray
+// per unit length, `density` is scattered and `falloff` absorbed, so light
+// dims with how deep into the fog it has gone, and the fog far from the
+// gaps gets darker than with scattering alone.
+//
+// Half of the scattered rays are sent around `direction`, towards the
+// light, rather than around the incoming ray. The phase function is the
+// same either way, but paths find the light through the gaps more often,
+// which matters for lights that can't be sampled directly, like a sun in
+// the background.
+#[derive(Debug, Clone, Copy)]
+pub struct GodRayMedium {
+    // Towards the light the shafts come from, normalized.
+    pub direction: Vec3,
+    // Share of the light it scatters that the fog gives off again, the
+    // brightness of the shafts. 1 loses none.
+    pub intensity: f64,
+    // Absorption per unit length.
+    pub falloff: f64,
+    // Scattering per unit length.
+    pub density: f64,
+}
+
+impl GodRayMedium {
+    pub fn new(direction: Vec3, intensity: f64, falloff: f64, density: f64) -> Self {
+        Self {
+            direction: direction.unit_vector(),
+            intensity,
+            falloff: falloff.max(0.0),
+            density: density.max(0.0),
+        }
+    }
+
+    // A volume of this fog filling `boundary`. Absorbed light counts
+    // towards how far rays get, so the medium's density includes it.
+    pub fn fill<B: Hittable>(self, boundary: B) -> ConstantMedium<B> {
+        ConstantMedium::new(boundary, self.density + self.falloff, Arc::new(self))
+    }
+
+    // Light coming out of a collision with the fog, which is scattered
+    // with probability density / (density + falloff).
+    fn albedo(&self) -> f64 {
+        let extinction = self.density + self.falloff;
+        if extinction <= 0.0 {
+            return 0.0;
+        }
+        self.intensity * self.density / extinction
+    }
+}
+
+impl Material for GodRayMedium {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let forward = ray_in.direction.unit_vector();
+        let axis = if random_float() < 0.5 {
+            forward
+        } else {
+            self.direction
+        };
+        let direction =
+            Onb::build_from_w(axis).local(sampling::henyey_greenstein(FORWARD_SCATTERING));
+        let pdf = 0.5
+            * (phase(FORWARD_SCATTERING, forward.dot(direction))
+                + phase(FORWARD_SCATTERING, self.direction.dot(direction)));
+
+        Some(
+            ScatterRecord::new(
+                Ray::new(rec.p, direction, Some(ray_in.time)),
+                Color::from(self.albedo()),
+            )
+            .with_pdf(pdf),
+        )
+    }
+
+    fn scattering_pdf(&self, ray_in: &Ray, _rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cos_theta = ray_in
+            .direction
+            .unit_vector()
+            .dot(scattered.direction.unit_vector());
+        phase(FORWARD_SCATTERING, cos_theta)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::GodRay {
+            direction: self.direction,
+            intensity: self.intensity,
+            falloff: self.falloff,
+            density: self.density,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use crate::{aabb::Aabb, material::Material, random_float, ray::Ray, Vec3};
+
+use super::{HitRecord, Hittable};
+
+pub mod god_ray;
+
+// Volume of constant density (smoke, fog, ...) filling a convex boundary.
+// A ray passing through scatters after a random, exponentially distributed
+// distance, and passes straight through if that's beyond the far side.
+pub struct ConstantMedium<B: Hittable> {
+    pub boundary: B,
+    pub neg_inv_density: f64,
+    pub phase_function: Arc<dyn Material>,
+}
+
+impl<B: Hittable> ConstantMedium<B> {
+    pub fn new(boundary: B, density: f64, phase_function: Arc<dyn Material>) -> Self {
+        Self {
+            boundary,
+            neg_inv_density: -1.0 / density,
+            phase_function,
+        }
+    }
+}
+
+impl<B: Hittable> Hittable for ConstantMedium<B> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Find where the ray enters and leaves the boundary, even if that's
+        // behind the origin (the ray may start inside the volume).
+        let rec1 = self.boundary.hit(ray, f64::NEG_INFINITY, f64::INFINITY)?;
+        let rec2 = self.boundary.hit(ray, rec1.t + 0.0001, f64::INFINITY)?;
+
+        let t1 = f64::max(f64::max(rec1.t, t_min), 0.0);
+        let t2 = f64::min(rec2.t, t_max);
+        if t1 >= t2 {
+            return None;
+        }
+
+        let ray_length = ray.direction.length();
+        let distance_inside_boundary = (t2 - t1) * ray_length;
+        let hit_distance = self.neg_inv_density * random_float().ln();
+        if hit_distance > distance_inside_boundary {
+            return None;
+        }
+
+        let t = t1 + hit_distance / ray_length;
+        Some(HitRecord {
+            t,
+            p: ray.at(t),
+            material: &self.phase_function,
+            // Volumes have no surface, so the normal and side are arbitrary.
+            normal: Vec3::new(1.0, 0.0, 0.0),
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 0.0),
+            front_face: true,
+        })
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.boundary.bounding_box(time0, time1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::aabox::AaBox,
+        material::{isotropic::Isotropic, lambertian::Lambertian},
+        rng, Color, Point3,
+    };
+
+    fn smoke(density: f64) -> ConstantMedium<AaBox<Lambertian>> {
+        ConstantMedium::new(
+            AaBox::new(
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(2.0, 2.0, 2.0),
+                Lambertian::new(Color::from(0.5)),
+            ),
+            density,
+            Arc::new(Isotropic::new(Color::from(1.0))),
+        )
+    }
+
+    #[test]
+    fn rays_pass_through_with_exponential_falloff() {
+        rng::seed_thread(11);
+        let smoke = smoke(0.5);
+        // The direction is two units long, so the ray is inside the box for
+        // t in [0.5, 1.5], a distance of 2.
+        let ray = Ray::new(Point3::new(1.0, 1.0, -1.0), Vec3::new(0.0, 0.0, 2.0), None);
+
+        let n = 100_000;
+        let mut passed = 0;
+        for _ in 0..n {
+            match smoke.hit(&ray, 0.001, f64::INFINITY) {
+                Some(hit) => {
+                    assert!((0.5..=1.5).contains(&hit.t), "{}", hit.t);
+                    assert!((0.0..=2.0).contains(&hit.p.z()));
+                }
+                None => passed += 1,
+            }
+        }
+
+        let transmittance = passed as f64 / n as f64;
+        assert!(
+            (transmittance - f64::exp(-0.5 * 2.0)).abs() < 0.01,
+            "{transmittance}"
+        );
+    }
+
+    #[test]
+    fn rays_starting_inside_scatter_ahead_of_them() {
+        rng::seed_thread(12);
+        let smoke = smoke(100.0);
+        let ray = Ray::new(Point3::new(1.0, 1.0, 1.0), Vec3::new(1.0, 0.0, 0.0), None);
+        for _ in 0..1000 {
+            let hit = smoke.hit(&ray, 0.001, f64::INFINITY).unwrap();
+            assert!(hit.t > 0.0 && hit.t <= 1.0, "{}", hit.t);
+        }
+
+        // Nothing to scatter off beyond t_max or outside the box.
+        assert!(smoke.hit(&ray, 0.001, 0.0001).is_none());
+        let outside = Ray::new(Point3::new(3.0, 1.0, 1.0), Vec3::new(0.0, 1.0, 0.0), None);
+        assert!(smoke.hit(&outside, 0.001, f64::INFINITY).is_none());
+    }
+}
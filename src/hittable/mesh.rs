@@ -0,0 +1,293 @@
+use std::{error::Error, fmt, fs, io, path::Path, sync::Arc};
+
+use crate::{aabb::Aabb, material::Material, ray::Ray, Point3, Vec3};
+
+use super::{bvh::BvhNode, triangle::Triangle, HitRecord, Hittable, HittableList};
+
+#[derive(Debug)]
+pub enum MeshError {
+    Io(io::Error),
+    Parse { line: usize, message: String },
+    Empty,
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MeshError::Io(err) => write!(f, "unable to read mesh: {}", err),
+            MeshError::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            MeshError::Empty => write!(f, "mesh contains no faces"),
+        }
+    }
+}
+
+impl Error for MeshError {}
+
+impl From<io::Error> for MeshError {
+    fn from(err: io::Error) -> Self {
+        MeshError::Io(err)
+    }
+}
+
+// Indices into the mesh's vertex attribute arrays for one triangle corner.
+#[derive(Debug, Clone, Copy)]
+pub struct FaceVertex {
+    pub position: usize,
+    pub uv: Option<usize>,
+    pub normal: Option<usize>,
+}
+
+pub struct TriangleMesh {
+    pub positions: Vec<Point3>,
+    pub uvs: Vec<(f64, f64)>,
+    pub normals: Vec<Vec3>,
+    pub faces: Vec<[FaceVertex; 3]>,
+    bvh: BvhNode,
+}
+
+impl TriangleMesh {
+    // Loads the triangles of a Wavefront OBJ file. Polygons with more than
+    // three vertices are triangulated as a fan, which is exact for the
+    // convex faces exporters emit.
+    pub fn from_obj(path: &Path, material: impl Material + 'static) -> Result<Self, MeshError> {
+        let source = fs::read_to_string(path)?;
+
+        let mut positions = Vec::new();
+        let mut uvs = Vec::new();
+        let mut normals = Vec::new();
+        let mut faces = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let mut tokens = line.split_whitespace();
+            let Some(keyword) = tokens.next() else {
+                continue;
+            };
+            let args: Vec<&str> = tokens.collect();
+
+            match keyword {
+                "v" => {
+                    let [x, y, z] = parse_floats(&args, line_number)?;
+                    positions.push(Point3::new(x, y, z));
+                }
+                "vt" => {
+                    let [u, v] = parse_floats(&args, line_number)?;
+                    uvs.push((u, v));
+                }
+                "vn" => {
+                    let [x, y, z] = parse_floats(&args, line_number)?;
+                    normals.push(Vec3::new(x, y, z).unit_vector());
+                }
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(parse_error(line_number, "face needs at least 3 vertices"));
+                    }
+                    let corners = args
+                        .iter()
+                        .map(|arg| parse_face_vertex(arg, &positions, &uvs, &normals, line_number))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    for i in 1..corners.len() - 1 {
+                        faces.push([corners[0], corners[i], corners[i + 1]]);
+                    }
+                }
+                // Comments, groups, smoothing groups, materials, ...
+                _ => {}
+            }
+        }
+
+        if faces.is_empty() {
+            return Err(MeshError::Empty);
+        }
+
+        let material: Arc<dyn Material> = Arc::new(material);
+        let mut triangles = HittableList::default();
+        for face in faces.iter() {
+            triangles.add(MeshTriangle {
+                triangle: Triangle::new(
+                    positions[face[0].position],
+                    positions[face[1].position],
+                    positions[face[2].position],
+                    material.clone(),
+                ),
+                normals: match (face[0].normal, face[1].normal, face[2].normal) {
+                    (Some(a), Some(b), Some(c)) => Some([normals[a], normals[b], normals[c]]),
+                    _ => None,
+                },
+                uvs: match (face[0].uv, face[1].uv, face[2].uv) {
+                    (Some(a), Some(b), Some(c)) => Some([uvs[a], uvs[b], uvs[c]]),
+                    _ => None,
+                },
+            });
+        }
+
+        Ok(Self {
+            positions,
+            uvs,
+            normals,
+            faces,
+            bvh: BvhNode::new(triangles, 0.0, 0.0),
+        })
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        self.bvh.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.bvh.bounding_box(time0, time1)
+    }
+}
+
+// A mesh face that interpolates per-vertex normals and texture coordinates
+// across the triangle when the OBJ file provides them.
+pub(super) struct MeshTriangle {
+    pub(super) triangle: Triangle<Arc<dyn Material>>,
+    pub(super) normals: Option<[Vec3; 3]>,
+    pub(super) uvs: Option<[(f64, f64); 3]>,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let mut rec = self.triangle.hit(ray, t_min, t_max)?;
+        let (b1, b2) = (rec.u, rec.v);
+        let b0 = 1.0 - b1 - b2;
+
+        if let Some([n0, n1, n2]) = self.normals {
+            let normal = (n0 * b0 + n1 * b1 + n2 * b2).unit_vector();
+            // Keep the shading normal on the same side as the geometric one.
+            rec.normal = if rec.front_face { normal } else { -normal };
+        }
+        if let Some([uv0, uv1, uv2]) = self.uvs {
+            rec.u = uv0.0 * b0 + uv1.0 * b1 + uv2.0 * b2;
+            rec.v = uv0.1 * b0 + uv1.1 * b1 + uv2.1 * b2;
+
+            // Solve e1 = du1 * T + dv1 * B and e2 = du2 * T + dv2 * B for
+            // the tangents of the texture's u and v. UVs that don't span
+            // any area keep the triangle's own.
+            let Triangle { v0, v1, v2, .. } = self.triangle;
+            let (e1, e2) = (v1 - v0, v2 - v0);
+            let (du1, dv1) = (uv1.0 - uv0.0, uv1.1 - uv0.1);
+            let (du2, dv2) = (uv2.0 - uv0.0, uv2.1 - uv0.1);
+            let det = du1 * dv2 - du2 * dv1;
+            if det.abs() > 1e-12 {
+                rec.tangent = ((e1 * dv2 - e2 * dv1) / det).unit_vector();
+                rec.bitangent = ((e2 * du1 - e1 * du2) / det).unit_vector();
+            }
+        }
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        self.triangle.bounding_box(time0, time1)
+    }
+}
+
+fn parse_error(line: usize, message: &str) -> MeshError {
+    MeshError::Parse {
+        line,
+        message: message.to_string(),
+    }
+}
+
+fn parse_floats<const N: usize>(args: &[&str], line: usize) -> Result<[f64; N], MeshError> {
+    // Extra components (e.g. the optional w of a vertex) are ignored.
+    if args.len() < N {
+        return Err(parse_error(line, &format!("expected {} numbers", N)));
+    }
+
+    let mut values = [0.0; N];
+    for (value, arg) in values.iter_mut().zip(args) {
+        *value = arg
+            .parse()
+            .map_err(|_| parse_error(line, &format!("invalid number '{}'", arg)))?;
+    }
+
+    Ok(values)
+}
+
+fn parse_face_vertex(
+    arg: &str,
+    positions: &[Point3],
+    uvs: &[(f64, f64)],
+    normals: &[Vec3],
+    line: usize,
+) -> Result<FaceVertex, MeshError> {
+    // Accepts "v", "v/vt", "v//vn" and "v/vt/vn".
+    let mut parts = arg.split('/');
+    let position = resolve_index(parts.next(), positions.len(), line)?
+        .ok_or_else(|| parse_error(line, "face vertex without a position"))?;
+    let uv = resolve_index(parts.next(), uvs.len(), line)?;
+    let normal = resolve_index(parts.next(), normals.len(), line)?;
+
+    Ok(FaceVertex {
+        position,
+        uv,
+        normal,
+    })
+}
+
+fn resolve_index(part: Option<&str>, len: usize, line: usize) -> Result<Option<usize>, MeshError> {
+    let Some(part) = part.filter(|p| !p.is_empty()) else {
+        return Ok(None);
+    };
+    let index: i64 = part
+        .parse()
+        .map_err(|_| parse_error(line, &format!("invalid index '{}'", part)))?;
+
+    // OBJ indices are 1-based; negative indices count back from the most
+    // recently defined element.
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        len as i64 + index
+    };
+    if index == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(parse_error(line, &format!("index {} out of range", index)));
+    }
+
+    Ok(Some(resolved as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, Color};
+
+    fn cube() -> TriangleMesh {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/cube.obj");
+        TriangleMesh::from_obj(&path, Lambertian::new(Color::from(0.5))).unwrap()
+    }
+
+    #[test]
+    fn quads_are_split_into_two_triangles() {
+        let cube = cube();
+        assert_eq!(cube.positions.len(), 8);
+        assert_eq!(cube.faces.len(), 12);
+    }
+
+    #[test]
+    fn rays_hit_the_face_they_point_at() {
+        let cube = cube();
+        for axis in [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        ] {
+            // Off the middle of the face, away from the diagonal between
+            // its two triangles.
+            let origin = axis * 5.0 + Vec3::new(0.1, 0.3, 0.2).cross(axis);
+            let hit = cube
+                .hit(&Ray::new(origin, -axis, None), 0.001, f64::MAX)
+                .unwrap();
+            assert!((hit.t - 4.5).abs() < 1e-9, "{:?}", axis);
+            assert!(hit.front_face);
+            assert_vec3_approx_eq!(hit.normal, axis, 1e-9);
+        }
+
+        let past = Ray::new(Point3::new(0.6, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0), None);
+        assert!(cube.hit(&past, 0.001, f64::MAX).is_none());
+    }
+}
@@ -1,33 +1,145 @@
-use crate::{material::Material, ray::Ray, Point3, Vec3};
+use std::sync::Arc;
 
+use crate::{
+    aabb::{surrounding_box, Aabb},
+    material::Material,
+    random_float,
+    ray::Ray,
+    Color, Point3, Vec3,
+};
+
+pub mod aabox;
+pub mod bvh;
+pub mod cone;
+pub mod cylinder;
+pub mod disk;
+pub mod heightmap;
+pub mod instance;
+pub mod light_list;
+pub mod medium;
+pub mod mesh;
 pub mod moving_sphere;
+pub mod plane;
+pub mod rect;
+pub mod sdf;
 pub mod sphere;
+pub mod sun;
+pub mod torus;
+pub mod transform;
+pub mod triangle;
 
+// Serializable for debugging, without the material. It can't be
+// deserialized, since it only borrows its material.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HitRecord<'a> {
     pub p: Point3,
     pub normal: Vec3,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub material: &'a dyn Material,
     pub t: f64,
+    // Surface coordinates of the hit point, for texture lookups.
+    pub u: f64,
+    pub v: f64,
+    // Unit directions along the surface in which u and v grow, for normal
+    // maps. Zero for primitives that don't provide them.
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
     pub front_face: bool,
 }
 
-pub trait Hittable: Sync {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord>;
+pub trait Hittable: Send + Sync {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>>;
+
+    // Returns the box enclosing the object over the shutter interval
+    // [time0, time1], or None if the object is unbounded.
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb>;
+
+    // Density, per unit solid angle, of `random_direction` returning
+    // `direction` from `origin`. Only objects that can be sampled as lights
+    // implement this and `random_direction`.
+    fn pdf_value(&self, _origin: Point3, _direction: Vec3) -> f64 {
+        0.0
+    }
+
+    // Direction from `origin` towards a random point on the object.
+    fn random_direction(&self, _origin: Point3) -> Vec3 {
+        Vec3::new(1.0, 0.0, 0.0)
+    }
+
+    // A point picked uniformly over the object's surface, as a hit from the
+    // side its normal points to, and the surface's total area. Lights
+    // implement it to emit photons from; None for everything else.
+    fn sample_surface(&self) -> Option<(HitRecord<'_>, f64)> {
+        None
+    }
+
+    // Radiance arriving from infinitely far away along `direction`, which
+    // rays leaving the scene that way see besides the background. Only
+    // distant lights like the sun, which nothing can hit, have any.
+    fn distant_radiance(&self, _direction: Vec3) -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+}
+
+// Unit vector along the x, y or z axis for `axis` 0, 1 or 2.
+fn unit_axis(axis: usize) -> Vec3 {
+    let mut v = Vec3::new(0.0, 0.0, 0.0);
+    v[axis] = 1.0;
+    v
 }
 
-#[derive(Default)]
+// Lets an object be shared, e.g. by the world and the list of lights.
+impl<H: Hittable + ?Sized> Hittable for Arc<H> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        (**self).hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        (**self).bounding_box(time0, time1)
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        (**self).pdf_value(origin, direction)
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        (**self).random_direction(origin)
+    }
+
+    fn sample_surface(&self) -> Option<(HitRecord<'_>, f64)> {
+        (**self).sample_surface()
+    }
+
+    fn distant_radiance(&self, direction: Vec3) -> Color {
+        (**self).distant_radiance(direction)
+    }
+}
+
+// Cloning a list shares its objects with the copy.
+#[derive(Default, Clone)]
 pub struct HittableList {
-    pub objects: Vec<Box<dyn Hittable>>,
+    pub objects: Vec<Arc<dyn Hittable>>,
 }
 
 impl HittableList {
     pub fn add(&mut self, object: impl Hittable + 'static) {
-        self.objects.push(Box::new(object));
+        self.objects.push(Arc::new(object));
+    }
+
+    pub fn clear(&mut self) {
+        self.objects.clear();
+    }
+
+    // Takes out the object at `index`, shifting the ones after it down.
+    // Panics if `index` is out of bounds.
+    pub fn remove(&mut self, index: usize) -> Arc<dyn Hittable> {
+        self.objects.remove(index)
     }
 }
 
 impl Hittable for HittableList {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         let mut closest_so_far = t_max;
         let mut hit_anything: Option<HitRecord> = None;
         for h in self.objects.iter() {
@@ -39,4 +151,95 @@ impl Hittable for HittableList {
 
         hit_anything
     }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let mut output_box: Option<Aabb> = None;
+        for h in self.objects.iter() {
+            let bbox = h.bounding_box(time0, time1)?;
+            output_box = Some(match output_box {
+                Some(b) => surrounding_box(b, bbox),
+                None => bbox,
+            });
+        }
+
+        output_box
+    }
+
+    // Picks one of the objects uniformly, so the density is the average of
+    // theirs.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+        let sum: f64 = self
+            .objects
+            .iter()
+            .map(|object| object.pdf_value(origin, direction))
+            .sum();
+
+        sum / self.objects.len() as f64
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        // Any direction will do, as pdf_value gives it no weight.
+        if self.objects.is_empty() {
+            return Vec3::new(1.0, 0.0, 0.0);
+        }
+        let index = (random_float() * self.objects.len() as f64) as usize;
+        self.objects[index.min(self.objects.len() - 1)].random_direction(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        material::{diffuse_light::DiffuseLight, lambertian::Lambertian},
+        rng,
+    };
+    use sphere::Sphere;
+
+    #[test]
+    fn empty_lists_have_no_directions_to_sample() {
+        let empty = HittableList::default();
+        let origin = Point3::new(0.0, 1.0, 0.0);
+        let direction = empty.random_direction(origin);
+        assert_eq!(direction.length(), 1.0);
+        assert_eq!(empty.pdf_value(origin, direction), 0.0);
+    }
+
+    #[test]
+    fn sampled_directions_point_at_the_objects() {
+        rng::seed_thread(1);
+        let mut lights = HittableList::default();
+        let light = DiffuseLight::new(Color::from(1.0));
+        lights.add(Sphere::new(Point3::new(5.0, 0.0, 0.0), 1.0, light.clone()));
+        lights.add(Sphere::new(Point3::new(-5.0, 0.0, 0.0), 1.0, light));
+
+        let origin = Point3::from(0.0);
+        for _ in 0..100 {
+            let direction = lights.random_direction(origin);
+            assert!(lights.pdf_value(origin, direction) > 0.0);
+        }
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        assert_eq!(lights.pdf_value(origin, up), 0.0);
+    }
+
+    #[test]
+    fn clones_share_their_objects() {
+        let mut world = HittableList::default();
+        let grey = Lambertian::new(Color::from(0.5));
+        world.add(Sphere::new(Point3::new(0.0, 0.0, -2.0), 0.5, grey.clone()));
+        world.add(Sphere::new(Point3::new(0.0, 0.0, -4.0), 1.0, grey));
+        let copy = world.clone();
+
+        assert_eq!(copy.objects.len(), 2);
+        for (a, b) in world.objects.iter().zip(&copy.objects) {
+            assert!(Arc::ptr_eq(a, b));
+        }
+        let ray = Ray::new(Point3::from(0.0), Vec3::new(0.0, 0.0, -1.0), None);
+        let hit = |list: &HittableList| list.hit(&ray, 0.001, f64::MAX).map(|hit| (hit.t, hit.p));
+        assert_eq!(hit(&copy), hit(&world));
+        assert_eq!(hit(&copy).unwrap().0, 1.5);
+    }
 }
@@ -1,6 +1,14 @@
-use crate::{material::Material, ray::Ray, Point3};
+use crate::{
+    aabb::{surrounding_box, Aabb},
+    material::Material,
+    ray::Ray,
+    Point3, Vec3,
+};
 
-use super::{HitRecord, Hittable};
+use super::{
+    sphere::{sphere_tangents, sphere_uv},
+    HitRecord, Hittable,
+};
 
 pub struct MovingSphere<M: Material> {
     pub center0: Point3,
@@ -30,14 +38,20 @@ impl<M: Material> MovingSphere<M> {
         }
     }
 
+    // The center moves linearly from center0 at time0 to center1 at time1.
+    // A sphere with an empty time range stays at center0 instead of
+    // producing NaNs.
     pub fn center(&self, time: f64) -> Point3 {
+        if self.time1 == self.time0 {
+            return self.center0;
+        }
         self.center0
             + (self.center1 - self.center0) * ((time - self.time0) / (self.time1 - self.time0))
     }
 }
 
 impl<M: Material> Hittable for MovingSphere<M> {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         let oc = ray.origin - self.center(ray.time);
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
@@ -60,6 +74,8 @@ impl<M: Material> Hittable for MovingSphere<M> {
 
         let p = ray.at(root);
         let outward_normal = (p - self.center(ray.time)) / self.radius;
+        let (u, v) = sphere_uv(outward_normal);
+        let (tangent, bitangent) = sphere_tangents(outward_normal);
         let front_face = ray.direction.dot(outward_normal) < 0.0;
         let normal = if front_face {
             outward_normal
@@ -72,7 +88,18 @@ impl<M: Material> Hittable for MovingSphere<M> {
             p: p,
             material: &self.material,
             normal: normal,
+            u,
+            v,
+            tangent,
+            bitangent,
             front_face: front_face,
         })
     }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let r = Vec3::from(self.radius);
+        let box0 = Aabb::new(self.center(time0) - r, self.center(time0) + r);
+        let box1 = Aabb::new(self.center(time1) - r, self.center(time1) + r);
+        Some(surrounding_box(box0, box1))
+    }
 }
@@ -0,0 +1,142 @@
+use crate::{aabb::Aabb, material::Material, ray::Ray, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+pub struct Plane<M: Material> {
+    pub point: Point3,
+    pub normal: Vec3,
+    pub material: M,
+    // Orthonormal axes spanning the plane, used for UV coordinates.
+    u_axis: Vec3,
+    v_axis: Vec3,
+}
+
+impl<M: Material> Plane<M> {
+    pub fn new(point: Point3, normal: Vec3, material: M) -> Self {
+        let normal = normal.unit_vector();
+        // Any vector not parallel to the normal works as a seed for the axes.
+        let seed = if normal.x().abs() > 0.9 {
+            Vec3::new(0.0, 1.0, 0.0)
+        } else {
+            Vec3::new(1.0, 0.0, 0.0)
+        };
+        let u_axis = seed.cross(normal).unit_vector();
+        let v_axis = normal.cross(u_axis);
+
+        Self {
+            point,
+            normal,
+            material,
+            u_axis,
+            v_axis,
+        }
+    }
+}
+
+impl<M: Material> Hittable for Plane<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Solve dot(origin + t * direction - point, normal) = 0 for t.
+        let denom = ray.direction.dot(self.normal);
+        if denom.abs() < 1e-8 {
+            // The ray runs parallel to the plane.
+            return None;
+        }
+
+        let t = (self.point - ray.origin).dot(self.normal) / denom;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        let p = ray.at(t);
+        let local = p - self.point;
+        let front_face = denom < 0.0;
+        let normal = if front_face {
+            self.normal
+        } else {
+            -self.normal
+        };
+
+        Some(HitRecord {
+            t,
+            p,
+            material: &self.material,
+            normal,
+            u: local.dot(self.u_axis),
+            v: local.dot(self.v_axis),
+            tangent: self.u_axis,
+            bitangent: self.v_axis,
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        // Infinite in extent, so it can't be placed in a BVH.
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, Color};
+
+    fn ground() -> Plane<Lambertian> {
+        Plane::new(
+            Point3::new(0.0, -0.5, 0.0),
+            Vec3::new(0.0, 2.0, 0.0),
+            Lambertian::new(Color::from(0.5)),
+        )
+    }
+
+    #[test]
+    fn rays_from_either_side_hit_the_plane() {
+        let plane = ground();
+
+        let down = Ray::new(Point3::new(1.0, 1.5, 2.0), Vec3::new(0.0, -1.0, 0.0), None);
+        let hit = plane.hit(&down, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(hit.t, 2.0);
+        assert_eq!(hit.p, Point3::new(1.0, -0.5, 2.0));
+        assert_eq!(hit.normal, Vec3::new(0.0, 1.0, 0.0));
+        assert!(hit.front_face);
+
+        let up = Ray::new(Point3::new(0.0, -1.5, 0.0), Vec3::new(0.0, 2.0, 0.0), None);
+        let hit = plane.hit(&up, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(hit.t, 0.5);
+        assert_eq!(hit.normal, Vec3::new(0.0, -1.0, 0.0));
+        assert!(!hit.front_face);
+    }
+
+    #[test]
+    fn rays_away_from_or_along_the_plane_miss() {
+        let plane = ground();
+
+        let away = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.3, 1.0, 0.0), None);
+        assert!(plane.hit(&away, 0.001, f64::INFINITY).is_none());
+
+        // The plane is 1.5 away, beyond t_max.
+        let short = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0), None);
+        assert!(plane.hit(&short, 0.001, 1.0).is_none());
+
+        let parallel = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 1.0), None);
+        assert!(plane.hit(&parallel, 0.001, f64::INFINITY).is_none());
+        let inside = Ray::new(Point3::new(0.0, -0.5, 0.0), Vec3::new(1.0, 0.0, 0.0), None);
+        assert!(plane.hit(&inside, 0.001, f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn uvs_are_distances_along_the_plane() {
+        let plane = ground();
+        assert!(plane.bounding_box(0.0, 1.0).is_none());
+
+        let ray =
+            |x: f64, z: f64| Ray::new(Point3::new(x, 1.0, z), Vec3::new(0.0, -1.0, 0.0), None);
+        let origin = plane.hit(&ray(0.0, 0.0), 0.001, f64::INFINITY).unwrap();
+        assert_eq!((origin.u, origin.v), (0.0, 0.0));
+
+        // Points 3 apart on the plane are 3 apart in UV too.
+        let hit = plane.hit(&ray(3.0, 0.0), 0.001, f64::INFINITY).unwrap();
+        assert!((hit.u.hypot(hit.v) - 3.0).abs() < 1e-12);
+        let hit = plane.hit(&ray(0.0, -3.0), 0.001, f64::INFINITY).unwrap();
+        assert!((hit.u.hypot(hit.v) - 3.0).abs() < 1e-12);
+    }
+}
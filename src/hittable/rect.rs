@@ -0,0 +1,360 @@
+use crate::{
+    aabb::Aabb, material::Material, random_float, random_float_between, ray::Ray, Point3, Vec3,
+};
+
+use super::{unit_axis, HitRecord, Hittable};
+
+// Rectangle in the plane z = k, spanning [x0, x1] x [y0, y1].
+pub struct XyRect<M: Material> {
+    pub x0: f64,
+    pub x1: f64,
+    pub y0: f64,
+    pub y1: f64,
+    pub k: f64,
+    pub material: M,
+}
+
+// Rectangle in the plane y = k, spanning [x0, x1] x [z0, z1].
+pub struct XzRect<M: Material> {
+    pub x0: f64,
+    pub x1: f64,
+    pub z0: f64,
+    pub z1: f64,
+    pub k: f64,
+    pub material: M,
+}
+
+// Rectangle in the plane x = k, spanning [y0, y1] x [z0, z1].
+pub struct YzRect<M: Material> {
+    pub y0: f64,
+    pub y1: f64,
+    pub z0: f64,
+    pub z1: f64,
+    pub k: f64,
+    pub material: M,
+}
+
+impl<M: Material> XyRect<M> {
+    pub fn new(x0: f64, x1: f64, y0: f64, y1: f64, k: f64, material: M) -> Self {
+        Self {
+            x0,
+            x1,
+            y0,
+            y1,
+            k,
+            material,
+        }
+    }
+}
+
+impl<M: Material> XzRect<M> {
+    pub fn new(x0: f64, x1: f64, z0: f64, z1: f64, k: f64, material: M) -> Self {
+        Self {
+            x0,
+            x1,
+            z0,
+            z1,
+            k,
+            material,
+        }
+    }
+}
+
+impl<M: Material> YzRect<M> {
+    pub fn new(y0: f64, y1: f64, z0: f64, z1: f64, k: f64, material: M) -> Self {
+        Self {
+            y0,
+            y1,
+            z0,
+            z1,
+            k,
+            material,
+        }
+    }
+}
+
+// Axis layout of a rectangle: the fixed axis the plane is normal to, and
+// the two in-plane axes in the order used for (u, v).
+struct Axes {
+    k: usize,
+    a: usize,
+    b: usize,
+}
+
+const XY: Axes = Axes { k: 2, a: 0, b: 1 };
+const XZ: Axes = Axes { k: 1, a: 0, b: 2 };
+const YZ: Axes = Axes { k: 0, a: 1, b: 2 };
+
+#[allow(clippy::too_many_arguments)]
+fn hit_rect<'a>(
+    axes: &Axes,
+    (a0, a1): (f64, f64),
+    (b0, b1): (f64, f64),
+    k: f64,
+    material: &'a dyn Material,
+    ray: &Ray,
+    t_min: f64,
+    t_max: f64,
+) -> Option<HitRecord<'a>> {
+    let t = (k - ray.origin[axes.k]) / ray.direction[axes.k];
+    // Also rejects NaN for rays parallel to the plane.
+    if !(t_min..=t_max).contains(&t) {
+        return None;
+    }
+
+    let p = ray.at(t);
+    let (a, b) = (p[axes.a], p[axes.b]);
+    if a < a0 || a > a1 || b < b0 || b > b1 {
+        return None;
+    }
+
+    let mut outward_normal = Vec3::new(0.0, 0.0, 0.0);
+    outward_normal[axes.k] = 1.0;
+    let front_face = ray.direction.dot(outward_normal) < 0.0;
+    let normal = if front_face {
+        outward_normal
+    } else {
+        -outward_normal
+    };
+
+    Some(HitRecord {
+        t,
+        p,
+        material,
+        normal,
+        u: (a - a0) / (a1 - a0),
+        v: (b - b0) / (b1 - b0),
+        tangent: unit_axis(axes.a),
+        bitangent: unit_axis(axes.b),
+        front_face,
+    })
+}
+
+fn rect_box(axes: &Axes, (a0, a1): (f64, f64), (b0, b1): (f64, f64), k: f64) -> Aabb {
+    let mut p0 = Point3::new(0.0, 0.0, 0.0);
+    let mut p1 = Point3::new(0.0, 0.0, 0.0);
+    p0[axes.a] = a0;
+    p1[axes.a] = a1;
+    p0[axes.b] = b0;
+    p1[axes.b] = b1;
+    p0[axes.k] = k;
+    p1[axes.k] = k;
+
+    // Flat along the fixed axis, so pad it to keep the slab test working.
+    Aabb::new(p0, p1).padded()
+}
+
+// Converts the area density of a uniformly sampled rectangle into a density
+// over the solid angle seen from `origin`.
+fn rect_pdf_value(
+    rect: &dyn Hittable,
+    axes: &Axes,
+    (a0, a1): (f64, f64),
+    (b0, b1): (f64, f64),
+    origin: Point3,
+    direction: Vec3,
+) -> f64 {
+    let Some(rec) = rect.hit(&Ray::new(origin, direction, None), 0.001, f64::INFINITY) else {
+        return 0.0;
+    };
+
+    let area = (a1 - a0) * (b1 - b0);
+    let distance_squared = rec.t * rec.t * direction.length_squared();
+    let cosine = (direction[axes.k] / direction.length()).abs();
+
+    distance_squared / (cosine * area)
+}
+
+fn rect_random_direction(
+    axes: &Axes,
+    (a0, a1): (f64, f64),
+    (b0, b1): (f64, f64),
+    k: f64,
+    origin: Point3,
+) -> Vec3 {
+    let mut p = Point3::new(0.0, 0.0, 0.0);
+    p[axes.a] = random_float_between(a0, a1);
+    p[axes.b] = random_float_between(b0, b1);
+    p[axes.k] = k;
+
+    p - origin
+}
+
+fn rect_sample_surface<'a>(
+    axes: &Axes,
+    (a0, a1): (f64, f64),
+    (b0, b1): (f64, f64),
+    k: f64,
+    material: &'a dyn Material,
+) -> (HitRecord<'a>, f64) {
+    let (u, v) = (random_float(), random_float());
+    let mut p = Point3::new(0.0, 0.0, 0.0);
+    p[axes.a] = a0 + u * (a1 - a0);
+    p[axes.b] = b0 + v * (b1 - b0);
+    p[axes.k] = k;
+    let mut normal = Vec3::new(0.0, 0.0, 0.0);
+    normal[axes.k] = 1.0;
+
+    let rec = HitRecord {
+        t: 0.0,
+        p,
+        material,
+        normal,
+        u,
+        v,
+        tangent: unit_axis(axes.a),
+        bitangent: unit_axis(axes.b),
+        front_face: true,
+    };
+    (rec, (a1 - a0) * (b1 - b0))
+}
+
+impl<M: Material> Hittable for XyRect<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (a, b) = ((self.x0, self.x1), (self.y0, self.y1));
+        hit_rect(&XY, a, b, self.k, &self.material, ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(rect_box(
+            &XY,
+            (self.x0, self.x1),
+            (self.y0, self.y1),
+            self.k,
+        ))
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let (a, b) = ((self.x0, self.x1), (self.y0, self.y1));
+        rect_pdf_value(self, &XY, a, b, origin, direction)
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let (a, b) = ((self.x0, self.x1), (self.y0, self.y1));
+        rect_random_direction(&XY, a, b, self.k, origin)
+    }
+
+    fn sample_surface(&self) -> Option<(HitRecord<'_>, f64)> {
+        let (a, b) = ((self.x0, self.x1), (self.y0, self.y1));
+        Some(rect_sample_surface(&XY, a, b, self.k, &self.material))
+    }
+}
+
+impl<M: Material> Hittable for XzRect<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (a, b) = ((self.x0, self.x1), (self.z0, self.z1));
+        hit_rect(&XZ, a, b, self.k, &self.material, ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(rect_box(
+            &XZ,
+            (self.x0, self.x1),
+            (self.z0, self.z1),
+            self.k,
+        ))
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let (a, b) = ((self.x0, self.x1), (self.z0, self.z1));
+        rect_pdf_value(self, &XZ, a, b, origin, direction)
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let (a, b) = ((self.x0, self.x1), (self.z0, self.z1));
+        rect_random_direction(&XZ, a, b, self.k, origin)
+    }
+
+    fn sample_surface(&self) -> Option<(HitRecord<'_>, f64)> {
+        let (a, b) = ((self.x0, self.x1), (self.z0, self.z1));
+        Some(rect_sample_surface(&XZ, a, b, self.k, &self.material))
+    }
+}
+
+impl<M: Material> Hittable for YzRect<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (a, b) = ((self.y0, self.y1), (self.z0, self.z1));
+        hit_rect(&YZ, a, b, self.k, &self.material, ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(rect_box(
+            &YZ,
+            (self.y0, self.y1),
+            (self.z0, self.z1),
+            self.k,
+        ))
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        let (a, b) = ((self.y0, self.y1), (self.z0, self.z1));
+        rect_pdf_value(self, &YZ, a, b, origin, direction)
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let (a, b) = ((self.y0, self.y1), (self.z0, self.z1));
+        rect_random_direction(&YZ, a, b, self.k, origin)
+    }
+
+    fn sample_surface(&self) -> Option<(HitRecord<'_>, f64)> {
+        let (a, b) = ((self.y0, self.y1), (self.z0, self.z1));
+        Some(rect_sample_surface(&YZ, a, b, self.k, &self.material))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, Color};
+
+    fn white() -> Lambertian {
+        Lambertian::new(Color::from(0.73))
+    }
+
+    #[test]
+    fn rays_hit_each_kind_of_rectangle_inside_its_bounds() {
+        let xy = XyRect::new(0.0, 2.0, 0.0, 4.0, 5.0, white());
+        let ray = Ray::new(Point3::new(0.5, 3.0, 0.0), Vec3::new(0.0, 0.0, 1.0), None);
+        let hit = xy.hit(&ray, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(hit.t, 5.0);
+        assert_eq!((hit.u, hit.v), (0.25, 0.75));
+        assert_eq!(hit.normal, Vec3::new(0.0, 0.0, -1.0));
+        assert!(!hit.front_face);
+
+        let xz = XzRect::new(3.5, 6.5, 3.5, 6.5, 9.99, white());
+        let ray = Ray::new(Point3::new(5.0, 0.0, 5.0), Vec3::new(0.0, 1.0, 0.0), None);
+        let hit = xz.hit(&ray, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(hit.p, Point3::new(5.0, 9.99, 5.0));
+        assert_eq!((hit.u, hit.v), (0.5, 0.5));
+
+        let yz = YzRect::new(0.0, 10.0, 0.0, 10.0, 10.0, white());
+        let ray = Ray::new(Point3::new(20.0, 2.0, 8.0), Vec3::new(-2.0, 0.0, 0.0), None);
+        let hit = yz.hit(&ray, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(hit.t, 5.0);
+        assert_eq!(hit.normal, Vec3::new(1.0, 0.0, 0.0));
+        assert!(hit.front_face);
+    }
+
+    #[test]
+    fn rays_outside_the_bounds_or_along_the_plane_miss() {
+        let xz = XzRect::new(3.5, 6.5, 3.5, 6.5, 9.99, white());
+        let up = |x: f64, z: f64| Ray::new(Point3::new(x, 0.0, z), Vec3::new(0.0, 1.0, 0.0), None);
+        assert!(xz.hit(&up(3.4, 5.0), 0.001, f64::INFINITY).is_none());
+        assert!(xz.hit(&up(5.0, 6.6), 0.001, f64::INFINITY).is_none());
+        assert!(xz.hit(&up(5.0, 5.0), 0.001, 9.0).is_none());
+
+        let along = Ray::new(Point3::new(0.0, 9.99, 5.0), Vec3::new(1.0, 0.0, 0.0), None);
+        assert!(xz.hit(&along, 0.001, f64::INFINITY).is_none());
+        let parallel = Ray::new(Point3::new(0.0, 5.0, 5.0), Vec3::new(1.0, 0.0, 0.0), None);
+        assert!(xz.hit(&parallel, 0.001, f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn bounding_boxes_are_padded_along_the_fixed_axis() {
+        let xz = XzRect::new(3.5, 6.5, 3.5, 6.5, 9.99, white());
+        let bbox = xz.bounding_box(0.0, 1.0).unwrap();
+        assert_eq!((bbox.min.x(), bbox.max.x()), (3.5, 6.5));
+        assert_eq!((bbox.min.z(), bbox.max.z()), (3.5, 6.5));
+        assert!(bbox.min.y() < 9.99 && 9.99 < bbox.max.y());
+    }
+}
@@ -0,0 +1,493 @@
+use crate::{
+    aabb::{surrounding_box, Aabb},
+    material::Material,
+    ray::Ray,
+    Point3, Vec3,
+};
+
+use super::{HitRecord, Hittable};
+
+// A surface given by its signed distance function: negative inside, positive
+// outside, and never more than the actual distance to the surface, so a ray
+// can safely advance by it.
+pub trait Sdf: Send + Sync {
+    fn distance(&self, p: Point3) -> f64;
+
+    // Box enclosing every point with a negative distance.
+    fn bounding_box(&self) -> Aabb;
+
+    // Surface coordinates of the point `p` on the surface, for texturing.
+    fn uv(&self, _p: Point3) -> (f64, f64) {
+        (0.0, 0.0)
+    }
+}
+
+// Renders an `Sdf` by sphere marching: starting where the ray enters the
+// bounding box, it repeatedly advances by the distance to the surface, at
+// most `max_step`, until it gets within `epsilon` of it. Lower `max_step` for
+// functions that overestimate the distance, such as heavy smooth unions.
+pub struct SdfMarcher<S: Sdf, M: Material> {
+    pub sdf: S,
+    pub max_step: f64,
+    pub material: M,
+    pub epsilon: f64,
+}
+
+impl<S: Sdf, M: Material> SdfMarcher<S, M> {
+    const MAX_STEPS: u32 = 1000;
+
+    pub fn new(sdf: S, max_step: f64, material: M) -> Self {
+        Self {
+            sdf,
+            max_step,
+            material,
+            epsilon: 1e-4,
+        }
+    }
+
+    // Stops marching `epsilon` away from the surface instead of 1e-4, which
+    // also sets the spacing the normals are estimated with. Fractals have
+    // detail at every scale; around a pixel's footprint smooths them out
+    // instead of rendering them as noise.
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    // Gradient of the distance by central differences.
+    fn normal(&self, p: Point3) -> Vec3 {
+        let h = self.epsilon;
+        let dx = Vec3::new(h, 0.0, 0.0);
+        let dy = Vec3::new(0.0, h, 0.0);
+        let dz = Vec3::new(0.0, 0.0, h);
+        Vec3::new(
+            self.sdf.distance(p + dx) - self.sdf.distance(p - dx),
+            self.sdf.distance(p + dy) - self.sdf.distance(p - dy),
+            self.sdf.distance(p + dz) - self.sdf.distance(p - dz),
+        )
+        .unit_vector()
+    }
+}
+
+impl<S: Sdf, M: Material> Hittable for SdfMarcher<S, M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (t_start, t_end) = self
+            .sdf
+            .bounding_box()
+            .padded()
+            .hit_interval(ray, t_min, t_max)?;
+
+        // March in units of distance; t_start and t_end are in units of the
+        // ray's direction.
+        let length = ray.direction.length();
+        let (mut s, s_end) = (t_start * length, t_end * length);
+        let direction = ray.direction / length;
+
+        // Rays travelling inside the object march on the negated distance.
+        // Rays starting on the surface (e.g. scattered ones) only count as
+        // hitting once they have moved clear of it.
+        let inside = self.sdf.distance(ray.origin + direction * s) < 0.0;
+        let mut clear = self.sdf.distance(ray.origin).abs() >= self.epsilon;
+        let mut hit = None;
+        for _ in 0..Self::MAX_STEPS {
+            let distance = self.sdf.distance(ray.origin + direction * s);
+            let distance = if inside { -distance } else { distance };
+            if distance < self.epsilon {
+                if clear {
+                    hit = Some(s);
+                    break;
+                }
+            } else {
+                clear = true;
+            }
+            s += distance.max(self.epsilon).min(self.max_step);
+            if s > s_end {
+                break;
+            }
+        }
+        let t = hit? / length;
+
+        let p = ray.at(t);
+        let (u, v) = self.sdf.uv(p);
+        let outward_normal = self.normal(p);
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        Some(HitRecord {
+            t,
+            p,
+            material: &self.material,
+            normal,
+            u,
+            v,
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 0.0),
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        Some(self.sdf.bounding_box())
+    }
+}
+
+pub struct SdfSphere {
+    pub center: Point3,
+    pub radius: f64,
+}
+
+impl SdfSphere {
+    pub fn new(center: Point3, radius: f64) -> Self {
+        Self { center, radius }
+    }
+}
+
+impl Sdf for SdfSphere {
+    fn distance(&self, p: Point3) -> f64 {
+        self.center.distance_to(p) - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = Vec3::from(self.radius);
+        Aabb::new(self.center - r, self.center + r)
+    }
+}
+
+// Axis-aligned box reaching `half_extents` from `center` along each axis.
+pub struct SdfBox {
+    pub center: Point3,
+    pub half_extents: Vec3,
+}
+
+impl SdfBox {
+    pub fn new(center: Point3, half_extents: Vec3) -> Self {
+        Self {
+            center,
+            half_extents,
+        }
+    }
+}
+
+impl Sdf for SdfBox {
+    fn distance(&self, p: Point3) -> f64 {
+        let local = p - self.center;
+        // Distance past the box's faces along each axis.
+        let q = Vec3::new(
+            local.x().abs() - self.half_extents.x(),
+            local.y().abs() - self.half_extents.y(),
+            local.z().abs() - self.half_extents.z(),
+        );
+        let outside = Vec3::max_components(q, Vec3::from(0.0)).length();
+        let inside = q.x().max(q.y()).max(q.z()).min(0.0);
+
+        outside + inside
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(
+            self.center - self.half_extents,
+            self.center + self.half_extents,
+        )
+    }
+}
+
+pub struct SdfUnion<A: Sdf, B: Sdf> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Sdf, B: Sdf> SdfUnion<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SdfUnion<A, B> {
+    fn distance(&self, p: Point3) -> f64 {
+        self.a.distance(p).min(self.b.distance(p))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        surrounding_box(self.a.bounding_box(), self.b.bounding_box())
+    }
+
+    fn uv(&self, p: Point3) -> (f64, f64) {
+        closer_uv(&self.a, &self.b, p)
+    }
+}
+
+pub struct SdfIntersection<A: Sdf, B: Sdf> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Sdf, B: Sdf> SdfIntersection<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SdfIntersection<A, B> {
+    fn distance(&self, p: Point3) -> f64 {
+        self.a.distance(p).max(self.b.distance(p))
+    }
+
+    // The overlap of both boxes. Disjoint shapes leave an inverted box,
+    // which no ray hits.
+    fn bounding_box(&self) -> Aabb {
+        let (a, b) = (self.a.bounding_box(), self.b.bounding_box());
+        Aabb {
+            min: Point3::max_components(a.min, b.min),
+            max: Point3::min_components(a.max, b.max),
+        }
+    }
+    fn uv(&self, p: Point3) -> (f64, f64) {
+        self.a.uv(p)
+    }
+}
+
+// `a` with `b` carved out of it.
+pub struct SdfDifference<A: Sdf, B: Sdf> {
+    pub a: A,
+    pub b: B,
+}
+
+impl<A: Sdf, B: Sdf> SdfDifference<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Self { a, b }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SdfDifference<A, B> {
+    fn distance(&self, p: Point3) -> f64 {
+        self.a.distance(p).max(-self.b.distance(p))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box()
+    }
+    fn uv(&self, p: Point3) -> (f64, f64) {
+        self.a.uv(p)
+    }
+}
+
+// Union that blends the shapes together where they are closer than `k`,
+// using the polynomial smooth minimum.
+pub struct SdfSmoothUnion<A: Sdf, B: Sdf> {
+    pub a: A,
+    pub b: B,
+    pub k: f64,
+}
+
+impl<A: Sdf, B: Sdf> SdfSmoothUnion<A, B> {
+    pub fn new(a: A, b: B, k: f64) -> Self {
+        Self { a, b, k }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SdfSmoothUnion<A, B> {
+    fn distance(&self, p: Point3) -> f64 {
+        let (a, b) = (self.a.distance(p), self.b.distance(p));
+        let h = (0.5 + 0.5 * (b - a) / self.k).clamp(0.0, 1.0);
+
+        b + (a - b) * h - self.k * h * (1.0 - h)
+    }
+
+    // The blend swells the union by at most k / 4.
+    fn bounding_box(&self) -> Aabb {
+        let bbox = surrounding_box(self.a.bounding_box(), self.b.bounding_box());
+        let pad = Vec3::from(self.k / 4.0);
+        Aabb::new(bbox.min - pad, bbox.max + pad)
+    }
+
+    fn uv(&self, p: Point3) -> (f64, f64) {
+        closer_uv(&self.a, &self.b, p)
+    }
+}
+
+// Unions take their texture coordinates from the shape nearest to `p`.
+fn closer_uv(a: &impl Sdf, b: &impl Sdf, p: Point3) -> (f64, f64) {
+    if a.distance(p) <= b.distance(p) {
+        a.uv(p)
+    } else {
+        b.uv(p)
+    }
+}
+
+// The power `power` Mandelbulb around the origin: points whose orbit under
+// z -> z^power + p (in spherical coordinates) stays within `escape_radius`
+// for `iterations` steps. The distance is Quilez's estimate
+// 0.5 * ln(r) * r / dr from the orbit's radius r and its derivative dr.
+//
+// For orbit trap coloring, u holds the orbit's closest approach to the
+// origin, clamped to [0, 1]; see `OrbitTrapTexture`.
+pub struct Mandelbulb {
+    pub power: f64,
+    pub iterations: u32,
+    pub escape_radius: f64,
+}
+
+impl Mandelbulb {
+    pub fn new(power: f64, iterations: u32, escape_radius: f64) -> Self {
+        Self {
+            power,
+            iterations,
+            escape_radius,
+        }
+    }
+
+    // Distance estimate and orbit trap at `p`.
+    fn estimate(&self, p: Point3) -> (f64, f64) {
+        let mut z = p;
+        let mut dr = 1.0;
+        let mut r = z.length();
+        let mut trap = r;
+        for _ in 0..self.iterations {
+            if r > self.escape_radius {
+                break;
+            }
+            // Spherical coordinates with the pole along y, so the bulb
+            // stands upright.
+            let theta = (z.y() / r).clamp(-1.0, 1.0).acos() * self.power;
+            let phi = z.z().atan2(z.x()) * self.power;
+            dr = r.powf(self.power - 1.0) * self.power * dr + 1.0;
+            z = Vec3::new(
+                theta.sin() * phi.cos(),
+                theta.cos(),
+                theta.sin() * phi.sin(),
+            ) * r.powf(self.power)
+                + p;
+            r = z.length();
+            trap = trap.min(r);
+        }
+
+        (0.5 * r.ln() * r / dr, trap)
+    }
+}
+
+impl Sdf for Mandelbulb {
+    fn distance(&self, p: Point3) -> f64 {
+        self.estimate(p).0
+    }
+
+    // Orbits starting farther out than 2^(1 / (power - 1)) grow at every
+    // step, so the set lies within that radius.
+    fn bounding_box(&self) -> Aabb {
+        let radius = 2f64.powf(1.0 / (self.power - 1.0)).min(self.escape_radius);
+        let r = Vec3::from(radius);
+        Aabb::new(-r, r)
+    }
+
+    fn uv(&self, p: Point3) -> (f64, f64) {
+        (self.estimate(p).1.clamp(0.0, 1.0), 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, Color};
+
+    fn marched<S: Sdf>(sdf: S) -> SdfMarcher<S, Lambertian> {
+        SdfMarcher::new(sdf, 1.0, Lambertian::new(Color::from(0.5)))
+    }
+
+    #[test]
+    fn marched_spheres_are_hit_where_analytic_ones_are() {
+        let sphere = marched(SdfSphere::new(Point3::new(0.0, 1.0, 0.0), 1.0));
+
+        let ray = Ray::new(Point3::new(0.3, 1.2, -5.0), Vec3::new(0.0, 0.0, 2.0), None);
+        let hit = sphere.hit(&ray, 0.001, f64::INFINITY).unwrap();
+        let z = -f64::sqrt(1.0 - 0.3 * 0.3 - 0.2 * 0.2);
+        assert!((hit.t - (5.0 + z) / 2.0).abs() < 1e-4, "{}", hit.t);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(0.3, 0.2, z), 1e-3);
+        assert!(hit.front_face);
+
+        // From the center, the ray leaves through the far side.
+        let inside = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0), None);
+        let hit = sphere.hit(&inside, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 1.0).abs() < 1e-4, "{}", hit.t);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(-1.0, 0.0, 0.0), 1e-3);
+        assert!(!hit.front_face);
+    }
+
+    #[test]
+    fn rays_past_or_short_of_the_surface_miss() {
+        let sphere = marched(SdfSphere::new(Point3::new(0.0, 1.0, 0.0), 1.0));
+
+        let beside = Ray::new(Point3::new(1.1, 1.0, -5.0), Vec3::new(0.0, 0.0, 1.0), None);
+        assert!(sphere.hit(&beside, 0.001, f64::INFINITY).is_none());
+        let away = Ray::new(Point3::new(0.0, 1.0, -5.0), Vec3::new(0.0, 0.0, -1.0), None);
+        assert!(sphere.hit(&away, 0.001, f64::INFINITY).is_none());
+        let short = Ray::new(Point3::new(0.0, 1.0, -5.0), Vec3::new(0.0, 0.0, 1.0), None);
+        assert!(sphere.hit(&short, 0.001, 3.9).is_none());
+    }
+
+    #[test]
+    fn combinations_keep_the_right_sides() {
+        let unit = || SdfSphere::new(Point3::from(0.0), 1.0);
+        let shifted = || SdfSphere::new(Point3::new(1.0, 0.0, 0.0), 1.0);
+        let (left, middle) = (Point3::new(-0.5, 0.0, 0.0), Point3::new(0.5, 0.0, 0.0));
+        let right = Point3::new(1.5, 0.0, 0.0);
+
+        let union = SdfUnion::new(unit(), shifted());
+        assert!(union.distance(left) < 0.0 && union.distance(right) < 0.0);
+        let intersection = SdfIntersection::new(unit(), shifted());
+        assert!(intersection.distance(middle) < 0.0);
+        assert!(intersection.distance(left) > 0.0 && intersection.distance(right) > 0.0);
+        let difference = SdfDifference::new(unit(), shifted());
+        assert!(difference.distance(left) < 0.0);
+        assert!(difference.distance(middle) > 0.0 && difference.distance(right) > 0.0);
+
+        // Blending fills in around the seam, but leaves far points alone.
+        let smooth = SdfSmoothUnion::new(unit(), shifted(), 0.5);
+        let seam = Point3::new(0.5, 0.9, 0.0);
+        assert!(smooth.distance(seam) < union.distance(seam));
+        let far = Point3::new(-3.0, 0.0, 0.0);
+        assert_eq!(smooth.distance(far), union.distance(far));
+
+        let cube = SdfBox::new(Point3::from(0.0), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(cube.distance(Point3::new(0.0, 0.0, 4.0)), 1.0);
+        assert_eq!(cube.distance(Point3::new(0.5, 0.0, 0.0)), -0.5);
+        assert_eq!(cube.distance(Point3::new(4.0, 6.0, 0.0)), 5.0);
+    }
+
+    #[test]
+    fn power_8_mandelbulbs_render_with_their_orbit_traps() {
+        let bulb = Mandelbulb::new(8.0, 8, 2.0);
+        assert!(bulb.distance(Point3::new(0.0, 0.0, 3.0)) > 0.0);
+        let marcher = marched(Mandelbulb::new(8.0, 8, 2.0)).with_epsilon(2e-3);
+
+        // A 16 x 16 image of the bulb seen along -z, through [-1.5, 1.5]^2.
+        let (mut hits, mut misses) = (0, 0);
+        for y in 0..16 {
+            for x in 0..16 {
+                let (px, py) = (
+                    -1.5 + 3.0 * (x as f64 + 0.5) / 16.0,
+                    -1.5 + 3.0 * (y as f64 + 0.5) / 16.0,
+                );
+                let ray = Ray::new(Point3::new(px, py, 3.0), Vec3::new(0.0, 0.0, -1.0), None);
+                let Some(hit) = marcher.hit(&ray, 0.001, f64::INFINITY) else {
+                    misses += 1;
+                    continue;
+                };
+                hits += 1;
+                assert!(hit.p.length() < 1.11, "{:?}", hit.p);
+                assert!(bulb.distance(hit.p).abs() < 0.01);
+                assert!((0.0..=1.0).contains(&hit.u));
+            }
+        }
+        // The bulb covers the middle of the image but not the corners.
+        assert!(hits > 60 && misses > 60, "{hits} hits, {misses} misses");
+        let center = Ray::new(
+            Point3::new(0.01, 0.02, 3.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            None,
+        );
+        assert!(marcher.hit(&center, 0.001, f64::INFINITY).is_some());
+    }
+}
@@ -1,4 +1,6 @@
-use crate::{material::Material, ray::Ray, Point3};
+use std::f64::consts::PI;
+
+use crate::{aabb::Aabb, material::Material, math::onb::Onb, ray::Ray, sampling, Point3, Vec3};
 
 use super::{HitRecord, Hittable};
 
@@ -16,10 +18,22 @@ impl<M: Material> Sphere<M> {
             material,
         }
     }
+
+    // Cosine of the half-angle of the cone the sphere subtends from
+    // `origin`, or None if `origin` is inside it.
+    fn cos_theta_max(&self, origin: Point3) -> Option<f64> {
+        let distance_squared = (self.center - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+        if distance_squared <= radius_squared {
+            return None;
+        }
+
+        Some(f64::sqrt(1.0 - radius_squared / distance_squared))
+    }
 }
 
 impl<M: Material> Hittable for Sphere<M> {
-    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
         let oc = ray.origin - self.center;
         let a = ray.direction.length_squared();
         let half_b = oc.dot(ray.direction);
@@ -42,6 +56,8 @@ impl<M: Material> Hittable for Sphere<M> {
 
         let p = ray.at(root);
         let outward_normal = (p - self.center) / self.radius;
+        let (u, v) = sphere_uv(outward_normal);
+        let (tangent, bitangent) = sphere_tangents(outward_normal);
         let front_face = ray.direction.dot(outward_normal) < 0.0;
         let normal = if front_face {
             outward_normal
@@ -54,7 +70,167 @@ impl<M: Material> Hittable for Sphere<M> {
             p: p,
             material: &self.material,
             normal: normal,
+            u,
+            v,
+            tangent,
+            bitangent,
             front_face: front_face,
         })
     }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        let r = Vec3::from(self.radius);
+        Some(Aabb::new(self.center - r, self.center + r))
+    }
+
+    // Directions are sampled uniformly over the cone of directions that
+    // hit the sphere, which wastes no samples on its far side. From inside,
+    // every direction hits it.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        if self
+            .hit(&Ray::new(origin, direction, None), 0.001, f64::MAX)
+            .is_none()
+        {
+            return 0.0;
+        }
+        match self.cos_theta_max(origin) {
+            Some(cos_theta_max) => 1.0 / (2.0 * PI * (1.0 - cos_theta_max)),
+            None => 1.0 / (4.0 * PI),
+        }
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        match self.cos_theta_max(origin) {
+            Some(cos_theta_max) => {
+                Onb::build_from_w(self.center - origin).local(sampling::uniform_cone(cos_theta_max))
+            }
+            None => sampling::uniform_sphere(),
+        }
+    }
+
+    fn sample_surface(&self) -> Option<(HitRecord<'_>, f64)> {
+        let normal = sampling::uniform_sphere();
+        let (u, v) = sphere_uv(normal);
+        let (tangent, bitangent) = sphere_tangents(normal);
+        let rec = HitRecord {
+            t: 0.0,
+            p: self.center + normal * self.radius,
+            material: &self.material,
+            normal,
+            u,
+            v,
+            tangent,
+            bitangent,
+            front_face: true,
+        };
+        Some((rec, 4.0 * PI * self.radius * self.radius))
+    }
+}
+
+// Maps a point on the unit sphere to (u, v) in [0, 1]: u is the angle
+// around the Y axis starting from -X, v the angle from -Y up to +Y.
+pub fn sphere_uv(p: Point3) -> (f64, f64) {
+    let theta = f64::acos((-p.y()).clamp(-1.0, 1.0));
+    let phi = f64::atan2(-p.z(), p.x()) + PI;
+
+    (phi / (2.0 * PI), theta / PI)
+}
+
+// Directions in which the u and v of `sphere_uv` grow at the point `p` on
+// the unit sphere: east along the circle of latitude and north along the
+// meridian. At the poles, where u is undefined, any east will do.
+pub fn sphere_tangents(p: Point3) -> (Vec3, Vec3) {
+    let east = Vec3::new(p.z(), 0.0, -p.x());
+    let east = if east.length_squared() > 1e-12 {
+        east.unit_vector()
+    } else {
+        Vec3::new(1.0, 0.0, 0.0)
+    };
+
+    (east, p.cross(east))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, rng, Color};
+
+    fn unit_sphere() -> Sphere<Lambertian> {
+        Sphere::new(Point3::from(0.0), 1.0, Lambertian::new(Color::from(0.5)))
+    }
+
+    #[test]
+    fn the_pdf_covers_the_solid_angle_the_sphere_subtends() {
+        rng::seed_thread(9);
+        let sphere = unit_sphere();
+        // Two radii away, the sphere subtends a cone of half-angle 30°.
+        let origin = Point3::new(0.0, 0.0, 2.0);
+        let towards = Vec3::new(0.0, 0.0, -1.0);
+        let solid_angle = 2.0 * PI * (1.0 - f64::sqrt(3.0) / 2.0);
+
+        let n = 200_000;
+        let (mut hits, mut integral) = (0, 0.0);
+        for _ in 0..n {
+            let direction = sampling::uniform_hemisphere(towards).unit_vector();
+            let pdf = sphere.pdf_value(origin, direction);
+            if pdf > 0.0 {
+                hits += 1;
+                assert!((pdf - 1.0 / solid_angle).abs() < 1e-9);
+            }
+            integral += pdf;
+        }
+        let hemisphere = 2.0 * PI;
+        let estimate = hits as f64 / n as f64 * hemisphere;
+        assert!((estimate - solid_angle).abs() < 0.01 * solid_angle);
+        let integral = integral / n as f64 * hemisphere;
+        assert!((integral - 1.0).abs() < 0.01, "integral {}", integral);
+    }
+
+    #[test]
+    fn sampled_directions_stay_within_the_cone() {
+        rng::seed_thread(10);
+        let sphere = unit_sphere();
+        let origin = Point3::new(3.0, -1.0, 2.0);
+        let towards = (sphere.center - origin).unit_vector();
+        let cos_theta_max = sphere.cos_theta_max(origin).unwrap();
+
+        for _ in 0..1000 {
+            let direction = sphere.random_direction(origin);
+            assert!((direction.length() - 1.0).abs() < 1e-9);
+            assert!(direction.dot(towards) >= cos_theta_max - 1e-9);
+            assert!(sphere.pdf_value(origin, direction) > 0.0);
+        }
+    }
+
+    #[test]
+    fn from_inside_every_direction_is_equally_likely() {
+        rng::seed_thread(11);
+        let sphere = unit_sphere();
+        let origin = Point3::new(0.2, 0.3, -0.1);
+
+        assert!(sphere.cos_theta_max(origin).is_none());
+        for _ in 0..100 {
+            let direction = sphere.random_direction(origin);
+            assert_eq!(sphere.pdf_value(origin, direction), 1.0 / (4.0 * PI));
+        }
+    }
+
+    #[test]
+    fn tangents_point_where_u_and_v_grow() {
+        rng::seed_thread(15);
+        let h = 1e-6;
+        for _ in 0..1000 {
+            let p = sampling::uniform_sphere();
+            let (tangent, bitangent) = sphere_tangents(p);
+            assert!((tangent.length() - 1.0).abs() < 1e-9);
+            assert!(tangent.dot(p).abs() < 1e-9 && bitangent.dot(p).abs() < 1e-9);
+
+            let (u, v) = sphere_uv(p);
+            let (u_east, _) = sphere_uv((p + tangent * h).unit_vector());
+            let (_, v_north) = sphere_uv((p + bitangent * h).unit_vector());
+            // Across the seam at u = 0 u wraps around from 1.
+            assert!(u_east > u || u - u_east > 0.5);
+            assert!(v_north > v);
+        }
+    }
 }
@@ -0,0 +1,78 @@
+use std::f64::consts::PI;
+
+use crate::{aabb::Aabb, math::onb::Onb, ray::Ray, sampling, Color, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+// A light infinitely far away that covers a small disk of the sky, like
+// the sun. Nothing can hit it, so it only belongs in the `LightList`, not
+// the world: surfaces see it through their shadow rays, and rays leaving
+// the scene towards it pick up its radiance through `distant_radiance`.
+//
+// `irradiance` is the light arriving on a surface facing the sun, spread
+// evenly over the disk. The disk's size sets how soft shadows are: the real
+// sun's angular radius of about 0.0047 radians gives crisp shadows with a
+// thin penumbra, and larger ones the soft, blurry shadows of a hazy day.
+// The sun emits no photons for photon mapping.
+#[derive(Debug, Clone, Copy)]
+pub struct SunLight {
+    // Towards the sun, normalized.
+    pub direction: Vec3,
+    pub angular_radius_radians: f64,
+    pub irradiance: Color,
+}
+
+impl SunLight {
+    pub fn new(direction: Vec3, angular_radius_radians: f64, irradiance: Color) -> Self {
+        Self {
+            direction: direction.unit_vector(),
+            // A point light's density is infinite, so keep the disk from
+            // shrinking to one.
+            angular_radius_radians: angular_radius_radians.clamp(1e-4, PI / 2.0),
+            irradiance,
+        }
+    }
+
+    fn cos_theta_max(&self) -> f64 {
+        self.angular_radius_radians.cos()
+    }
+
+    fn solid_angle(&self) -> f64 {
+        2.0 * PI * (1.0 - self.cos_theta_max())
+    }
+
+    fn covers(&self, direction: Vec3) -> bool {
+        direction.unit_vector().dot(self.direction) >= self.cos_theta_max()
+    }
+}
+
+impl Hittable for SunLight {
+    fn hit(&self, _ray: &Ray, _t_min: f64, _t_max: f64) -> Option<HitRecord<'_>> {
+        None
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        None
+    }
+
+    // Uniform over the disk, wherever `origin` is.
+    fn pdf_value(&self, _origin: Point3, direction: Vec3) -> f64 {
+        if self.covers(direction) {
+            1.0 / self.solid_angle()
+        } else {
+            0.0
+        }
+    }
+
+    fn random_direction(&self, _origin: Point3) -> Vec3 {
+        Onb::build_from_w(self.direction).local(sampling::uniform_cone(self.cos_theta_max()))
+    }
+
+    fn distant_radiance(&self, direction: Vec3) -> Color {
+        if self.covers(direction) {
+            self.irradiance / self.solid_angle()
+        } else {
+            Color::new(0.0, 0.0, 0.0)
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::f64::consts::PI;
+
+use crate::{aabb::Aabb, material::Material, math::roots::solve_quartic, ray::Ray, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+// Ring around the y axis through `center`: a tube of `minor_radius` whose
+// middle runs along a circle of `major_radius`.
+pub struct Torus<M: Material> {
+    pub center: Point3,
+    pub major_radius: f64,
+    pub minor_radius: f64,
+    pub material: M,
+}
+
+impl<M: Material> Torus<M> {
+    pub fn new(center: Point3, major_radius: f64, minor_radius: f64, material: M) -> Self {
+        Self {
+            center,
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+
+    // Value and derivative along the ray of the torus' implicit function,
+    // for polishing roots with Newton's method.
+    fn implicit(&self, o: Vec3, d: Vec3, t: f64) -> (f64, f64) {
+        let p = o + d * t;
+        let r2 = self.major_radius * self.major_radius;
+        let s = p.length_squared() + r2 - self.minor_radius * self.minor_radius;
+        let value = s * s - 4.0 * r2 * (p.x() * p.x() + p.z() * p.z());
+        let gradient = Vec3::new(
+            4.0 * p.x() * (s - 2.0 * r2),
+            4.0 * p.y() * s,
+            4.0 * p.z() * (s - 2.0 * r2),
+        );
+
+        (value, gradient.dot(d))
+    }
+}
+
+impl<M: Material> Hittable for Torus<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let (big_r, small_r) = (self.major_radius, self.minor_radius);
+
+        // Work with a unit direction, and start the ray next to the torus'
+        // bounding sphere: far away origins make the quartic's coefficients
+        // huge and its roots inaccurate.
+        let length = ray.direction.length();
+        let d = ray.direction / length;
+        let mut o = ray.origin - self.center;
+        let start = (-o.dot(d) - (big_r + small_r)).max(0.0);
+        o += d * start;
+
+        // |p|^2 + R^2 - r^2 = 2 R sqrt(x^2 + z^2), squared, with p = o + t d:
+        // (t^2 + 2 f t + g)^2 = 4 R^2 ((ox + t dx)^2 + (oz + t dz)^2).
+        let f = o.dot(d);
+        let g = o.length_squared() + big_r * big_r - small_r * small_r;
+        let four_r2 = 4.0 * big_r * big_r;
+        let roots = solve_quartic([
+            g * g - four_r2 * (o.x() * o.x() + o.z() * o.z()),
+            4.0 * f * g - 2.0 * four_r2 * (o.x() * d.x() + o.z() * d.z()),
+            4.0 * f * f + 2.0 * g - four_r2 * (d.x() * d.x() + d.z() * d.z()),
+            4.0 * f,
+            1.0,
+        ]);
+
+        let mut closest: Option<f64> = None;
+        for &root in roots.as_slice() {
+            // Two Newton steps clean up the closed-form root.
+            let mut t = root;
+            for _ in 0..2 {
+                let (value, slope) = self.implicit(o, d, t);
+                if slope.abs() > 1e-12 {
+                    t -= value / slope;
+                }
+            }
+            // Back to the parameter of the caller's ray.
+            let t = (t + start) / length;
+            if t >= t_min && t <= closest.unwrap_or(t_max) {
+                closest = Some(t);
+            }
+        }
+        let t = closest?;
+
+        // The normal points away from the nearest point on the tube's
+        // center circle.
+        let p = ray.at(t);
+        let local = p - self.center;
+        let radial = Vec3::new(local.x(), 0.0, local.z());
+        let ring_distance = radial.length();
+        let ring_point = if ring_distance > 0.0 {
+            radial * (big_r / ring_distance)
+        } else {
+            Vec3::new(big_r, 0.0, 0.0)
+        };
+        let outward_normal = (local - ring_point) / small_r;
+        let front_face = ray.direction.dot(outward_normal) < 0.0;
+        let normal = if front_face {
+            outward_normal
+        } else {
+            -outward_normal
+        };
+
+        // phi goes around the y axis, theta around the tube.
+        let phi = local.z().atan2(local.x()) + PI;
+        let theta = local.y().atan2(ring_distance - big_r) + PI;
+
+        Some(HitRecord {
+            t,
+            p,
+            material: &self.material,
+            normal,
+            u: phi / (2.0 * PI),
+            v: theta / (2.0 * PI),
+            tangent: Vec3::new(0.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 0.0),
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        let outer = self.major_radius + self.minor_radius;
+        let extent = Vec3::new(outer, self.minor_radius, outer);
+        Some(Aabb::new(self.center - extent, self.center + extent))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, rng, Color};
+
+    // A ring of radius 2 with a tube of radius 0.5, around the origin.
+    fn torus() -> Torus<Lambertian> {
+        Torus::new(
+            Point3::from(0.0),
+            2.0,
+            0.5,
+            Lambertian::new(Color::from(0.5)),
+        )
+    }
+
+    #[test]
+    fn rays_through_the_hole_miss_and_through_the_body_hit() {
+        let torus = torus();
+        let down = |x: f64| Ray::new(Point3::new(x, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0), None);
+
+        assert!(torus.hit(&down(0.0), 0.001, f64::INFINITY).is_none());
+        assert!(torus.hit(&down(1.4), 0.001, f64::INFINITY).is_none());
+
+        let hit = torus.hit(&down(2.0), 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 4.5).abs() < 1e-9);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(0.0, 1.0, 0.0), 1e-9);
+        assert!(hit.front_face);
+    }
+
+    #[test]
+    fn the_nearest_of_the_four_crossings_is_returned() {
+        let torus = torus();
+        // Along the x axis the ray crosses the tube at 2.5, 1.5, -1.5 and
+        // -2.5, from a long way off.
+        let ray = Ray::new(
+            Point3::new(1000.0, 0.0, 0.0),
+            Vec3::new(-2.0, 0.0, 0.0),
+            None,
+        );
+
+        let hit = torus.hit(&ray, 0.001, f64::INFINITY).unwrap();
+        assert_vec3_approx_eq!(hit.p, Point3::new(2.5, 0.0, 0.0), 1e-9);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(1.0, 0.0, 0.0), 1e-9);
+
+        // Past the first crossing, the inside of the tube is hit next.
+        let hit = torus
+            .hit(&ray, (1000.0 - 2.4) / 2.0, f64::INFINITY)
+            .unwrap();
+        assert_vec3_approx_eq!(hit.p, Point3::new(1.5, 0.0, 0.0), 1e-9);
+        assert!(!hit.front_face);
+    }
+
+    #[test]
+    fn hits_lie_on_the_surface() {
+        rng::seed_thread(13);
+        let torus = torus();
+        let bbox = torus.bounding_box(0.0, 1.0).unwrap();
+
+        let mut hits = 0;
+        for _ in 0..2000 {
+            let origin = Vec3::random_between(-6.0, 6.0);
+            let target = Vec3::random_between(-2.5, 2.5);
+            let Some(hit) = torus.hit(
+                &Ray::new(origin, target - origin, None),
+                0.001,
+                f64::INFINITY,
+            ) else {
+                continue;
+            };
+            hits += 1;
+
+            let ring = Vec3::new(hit.p.x(), 0.0, hit.p.z()).length() - 2.0;
+            assert!((ring.hypot(hit.p.y()) - 0.5).abs() < 1e-9);
+            assert!((hit.normal.length() - 1.0).abs() < 1e-9);
+            assert!((0.0..=1.0).contains(&hit.u) && (0.0..=1.0).contains(&hit.v));
+            for axis in 0..3 {
+                assert!(bbox.min[axis] - 1e-9 <= hit.p[axis]);
+                assert!(hit.p[axis] <= bbox.max[axis] + 1e-9);
+            }
+        }
+        assert!(hits > 500);
+    }
+}
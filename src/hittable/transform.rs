@@ -0,0 +1,247 @@
+use crate::{aabb::Aabb, ray::Ray, Point3, Vec3};
+
+use super::{HitRecord, Hittable};
+
+// Moves an object by `offset`. Intersections are found by moving the ray
+// the opposite way instead, so the inner object never changes.
+pub struct Translate<H: Hittable> {
+    pub inner: H,
+    pub offset: Vec3,
+}
+
+impl<H: Hittable> Translate<H> {
+    pub fn new(inner: H, offset: Vec3) -> Self {
+        Self { inner, offset }
+    }
+}
+
+impl<H: Hittable> Hittable for Translate<H> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        let moved = Ray::new(ray.origin - self.offset, ray.direction, Some(ray.time));
+        let mut rec = self.inner.hit(&moved, t_min, t_max)?;
+        // Normals and the facing are unaffected by a translation.
+        rec.p += self.offset;
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, time0: f64, time1: f64) -> Option<Aabb> {
+        let bbox = self.inner.bounding_box(time0, time1)?;
+        Some(transform_box(bbox, |p| p + self.offset))
+    }
+
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        self.inner.pdf_value(origin - self.offset, direction)
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        self.inner.random_direction(origin - self.offset)
+    }
+}
+
+// Rotates an object about the y axis. The ray is rotated into the object's
+// frame, and the hit point and normal are rotated back into world space.
+pub struct RotateY<H: Hittable> {
+    pub inner: H,
+    sin_theta: f64,
+    cos_theta: f64,
+    // Box of the rotated object over the shutter interval [0, 1].
+    bbox: Option<Aabb>,
+}
+
+impl<H: Hittable> RotateY<H> {
+    pub fn new(inner: H, angle_degrees: f64) -> Self {
+        let theta = angle_degrees.to_radians();
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let bbox = inner
+            .bounding_box(0.0, 1.0)
+            .map(|bbox| transform_box(bbox, |p| rotate_y(p, sin_theta, cos_theta)));
+
+        Self {
+            inner,
+            sin_theta,
+            cos_theta,
+            bbox,
+        }
+    }
+}
+
+impl<H: Hittable> Hittable for RotateY<H> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Rotating by -theta takes world space to object space.
+        let rotated = Ray::new(
+            rotate_y(ray.origin, -self.sin_theta, self.cos_theta),
+            rotate_y(ray.direction, -self.sin_theta, self.cos_theta),
+            Some(ray.time),
+        );
+        let mut rec = self.inner.hit(&rotated, t_min, t_max)?;
+        // Rotations preserve angles, so front_face is still correct.
+        rec.p = rotate_y(rec.p, self.sin_theta, self.cos_theta);
+        rec.normal = rotate_y(rec.normal, self.sin_theta, self.cos_theta);
+        rec.tangent = rotate_y(rec.tangent, self.sin_theta, self.cos_theta);
+        rec.bitangent = rotate_y(rec.bitangent, self.sin_theta, self.cos_theta);
+
+        Some(rec)
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        self.bbox
+    }
+
+    // Solid angles are unchanged by a rotation, so only the arguments and
+    // the sampled direction need rotating.
+    fn pdf_value(&self, origin: Point3, direction: Vec3) -> f64 {
+        self.inner.pdf_value(
+            rotate_y(origin, -self.sin_theta, self.cos_theta),
+            rotate_y(direction, -self.sin_theta, self.cos_theta),
+        )
+    }
+
+    fn random_direction(&self, origin: Point3) -> Vec3 {
+        let local = rotate_y(origin, -self.sin_theta, self.cos_theta);
+        rotate_y(
+            self.inner.random_direction(local),
+            self.sin_theta,
+            self.cos_theta,
+        )
+    }
+}
+
+fn rotate_y(v: Vec3, sin_theta: f64, cos_theta: f64) -> Vec3 {
+    Vec3::new(
+        cos_theta * v.x() + sin_theta * v.z(),
+        v.y(),
+        -sin_theta * v.x() + cos_theta * v.z(),
+    )
+}
+
+// Smallest box holding all eight transformed corners of `bbox`.
+pub(super) fn transform_box(bbox: Aabb, transform: impl Fn(Point3) -> Point3) -> Aabb {
+    let mut min = Point3::new(f64::INFINITY, f64::INFINITY, f64::INFINITY);
+    let mut max = Point3::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+
+    for i in 0..8 {
+        let corner = Point3::new(
+            if i & 1 == 0 {
+                bbox.min.x()
+            } else {
+                bbox.max.x()
+            },
+            if i & 2 == 0 {
+                bbox.min.y()
+            } else {
+                bbox.max.y()
+            },
+            if i & 4 == 0 {
+                bbox.min.z()
+            } else {
+                bbox.max.z()
+            },
+        );
+        let p = transform(corner);
+        min = Point3::min_components(min, p);
+        max = Point3::max_components(max, p);
+    }
+
+    Aabb { min, max }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_vec3_approx_eq,
+        hittable::{aabox::AaBox, rect::XzRect, sphere::Sphere},
+        material::lambertian::Lambertian,
+        rng, sampling, Color,
+    };
+
+    fn unit_sphere() -> Sphere<Lambertian> {
+        Sphere::new(Point3::from(0.0), 1.0, Lambertian::new(Color::from(0.5)))
+    }
+
+    // Mirrors across the x = 0 plane.
+    fn mirror(v: Vec3) -> Vec3 {
+        Vec3::new(-v.x(), v.y(), v.z())
+    }
+
+    #[test]
+    fn translated_copies_are_hit_symmetrically() {
+        let left = Translate::new(unit_sphere(), Vec3::new(-2.0, 0.0, 0.0));
+        let right = Translate::new(unit_sphere(), Vec3::new(2.0, 0.0, 0.0));
+
+        for direction in [Vec3::new(0.1, 0.0, -1.0), Vec3::new(-0.05, -0.02, -1.0)] {
+            let ray = Ray::new(Point3::new(1.5, 0.2, 5.0), direction, None);
+            let mirrored = Ray::new(mirror(ray.origin), mirror(direction), None);
+            let a = right.hit(&ray, 0.001, f64::INFINITY).unwrap();
+            let b = left.hit(&mirrored, 0.001, f64::INFINITY).unwrap();
+            assert!((a.t - b.t).abs() < 1e-12);
+            assert_vec3_approx_eq!(a.p, mirror(b.p), 1e-12);
+            assert_vec3_approx_eq!(a.normal, mirror(b.normal), 1e-12);
+        }
+
+        let bbox = right.bounding_box(0.0, 1.0).unwrap();
+        assert_eq!(bbox.min, Point3::new(1.0, -1.0, -1.0));
+        assert_eq!(bbox.max, Point3::new(3.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn rotated_objects_are_hit_in_their_new_orientation() {
+        // A long box along x, turned to lie along z.
+        let slab = AaBox::new(
+            Point3::new(-2.0, 0.0, -0.5),
+            Point3::new(2.0, 1.0, 0.5),
+            Lambertian::new(Color::from(0.5)),
+        );
+        let rotated = RotateY::new(slab, 90.0);
+
+        let bbox = rotated.bounding_box(0.0, 1.0).unwrap();
+        assert_vec3_approx_eq!(bbox.min, Point3::new(-0.5, 0.0, -2.0), 1e-12);
+        assert_vec3_approx_eq!(bbox.max, Point3::new(0.5, 1.0, 2.0), 1e-12);
+
+        let along_z = Ray::new(Point3::new(0.0, 0.5, 5.0), Vec3::new(0.0, 0.0, -1.0), None);
+        let hit = rotated.hit(&along_z, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 3.0).abs() < 1e-12);
+        assert_vec3_approx_eq!(hit.normal, Vec3::new(0.0, 0.0, 1.0), 1e-12);
+        assert!(hit.front_face);
+
+        // Where the box used to reach is empty now.
+        let old_end = Ray::new(Point3::new(1.5, 0.5, 5.0), Vec3::new(0.0, 0.0, -1.0), None);
+        assert!(rotated.hit(&old_end, 0.001, f64::INFINITY).is_none());
+    }
+
+    // A 2x2 light centered above the origin at height 2.
+    fn ceiling_light() -> XzRect<Lambertian> {
+        XzRect::new(-1.0, 1.0, -1.0, 1.0, 2.0, Lambertian::new(Color::from(0.5)))
+    }
+
+    #[test]
+    fn rotated_lights_keep_their_solid_angle_pdf() {
+        rng::seed_thread(4);
+        let light = RotateY::new(ceiling_light(), 30.0);
+        let origin = Point3::new(0.3, 0.0, -0.2);
+
+        // Straight up from below its center, the pdf is distance² over the
+        // area times the cosine: 4 / (4 * 1).
+        let up = Vec3::new(0.0, 1.0, 0.0);
+        assert!((light.pdf_value(Point3::from(0.0), up) - 1.0).abs() < 1e-9);
+
+        // Sampled directions head for the rotated light.
+        for _ in 0..1000 {
+            let direction = light.random_direction(origin);
+            let ray = Ray::new(origin, direction, None);
+            assert!(light.hit(&ray, 0.001, f64::INFINITY).is_some());
+            assert!(light.pdf_value(origin, direction) > 0.0);
+        }
+
+        // And the pdf integrates to 1 over all directions.
+        let n = 200_000;
+        let integral = (0..n)
+            .map(|_| light.pdf_value(origin, sampling::uniform_sphere()))
+            .sum::<f64>()
+            * 4.0
+            * std::f64::consts::PI
+            / n as f64;
+        assert!((integral - 1.0).abs() < 0.02, "integral {}", integral);
+    }
+}
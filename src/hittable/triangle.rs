@@ -0,0 +1,176 @@
+use crate::{
+    aabb::{surrounding_box, Aabb},
+    material::Material,
+    ray::Ray,
+    Point3, Vec3,
+};
+
+use super::{HitRecord, Hittable};
+
+pub struct Triangle<M: Material> {
+    pub v0: Point3,
+    pub v1: Point3,
+    pub v2: Point3,
+    pub material: M,
+    e1: Vec3,
+    e2: Vec3,
+    normal: Vec3,
+}
+
+impl<M: Material> Triangle<M> {
+    pub fn new(v0: Point3, v1: Point3, v2: Point3, material: M) -> Self {
+        let e1 = v1 - v0;
+        let e2 = v2 - v0;
+        Self {
+            v0,
+            v1,
+            v2,
+            material,
+            e1,
+            e2,
+            // Counter-clockwise winding faces the viewer.
+            normal: e1.cross(e2).unit_vector(),
+        }
+    }
+}
+
+impl<M: Material> Hittable for Triangle<M> {
+    fn hit(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<HitRecord<'_>> {
+        // Möller–Trumbore: solve origin + t * direction = v0 + u * e1 + v * e2
+        // for (t, u, v) using Cramer's rule.
+        let pvec = ray.direction.cross(self.e2);
+        let det = self.e1.dot(pvec);
+        if det.abs() < 1e-8 {
+            // The ray is parallel to the triangle's plane.
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let tvec = ray.origin - self.v0;
+        let u = tvec.dot(pvec) * inv_det;
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let qvec = tvec.cross(self.e1);
+        let v = ray.direction.dot(qvec) * inv_det;
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let t = self.e2.dot(qvec) * inv_det;
+        if t < t_min || t_max < t {
+            return None;
+        }
+
+        let front_face = ray.direction.dot(self.normal) < 0.0;
+        let normal = if front_face {
+            self.normal
+        } else {
+            -self.normal
+        };
+
+        Some(HitRecord {
+            t,
+            p: ray.at(t),
+            material: &self.material,
+            normal,
+            u,
+            v,
+            tangent: self.e1.unit_vector(),
+            bitangent: self.e2.unit_vector(),
+            front_face,
+        })
+    }
+
+    fn bounding_box(&self, _time0: f64, _time1: f64) -> Option<Aabb> {
+        let bbox = surrounding_box(Aabb::new(self.v0, self.v1), Aabb::new(self.v2, self.v2));
+        Some(bbox.padded())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, Color};
+
+    // The right triangle with its corner at the origin in the z = 0 plane,
+    // facing +z.
+    fn triangle() -> Triangle<Lambertian> {
+        Triangle::new(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Lambertian::new(Color::from(0.5)),
+        )
+    }
+
+    fn ray_towards(x: f64, y: f64, from_z: f64) -> Ray {
+        Ray::new(
+            Point3::new(x, y, from_z),
+            Vec3::new(0.0, 0.0, -from_z.signum()),
+            None,
+        )
+    }
+
+    #[test]
+    fn rays_through_the_inside_hit() {
+        let triangle = triangle();
+        let hit = triangle
+            .hit(&ray_towards(0.25, 0.5, 2.0), 0.001, f64::MAX)
+            .unwrap();
+        assert!((hit.t - 2.0).abs() < 1e-12);
+        assert_vec3_approx_eq!(hit.p, Point3::new(0.25, 0.5, 0.0), 1e-12);
+    }
+
+    #[test]
+    fn rays_past_the_edges_miss() {
+        let triangle = triangle();
+        for (x, y) in [(-0.1, 0.5), (0.5, -0.1), (0.6, 0.6), (2.0, 2.0)] {
+            let ray = ray_towards(x, y, 2.0);
+            assert!(triangle.hit(&ray, 0.001, f64::MAX).is_none(), "{} {}", x, y);
+        }
+        // Nor does a ray in the triangle's plane.
+        let grazing = Ray::new(Point3::new(-1.0, 0.2, 0.0), Vec3::new(1.0, 0.0, 0.0), None);
+        assert!(triangle.hit(&grazing, 0.001, f64::MAX).is_none());
+    }
+
+    #[test]
+    fn barycentric_coordinates_stay_in_the_unit_range() {
+        let triangle = triangle();
+        for (x, y) in [(0.0, 0.0), (1.0, 0.0), (0.0, 1.0), (0.5, 0.5), (0.2, 0.3)] {
+            let hit = triangle
+                .hit(&ray_towards(x, y, 2.0), 0.001, f64::MAX)
+                .unwrap();
+            assert!((0.0..=1.0).contains(&hit.u) && (0.0..=1.0).contains(&hit.v));
+            assert!(hit.u + hit.v <= 1.0 + 1e-12);
+            // Along the edges from v0, u and v are the distances covered.
+            assert!((hit.u - x).abs() < 1e-12 && (hit.v - y).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn front_face_follows_the_winding() {
+        let triangle = triangle();
+        let front = triangle
+            .hit(&ray_towards(0.2, 0.2, 2.0), 0.001, f64::MAX)
+            .unwrap();
+        assert!(front.front_face);
+        assert_vec3_approx_eq!(front.normal, Vec3::new(0.0, 0.0, 1.0), 1e-12);
+
+        let back = triangle
+            .hit(&ray_towards(0.2, 0.2, -2.0), 0.001, f64::MAX)
+            .unwrap();
+        assert!(!back.front_face);
+        assert_vec3_approx_eq!(back.normal, Vec3::new(0.0, 0.0, -1.0), 1e-12);
+    }
+
+    #[test]
+    fn flat_triangles_get_a_box_with_volume() {
+        let bbox = triangle().bounding_box(0.0, 1.0).unwrap();
+        assert!(bbox.max.z() > bbox.min.z());
+        assert!(bbox.min.z() < 0.0 && bbox.max.z() > 0.0);
+        assert_vec3_approx_eq!(bbox.max - bbox.min, Vec3::new(1.0, 1.0, 1e-4), 1e-12);
+        assert!(bbox.hit(&ray_towards(0.2, 0.2, 2.0), 0.001, f64::MAX));
+    }
+}
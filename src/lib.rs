@@ -1,17 +1,67 @@
 use std::{
+    fmt,
     iter::Sum,
-    ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub},
+    ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
-use rand::Rng;
-
+pub mod aabb;
+pub mod background;
 pub mod camera;
+#[cfg(feature = "spectral")]
+pub mod color;
+pub mod config;
+#[cfg(feature = "photon-mapping")]
+pub mod gi;
 pub mod hittable;
 pub mod material;
+pub mod math;
+pub mod output;
+pub mod pdf;
+pub mod postprocess;
 pub mod ray;
+pub mod render;
+pub mod rng;
+pub mod sampler;
+pub mod sampling;
+#[cfg(feature = "serde")]
+pub mod scene;
+pub mod stats;
+pub mod texture;
+pub mod tonemap;
+
+pub use render::render_with_progress;
+
+// Panics unless every component of the two vectors is within `eps` of the
+// other, showing both vectors and their difference.
+#[macro_export]
+macro_rules! assert_vec3_approx_eq {
+    ($a:expr, $b:expr, $eps:expr $(,)?) => {{
+        let (a, b, eps): ($crate::Vec3, $crate::Vec3, f64) = ($a, $b, $eps);
+        if !a.approx_eq(b, eps) {
+            panic!(
+                "assertion failed: `{} ≈ {}` (epsilon {})\n  left: {}\n right: {}\n  diff: {}",
+                stringify!($a),
+                stringify!($b),
+                eps,
+                a,
+                b,
+                a - b
+            );
+        }
+    }};
+}
 
 // TODO: Reconsider using borrow instead of copy.
-#[derive(Debug, Clone, Copy)]
+// PartialEq compares exactly, which suits values that are known to be
+// representable, like whole numbers. Use approx_eq for computed ones.
+// The default is the zero vector.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+// Serialized as a plain [x, y, z] array.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
 pub struct Vec3 {
     e: [f64; 3],
 }
@@ -21,7 +71,7 @@ pub type Color = Vec3;
 
 pub fn random_float() -> f64 {
     // Generate random number in the range [0.0, 1.0)
-    rand::thread_rng().gen_range(0.0..1.0)
+    rng::next_f64()
 }
 
 pub fn random_float_between(min: f64, max: f64) -> f64 {
@@ -58,6 +108,27 @@ impl Vec3 {
         self[0] * self[0] + self[1] * self[1] + self[2] * self[2]
     }
 
+    #[inline]
+    pub fn distance(a: Point3, b: Point3) -> f64 {
+        Self::distance_squared(a, b).sqrt()
+    }
+
+    #[inline]
+    pub fn distance_squared(a: Point3, b: Point3) -> f64 {
+        let (dx, dy, dz) = (a[0] - b[0], a[1] - b[1], a[2] - b[2]);
+        dx * dx + dy * dy + dz * dz
+    }
+
+    #[inline]
+    pub fn distance_to(&self, other: Point3) -> f64 {
+        Self::distance(*self, other)
+    }
+
+    // Whether every component is within `epsilon` of the one of `other`.
+    pub fn approx_eq(self, other: Vec3, epsilon: f64) -> bool {
+        (0..3).all(|i| (self[i] - other[i]).abs() <= epsilon)
+    }
+
     pub fn near_zero(&self) -> bool {
         // Return true if the vector is close to zero in all dimensions.
         const S: f64 = 1e-8;
@@ -94,40 +165,66 @@ impl Vec3 {
         Self::new(self[0] / length, self[1] / length, self[2] / length)
     }
 
+    // Linear interpolation from a (t = 0) to b (t = 1).
+    #[inline]
+    pub fn lerp(a: Self, b: Self, t: f64) -> Self {
+        a * (1.0 - t) + b * t
+    }
+
+    #[inline]
+    pub fn lerp_to(&self, other: Self, t: f64) -> Self {
+        Self::lerp(*self, other, t)
+    }
+
+    // Component-wise minimum and maximum. Like f64::min and f64::max, a NaN
+    // component loses against a number.
+    #[inline]
+    pub fn min_components(a: Self, b: Self) -> Self {
+        Self::new(a[0].min(b[0]), a[1].min(b[1]), a[2].min(b[2]))
+    }
+
+    #[inline]
+    pub fn max_components(a: Self, b: Self) -> Self {
+        Self::new(a[0].max(b[0]), a[1].max(b[1]), a[2].max(b[2]))
+    }
+
+    #[inline]
+    pub fn clamp_components(&self, min: f64, max: f64) -> Self {
+        Self::new(
+            self[0].clamp(min, max),
+            self[1].clamp(min, max),
+            self[2].clamp(min, max),
+        )
+    }
+
+    #[inline]
+    pub fn abs(&self) -> Self {
+        Self::new(self[0].abs(), self[1].abs(), self[2].abs())
+    }
+
+    #[deprecated(note = "use sampling::in_unit_sphere")]
     pub fn random_in_unit_sphere() -> Self {
-        loop {
-            let p = Self::random_between(-1.0, 1.0);
-            if p.length_squared() < 1.0 {
-                return p;
-            }
-        }
+        sampling::in_unit_sphere()
     }
 
+    #[deprecated(note = "use sampling::uniform_disk")]
     pub fn random_in_unit_disk() -> Self {
-        loop {
-            let p = Vec3::new(
-                random_float_between(-1.0, 1.0),
-                random_float_between(-1.0, 1.0),
-                0.0,
-            );
-            if p.length_squared() < 1.0 {
-                return p;
-            }
-        }
+        sampling::uniform_disk()
+    }
+
+    #[deprecated(note = "use sampling::uniform_polygon")]
+    pub fn random_in_polygon(sides: u32, rotation_degrees: f64) -> Self {
+        sampling::uniform_polygon(sides, rotation_degrees)
     }
 
+    #[deprecated(note = "use sampling::uniform_hemisphere")]
     pub fn random_in_hemisphere(normal: Self) -> Self {
-        let in_unit_sphere: Self = Self::random_in_unit_sphere();
-        if in_unit_sphere.dot(normal) > 0.0 {
-            // In the same hemisphere as the normal.
-            in_unit_sphere
-        } else {
-            -in_unit_sphere
-        }
+        sampling::uniform_hemisphere(normal)
     }
 
+    #[deprecated(note = "use sampling::uniform_sphere")]
     pub fn random_unit_vector() -> Self {
-        Self::random_in_unit_sphere().unit_vector()
+        sampling::uniform_sphere()
     }
 
     pub fn random() -> Self {
@@ -147,12 +244,159 @@ impl Vec3 {
     }
 }
 
+// Colors as sRGB hex strings like "#ff8000", the way color pickers and
+// web tools write them. Linear values are clamped to [0, 1] and encoded
+// with the sRGB transfer curve.
+impl Color {
+    pub fn to_hex_string(&self) -> String {
+        let byte = |c: f64| (srgb_encode(c.clamp(0.0, 1.0)) * 255.0).round() as u8;
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            byte(self[0]),
+            byte(self[1]),
+            byte(self[2])
+        )
+    }
+
+    pub fn from_hex_str(s: &str) -> Result<Color, ColorError> {
+        let error = || ColorError(s.to_string());
+        let hex = s.strip_prefix('#').unwrap_or(s);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(error());
+        }
+        let channel = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16)
+                .map(|b| srgb_decode(b as f64 / 255.0))
+                .map_err(|_| error())
+        };
+
+        Ok(Color::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+}
+
+fn srgb_encode(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        12.92 * c
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn srgb_decode(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[derive(Debug)]
+pub struct ColorError(String);
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid color '{}', expected #RRGGBB", self.0)
+    }
+}
+
+impl std::error::Error for ColorError {}
+
+// Written as "(x, y, z)". Precision and other flags apply to each
+// component, so "{:.2}" gives "(1.00, 0.50, 0.00)".
+impl fmt::Display for Vec3 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(")?;
+        fmt::Display::fmt(&self[0], f)?;
+        write!(f, ", ")?;
+        fmt::Display::fmt(&self[1], f)?;
+        write!(f, ", ")?;
+        fmt::Display::fmt(&self[2], f)?;
+        write!(f, ")")
+    }
+}
+
+#[derive(Debug)]
+pub struct Vec3ParseError(String);
+
+impl fmt::Display for Vec3ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid vector '{}', expected (x, y, z)", self.0)
+    }
+}
+
+impl std::error::Error for Vec3ParseError {}
+
+// Parses the Display format back, ignoring whitespace around the parts.
+impl std::str::FromStr for Vec3 {
+    type Err = Vec3ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || Vec3ParseError(s.to_string());
+        let inner = s
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or_else(error)?;
+
+        let mut e = [0.0; 3];
+        let mut parts = inner.split(',');
+        for value in e.iter_mut() {
+            *value = parts
+                .next()
+                .and_then(|p| p.trim().parse().ok())
+                .ok_or_else(error)?;
+        }
+        if parts.next().is_some() {
+            return Err(error());
+        }
+
+        Ok(Self { e })
+    }
+}
+
 impl Sum for Vec3 {
     fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
         iter.fold(Vec3::new(0.0, 0.0, 0.0), |acc, x| acc + x)
     }
 }
 
+impl<'a> Sum<&'a Vec3> for Vec3 {
+    fn sum<I: Iterator<Item = &'a Vec3>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+// A vector with all three components set to the same value.
+impl From<f64> for Vec3 {
+    fn from(v: f64) -> Self {
+        Self::new(v, v, v)
+    }
+}
+
+impl From<[f64; 3]> for Vec3 {
+    fn from(e: [f64; 3]) -> Self {
+        Self { e }
+    }
+}
+
+impl From<Vec3> for [f64; 3] {
+    fn from(v: Vec3) -> Self {
+        v.e
+    }
+}
+
+impl From<(f64, f64, f64)> for Vec3 {
+    fn from((x, y, z): (f64, f64, f64)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl From<Vec3> for (f64, f64, f64) {
+    fn from(v: Vec3) -> Self {
+        (v[0], v[1], v[2])
+    }
+}
+
 impl Neg for Vec3 {
     type Output = Self;
 
@@ -245,3 +489,243 @@ impl Div<f64> for Vec3 {
         self * (1.0 / rhs)
     }
 }
+
+// Scalar broadcasts: the scalar is applied to every component.
+impl Add<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: f64) -> Vec3 {
+        Vec3 {
+            e: [self[0] + rhs, self[1] + rhs, self[2] + rhs],
+        }
+    }
+}
+
+impl Sub<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: f64) -> Vec3 {
+        self + -rhs
+    }
+}
+
+impl AddAssign<f64> for Vec3 {
+    fn add_assign(&mut self, rhs: f64) {
+        *self = *self + rhs
+    }
+}
+
+impl SubAssign<f64> for Vec3 {
+    fn sub_assign(&mut self, rhs: f64) {
+        *self = *self - rhs
+    }
+}
+
+impl Mul<Vec3> for f64 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        rhs * self
+    }
+}
+
+// Divides the scalar by each component, e.g. 1.0 / direction.
+impl Div<Vec3> for f64 {
+    type Output = Vec3;
+
+    fn div(self, rhs: Vec3) -> Vec3 {
+        Vec3 {
+            e: [self / rhs[0], self / rhs[1], self / rhs[2]],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_runs_from_a_to_b() {
+        let (a, b) = (Vec3::new(1.0, 2.0, -4.0), Vec3::new(3.0, -2.0, 4.0));
+        assert_eq!(Vec3::lerp(a, b, 0.0), a);
+        assert_eq!(Vec3::lerp(a, b, 1.0), b);
+        assert_eq!(Vec3::lerp(a, b, 0.5), Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(a.lerp_to(b, 0.5), Vec3::lerp(a, b, 0.5));
+        assert_eq!(a.lerp_to(b, 0.0), a);
+        assert_eq!(a.lerp_to(b, 1.0), b);
+    }
+
+    #[test]
+    fn clamp_components_and_abs_apply_to_each_component() {
+        let v = Vec3::new(-0.5, 0.25, 1.5);
+        assert_eq!(v.clamp_components(0.0, 1.0), Vec3::new(0.0, 0.25, 1.0));
+        assert_eq!(v.clamp_components(0.25, 0.25), Vec3::from(0.25));
+        assert_eq!(v.abs(), Vec3::new(0.5, 0.25, 1.5));
+        assert_eq!(Vec3::new(-0.0, -3.0, 0.0).abs(), Vec3::new(0.0, 3.0, 0.0));
+    }
+
+    #[test]
+    fn min_and_max_components_pick_each_component_separately() {
+        let (a, b) = (Vec3::new(1.0, -2.0, 3.0), Vec3::new(-1.0, 2.0, 3.0));
+        assert_eq!(Vec3::min_components(a, b), Vec3::new(-1.0, -2.0, 3.0));
+        assert_eq!(Vec3::max_components(a, b), Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(Vec3::min_components(a, a), a);
+
+        // Like f64::min and f64::max, NaN loses against a number...
+        let nan = Vec3::new(f64::NAN, 0.0, f64::NAN);
+        let numbers = Vec3::new(5.0, 1.0, -5.0);
+        assert_eq!(
+            Vec3::min_components(nan, numbers),
+            Vec3::new(5.0, 0.0, -5.0)
+        );
+        assert_eq!(
+            Vec3::max_components(numbers, nan),
+            Vec3::new(5.0, 1.0, -5.0)
+        );
+        // ...and comes out only when both components are NaN.
+        let both = Vec3::min_components(nan, nan);
+        assert!(both.x().is_nan() && both.y() == 0.0 && both.z().is_nan());
+        assert!(Vec3::max_components(nan, nan).x().is_nan());
+    }
+
+    #[test]
+    fn vectors_round_trip_through_their_text_form() {
+        let v = Vec3::new(1.0, -0.5, 1e-7);
+        assert_eq!(v.to_string(), "(1, -0.5, 0.0000001)");
+        assert_eq!(format!("{:.2}", v), "(1.00, -0.50, 0.00)");
+
+        rng::seed_thread(1);
+        for _ in 0..100 {
+            let v = Vec3::random_between(-1e3, 1e3);
+            assert_eq!(v.to_string().parse::<Vec3>().unwrap(), v);
+        }
+        assert_eq!(
+            " ( 1,2 ,  3 ) ".parse::<Vec3>().unwrap(),
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn malformed_vectors_are_rejected() {
+        for text in [
+            "",
+            "()",
+            "(1, 2)",
+            "(1, 2, 3, 4)",
+            "1, 2, 3",
+            "(1, 2, 3",
+            "(1, x, 3)",
+        ] {
+            let err = text.parse::<Vec3>().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("invalid vector '{}', expected (x, y, z)", text)
+            );
+        }
+    }
+
+    #[test]
+    fn colors_round_trip_through_hex_strings() {
+        assert_eq!(Color::new(1.0, 0.0, 0.0).to_hex_string(), "#ff0000");
+        // sRGB encoded, so linear 0.5 is brighter than #808080.
+        assert_eq!(Color::from(0.5).to_hex_string(), "#bcbcbc");
+        assert_eq!(Color::new(-1.0, 2.0, 0.0).to_hex_string(), "#00ff00");
+
+        for hex in ["#000000", "#ffffff", "#ff8000", "#1a2b3c", "#bcbcbc"] {
+            assert_eq!(Color::from_hex_str(hex).unwrap().to_hex_string(), hex);
+        }
+        let without_hash = Color::from_hex_str("FF8000").unwrap();
+        assert_eq!(without_hash, Color::from_hex_str("#ff8000").unwrap());
+        assert_vec3_approx_eq!(
+            Color::from_hex_str("#ffffff").unwrap(),
+            Color::from(1.0),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn malformed_hex_colors_are_rejected() {
+        for hex in ["", "#", "#fff", "#ff80000", "#gg0000", "#ff 000", "#ff00é"] {
+            let err = Color::from_hex_str(hex).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("invalid color '{}', expected #RRGGBB", hex)
+            );
+        }
+    }
+
+    #[test]
+    fn scalars_fill_every_component() {
+        assert_eq!(Vec3::from(0.5), Vec3::new(0.5, 0.5, 0.5));
+        assert_eq!(Color::from(-2.0), Color::new(-2.0, -2.0, -2.0));
+    }
+
+    #[test]
+    fn arrays_convert_both_ways() {
+        let v = Vec3::from([1.0, 2.0, 3.0]);
+        assert_eq!(v, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(<[f64; 3]>::from(v), [1.0, 2.0, 3.0]);
+        let array: [f64; 3] = Vec3::new(-1.0, 0.0, 1.0).into();
+        assert_eq!(array, [-1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn tuples_convert_both_ways() {
+        let v = Vec3::from((1.0, 2.0, 3.0));
+        assert_eq!(v, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(<(f64, f64, f64)>::from(v), (1.0, 2.0, 3.0));
+        let (x, y, z) = Vec3::new(-1.0, 0.0, 1.0).into();
+        assert_eq!((x, y, z), (-1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn scalars_broadcast_over_every_component() {
+        let v = Vec3::new(1.0, -2.0, 4.0);
+        assert_eq!(v + 0.5, Vec3::new(1.5, -1.5, 4.5));
+        assert_eq!(v - 0.5, Vec3::new(0.5, -2.5, 3.5));
+
+        let mut w = v;
+        w += 1.0;
+        assert_eq!(w, Vec3::new(2.0, -1.0, 5.0));
+        w -= 3.0;
+        assert_eq!(w, Vec3::new(-1.0, -4.0, 2.0));
+    }
+
+    #[test]
+    fn scalars_can_come_first() {
+        let v = Vec3::new(1.0, -2.0, 4.0);
+        assert_eq!(2.0 * v, v * 2.0);
+        assert_eq!(2.0 * v, Vec3::new(2.0, -4.0, 8.0));
+        assert_eq!(1.0 / v, Vec3::new(1.0, -0.5, 0.25));
+
+        let inverse = 1.0 / Vec3::new(0.0, -0.0, 2.0);
+        assert_eq!(inverse, Vec3::new(f64::INFINITY, f64::NEG_INFINITY, 0.5));
+    }
+
+    #[test]
+    fn distances_between_known_points() {
+        let (a, b) = (Point3::new(1.0, 2.0, 3.0), Point3::new(4.0, 6.0, 3.0));
+        assert_eq!(Vec3::distance_squared(a, b), 25.0);
+        assert_eq!(Vec3::distance(a, b), 5.0);
+        assert_eq!(a.distance_to(b), 5.0);
+        assert_eq!(b.distance_to(a), 5.0);
+        assert_eq!(a.distance_to(a), 0.0);
+
+        let (c, d) = (Point3::new(-1.0, -1.0, -1.0), Point3::new(1.0, 1.0, 1.0));
+        assert_eq!(Vec3::distance_squared(c, d), 12.0);
+        assert_eq!(Vec3::distance(c, d), 12f64.sqrt());
+    }
+
+    #[test]
+    fn colors_sum_by_value_and_by_reference() {
+        let colors = [
+            Color::new(0.5, 0.0, 0.25),
+            Color::new(0.25, 1.0, 0.25),
+            Color::new(0.25, 0.5, 0.5),
+        ];
+        let total = Color::new(1.0, 1.5, 1.0);
+        assert_eq!(colors.iter().sum::<Color>(), total);
+        assert_eq!(colors.into_iter().sum::<Color>(), total);
+        assert_eq!(std::iter::empty::<Color>().sum::<Color>(), Color::from(0.0));
+    }
+}
@@ -1,136 +1,569 @@
-use std::thread;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use crossbeam::channel::{unbounded, Sender};
-use sfml::{
-    graphics::{Color as SFMLColor, Font, RenderTarget, RenderWindow, Sprite, Text, Texture, Transformable},
-    window::{Event, Style},
-};
+use clap::{error::ErrorKind, CommandFactory, Parser, ValueEnum};
+use crossbeam::channel::{unbounded, Receiver, Sender};
 use tracy::{
-    camera::Camera,
-    hittable::{sphere::Sphere, HittableList, moving_sphere::MovingSphere},
-    material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
-    random_float, random_float_between, Color, Point3, Vec3,
+    background::{constant::ConstantBackground, rayleigh::RayleighSky, Background},
+    camera::{Camera, RayGenerator},
+    config::RenderConfig,
+    hittable::{
+        bvh::{BvhNode, FlatBvh},
+        light_list::LightList,
+        Hittable, HittableList,
+    },
+    output::{
+        exr::{write_exr, write_exr_with_aovs, Aovs},
+        ppm::write_ppm,
+        save_png,
+    },
+    postprocess::bilateral::bilateral_filter,
+    ray::RenderMode,
+    render::{render_tiles, tiles, TILE_SIZE},
+    rng,
+    sampler::{adaptive::AdaptiveSampler, AnySampler, Sampler, SamplerKind},
+    stats::RENDER_STATS,
+    tonemap::{tone_map, ToneMap},
+    Color, Point3, Vec3,
 };
 
 use rayon::prelude::*;
+#[cfg(feature = "serde")]
+use tracy::scene::{load_scene, toml_loader::load_toml};
+#[cfg(feature = "photon-mapping")]
+use tracy::gi::photon_map::PhotonMap;
 
-// Image
-const ASPECT_RATIO: f64 = 3.0 / 2.0;
-const IMAGE_WIDTH: u32 = 240;
-const IMAGE_HEIGHT: u32 = (IMAGE_WIDTH as f64 / ASPECT_RATIO as f64) as u32;
-const SAMPLES_PER_PIXEL: u32 = 100;
-const MAX_DEPTH: i32 = 50;
+mod checkpoint;
+mod scenes;
+#[cfg(feature = "window")]
+mod window;
 
-fn main() {
-    // UI
+use checkpoint::{fnv1a, load_checkpoint, Checkpoint, CheckpointWriter};
 
-    let mut window = RenderWindow::new(
-        (IMAGE_WIDTH, IMAGE_HEIGHT),
-        "Tracy",
-        Style::CLOSE,
-        &Default::default(),
-    );
+// Finished tiles are saved here while rendering, and the file is removed
+// once the render completes.
+const CHECKPOINT_PATH: &str = "out.checkpoint";
+
+// Command line flags. Render settings that aren't given come from the
+// scene.
+#[derive(Parser)]
+#[command(version, about = "Renders a scene with a path tracer")]
+struct Options {
+    /// Render a preset (cornell, cornell-smoke, earth, ggx, god-rays, mandelbulb, marble-wood, normal-map, pool, prism, rect-light, sdf-blob, sun, thin-film or velvet), or a JSON or TOML scene file
+    #[arg(long, value_name = "NAME|PATH")]
+    scene: Option<PathBuf>,
+    /// Write the image to a file instead of opening a window
+    #[arg(long)]
+    headless: bool,
+    /// File headless renders are saved to, out.png by default
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+    /// Format of the output file, if it isn't the extension of the output path
+    #[arg(long, value_name = "FORMAT", value_enum, ignore_case = true)]
+    format: Option<OutputFormat>,
+    /// Image width in pixels
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    width: Option<u32>,
+    /// Image height in pixels
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    height: Option<u32>,
+    /// Samples per pixel, the most any pixel takes with --adaptive
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    samples: Option<u32>,
+    /// Maximum number of bounces per path
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    max_depth: Option<u32>,
+    /// Add depth and normal layers to EXR output
+    #[arg(long)]
+    aovs: bool,
+    /// Refine the whole image one sample per pixel at a time
+    #[arg(long, conflicts_with = "resume")]
+    progressive: bool,
+    /// Make the render reproducible: the same seed and settings give the same image
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+    /// Sub-pixel positions: random, stratified or halton
+    #[arg(long, value_name = "NAME", default_value = "stratified")]
+    sampler: SamplerKind,
+    /// path, or ao[=DISTANCE[,SAMPLES]] for ambient occlusion, or spectral when built
+    /// with the spectral feature
+    #[arg(long, value_name = "MODE", default_value = "path")]
+    mode: RenderMode,
+    /// Stop sampling pixels once their relative noise is below T, e.g. 0.02. Ignored by
+    /// progressive renders
+    #[arg(long, value_name = "T", value_parser = parse_positive)]
+    adaptive: Option<f64>,
+    /// linear, gamma[=G], reinhard, reinhard-lum or aces, for the window and 8-bit output.
+    /// EXR stays linear
+    #[arg(long, value_name = "NAME", default_value = "gamma")]
+    tone_map: ToneMap,
+    /// Smooth the finished image with a bilateral filter (headless only)
+    #[arg(long)]
+    denoise: bool,
+    // By default pixel noise is averaged over a small neighbourhood, and
+    // colors further apart than about the range width are treated as
+    // edges. Guiding the filter by normals instead bleeds lights into the
+    // surfaces next to them.
+    /// Width of the denoising filter in pixels
+    #[arg(long, value_name = "SIGMA", default_value_t = 1.0, value_parser = parse_positive)]
+    denoise_spatial: f64,
+    /// Difference in color the denoising filter treats as an edge
+    #[arg(long, value_name = "SIGMA", default_value_t = 0.25, value_parser = parse_positive)]
+    denoise_range: f64,
+    /// Continue the interrupted render saved in out.checkpoint, if its settings match
+    #[arg(long)]
+    resume: bool,
+    /// Number of render threads, 0 for one per core. Fewer leave cores free for other
+    /// programs on shared machines
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    jobs: usize,
+    /// Render with photon mapping from N photons instead of path tracing, 0 to path trace
+    #[cfg(feature = "photon-mapping")]
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    photons: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    Png,
+    Ppm,
+    Exr,
+}
+
+impl OutputFormat {
+    // Parses a file extension, ignoring case.
+    fn from_extension(extension: &str) -> Option<Self> {
+        <Self as ValueEnum>::from_str(extension, true).ok()
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Ppm => "ppm",
+            OutputFormat::Exr => "exr",
+        }
+    }
+}
 
-    let mut texture = Texture::new().unwrap();
-    
-    if !texture.create(IMAGE_WIDTH, IMAGE_HEIGHT) {
-        panic!("Unable to create texture");
+impl Options {
+    // The format headless renders are saved in: --format, or else the
+    // extension of --output.
+    fn output_format(&self) -> Result<OutputFormat, String> {
+        match (self.format, &self.output) {
+            (Some(format), _) => Ok(format),
+            (None, Some(path)) => path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(OutputFormat::from_extension)
+                .ok_or_else(|| {
+                    format!(
+                        "unable to tell the format of {}: supported extensions are .png, .ppm and .exr, or pass --format",
+                        path.display()
+                    )
+                }),
+            (None, None) => Ok(OutputFormat::Png),
+        }
     }
 
-    texture.set_smooth(false);
+    // Where headless renders are saved: --output, or else out.png or the
+    // extension of --format.
+    fn output_path(&self) -> PathBuf {
+        match &self.output {
+            Some(path) => path.clone(),
+            None => {
+                let extension = self.format.unwrap_or(OutputFormat::Png).extension();
+                PathBuf::from(format!("out.{}", extension))
+            }
+        }
+    }
+}
+
+fn parse_positive(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(number) if number > 0.0 => Ok(number),
+        _ => Err("expected a positive number".to_string()),
+    }
+}
+
+// Parses the command line, or exits with a usage message if it doesn't
+// make sense.
+fn parse_args() -> Options {
+    parse_args_from(std::env::args_os()).unwrap_or_else(|err| err.exit())
+}
+
+// Parses `args`, the program name first, and checks the combinations of
+// flags that clap can't.
+fn parse_args_from<I, T>(args: I) -> Result<Options, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let options = Options::try_parse_from(args)?;
+    if let Err(message) = options.output_format() {
+        return Err(usage_error(ErrorKind::InvalidValue, message));
+    }
+    #[cfg(feature = "photon-mapping")]
+    if options.photons > 0 && options.mode != RenderMode::PathTrace {
+        return Err(usage_error(
+            ErrorKind::ArgumentConflict,
+            "--photons replaces path tracing and can't be combined with --mode",
+        ));
+    }
 
-    // Load a font for rendering text (embedded open-source DejaVu Sans)
-    let font = unsafe {
-        Font::from_memory(include_bytes!("../DejaVuSans.ttf"))
-            .expect("Failed to load font")
+    Ok(options)
+}
+
+fn usage_error(kind: ErrorKind, message: impl fmt::Display) -> clap::Error {
+    Options::command().error(kind, message)
+}
+
+fn main() {
+    let options = parse_args();
+    let progressive = options.progressive;
+    // Before anything runs on the pool, which the BVH is built on too.
+    if let Err(err) = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs)
+        .build_global()
+    {
+        eprintln!("Unable to set up {} render threads: {}", options.jobs, err);
+        std::process::exit(1);
+    }
+    let saved = if options.resume {
+        resume_checkpoint()
+    } else {
+        None
     };
 
-    let (s, r) = unbounded();
+    // Before the scene is built, since some scenes are randomized too.
+    // Resumed renders keep the seed they were started with, and unseeded
+    // ones pick a random seed so the checkpoint can record it.
+    let seed = options
+        .seed
+        .or(saved.as_ref().map(|saved| saved.seed))
+        .unwrap_or_else(rng::global_seed);
+    rng::set_global_seed(seed);
 
-    thread::spawn(|| {
-        render(s);
+    let scene = prepare_scene(&options).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
     });
+    if let Err(err) = scene.config.validate() {
+        usage_error(ErrorKind::InvalidValue, err).exit();
+    }
 
-    let total_pixels = IMAGE_WIDTH * IMAGE_HEIGHT;
-    let mut pixels_rendered = 0u32;
-    let mut rendering_complete = false;
-
-    while window.is_open() {
-        // Event processing
-        while let Some(event) = window.poll_event() {
-            // Request closing for the window
-            if event == Event::Closed {
-                window.close();
+    let settings = checkpoint_settings(&options, &scene);
+    let tiles = match saved {
+        Some(saved) if saved.seed == seed && saved.settings == settings => {
+            if saved.fits(scene.config.width, scene.config.height) {
+                eprintln!("Resuming with {} finished tiles", saved.tiles.len());
+                saved.tiles
+            } else {
+                eprintln!(
+                    "{} has tiles that don't fit the image, starting over",
+                    CHECKPOINT_PATH
+                );
+                Vec::new()
             }
         }
+        Some(_) => {
+            eprintln!(
+                "{} is from a render with other settings, starting over",
+                CHECKPOINT_PATH
+            );
+            Vec::new()
+        }
+        None => Vec::new(),
+    };
+    let checkpoint = Checkpoint {
+        seed,
+        settings,
+        tiles,
+    };
+    let config = scene.config;
+    let job = start_render(scene, checkpoint, progressive);
 
-        // Process all pending messages without blocking
-        while let Ok(msg) = r.try_recv() {
-            match msg {
-                RenderMessage::Pixel(pixel) => {
-                    unsafe {
-                        texture.update_from_pixels(&pixel.color, 1, 1, pixel.x, pixel.y);
-                    }
-                    pixels_rendered += 1;
-                }
-                RenderMessage::Progress(count) => {
-                    pixels_rendered = count;
-                }
-                RenderMessage::Done => {
-                    rendering_complete = true;
-                    eprintln!("Rendering complete!");
-                }
+    #[cfg(feature = "window")]
+    if !options.headless {
+        window::run_window(job, &config, &options);
+        return;
+    }
+    run_headless(&job.receiver, &config, &options);
+    job.stop();
+}
+
+// Builds the scene chosen on the command line with the render settings,
+// sampler and mode given there. The config isn't validated yet.
+fn prepare_scene(options: &Options) -> Result<Scene, String> {
+    let mut scene = build_scene(options.scene.as_deref())?;
+    scene.config = override_config(scene.config, options);
+    scene.sampler_kind = options.sampler;
+    scene.mode = options.mode;
+    // The configured sample count becomes the most any pixel may take.
+    // Pixels are sampled in batches of a square number of samples, so each
+    // batch covers a full stratified grid.
+    scene.adaptive = options.adaptive.map(|threshold| {
+        let samples = scene.config.samples;
+        let batch = ((samples as f64 / 8.0).sqrt() as u32).max(4).pow(2);
+        AdaptiveSampler::new(batch, samples, threshold)
+    });
+    #[cfg(feature = "photon-mapping")]
+    if options.photons > 0 {
+        scene.photon_map = Some(PhotonMap::build(
+            &scene.world,
+            &scene.lights,
+            options.photons,
+            scene.config.max_depth,
+        ));
+    }
+
+    Ok(scene)
+}
+
+// Everything besides the seed that a checkpoint's tiles depend on. Scene
+// files are included by a hash of their contents, so tiles of a file that
+// was edited since aren't resumed.
+fn checkpoint_settings(options: &Options, scene: &Scene) -> String {
+    let contents = options
+        .scene
+        .as_deref()
+        .and_then(|path| fs::read(path).ok())
+        .map(|bytes| fnv1a(&bytes));
+
+    let settings = format!(
+        "scene {:?} ({:?}), {:?}, {:?}, {:?}, {:?}, tile size {}",
+        options.scene,
+        contents,
+        scene.config,
+        scene.sampler_kind,
+        scene.mode,
+        scene.adaptive,
+        TILE_SIZE
+    );
+    #[cfg(feature = "photon-mapping")]
+    let settings = format!("{}, {} photons", settings, options.photons);
+
+    settings
+}
+
+// A render running on a thread of its own.
+struct RenderJob {
+    receiver: Receiver<RenderMessage>,
+    thread: JoinHandle<()>,
+}
+
+impl RenderJob {
+    // Stops the render after the tiles or pass in progress, which notice
+    // that nobody receives their pixels anymore, and waits for it.
+    fn stop(self) {
+        drop(self.receiver);
+        let _ = self.thread.join();
+    }
+}
+
+fn start_render(scene: Scene, checkpoint: Checkpoint, progressive: bool) -> RenderJob {
+    let (s, receiver) = unbounded();
+    let thread = thread::spawn(move || {
+        if progressive {
+            render_progressive(&scene, s);
+        } else {
+            render(&scene, checkpoint, s);
+        }
+    });
+
+    RenderJob { receiver, thread }
+}
+
+// Applies the render settings given on the command line. The camera was
+// set up for the scene's aspect ratio, so width and height can't change it.
+fn override_config(config: RenderConfig, options: &Options) -> RenderConfig {
+    let aspect_ratio = config.aspect_ratio();
+    let (width, height) = match (options.width, options.height) {
+        (None, None) => (config.width, config.height),
+        (Some(width), None) => (width, (width as f64 / aspect_ratio).round() as u32),
+        (None, Some(height)) => ((height as f64 * aspect_ratio).round() as u32, height),
+        (Some(width), Some(height)) => {
+            // Rounding may put the other side a pixel off.
+            if (width as f64 / aspect_ratio - height as f64).abs() > 1.0 {
+                let message = format!(
+                    "{}x{} doesn't match the scene's aspect ratio of {:.3}, pass only --width or --height",
+                    width, height, aspect_ratio
+                );
+                usage_error(ErrorKind::ArgumentConflict, message).exit();
             }
+            (width, height)
         }
+    };
 
-        window.clear(SFMLColor::rgb(30, 30, 30));
+    RenderConfig {
+        width,
+        height,
+        samples: options.samples.unwrap_or(config.samples),
+        max_depth: options.max_depth.unwrap_or(config.max_depth),
+    }
+}
 
-        if rendering_complete {
-            // Show the final rendered image
-            let mut sprite = Sprite::new();
-            sprite.set_texture(&texture, true);
-            window.draw(&sprite);
-        } else {
-            // Show progress text in the center of the window
-            let progress_text = format!(
-                "Beep Boop..Tracing..\n\n{}/{} pixels",
-                pixels_rendered, total_pixels
-            );
-            
-            let mut text = Text::new(&progress_text, &font, 24);
-            text.set_fill_color(SFMLColor::WHITE);
-            
-            // Center the text
-            let text_bounds = text.local_bounds();
-            text.set_position((
-                (IMAGE_WIDTH as f32 - text_bounds.width) / 2.0 - text_bounds.left,
-                (IMAGE_HEIGHT as f32 - text_bounds.height) / 2.0 - text_bounds.top,
-            ));
-            
-            window.draw(&text);
-        }
-
-        window.display();
+// The tiles saved by an interrupted run, if there are any.
+fn resume_checkpoint() -> Option<Checkpoint> {
+    let path = Path::new(CHECKPOINT_PATH);
+    match load_checkpoint(path) {
+        Ok(saved) => Some(saved),
+        Err(err) => {
+            eprintln!("Unable to resume from {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+// Collects the rendered pixels without opening a window and writes the
+// finished image to the output path.
+fn run_headless(r: &Receiver<RenderMessage>, config: &RenderConfig, options: &Options) {
+    let format = options.output_format().expect("checked by parse_args");
+    let (aovs, tm) = (options.aovs, options.tone_map);
+    let (width, height) = (config.width, config.height);
+    let pixel_total = (width * height) as usize;
+    let mut colors = vec![Color::new(0.0, 0.0, 0.0); pixel_total];
+    let mut depth = vec![f64::INFINITY; pixel_total];
+    let mut normal = vec![Vec3::new(0.0, 0.0, 0.0); pixel_total];
+    let mut store = |pixel: &Pixel| {
+        let index = (pixel.y * width + pixel.x) as usize;
+        colors[index] = pixel.color / pixel.sample_count as f64;
+        depth[index] = pixel.depth;
+        normal[index] = pixel.normal;
+    };
+
+    for msg in r.iter() {
+        match msg {
+            RenderMessage::Pixel(pixel) => store(&pixel),
+            RenderMessage::Tile(pixels) => pixels.iter().for_each(&mut store),
+            RenderMessage::Progress(_) => {}
+            RenderMessage::Done => break,
+        }
+    }
+    if options.denoise {
+        colors = bilateral_filter(
+            &colors,
+            width,
+            height,
+            options.denoise_spatial,
+            options.denoise_range,
+        );
+    }
+
+    let rgb = || {
+        colors.iter().map(|&color| {
+            let [r, g, b, _] = to_rgba(color, 1, tm);
+            (r, g, b)
+        })
+    };
+    let output = options.output_path();
+    let path = output.as_path();
+    let result = match format {
+        OutputFormat::Png => {
+            let bytes: Vec<u8> = rgb().flat_map(|(r, g, b)| [r, g, b]).collect();
+            save_png(&bytes, width, height, path).map_err(|e| e.to_string())
+        }
+        OutputFormat::Ppm => {
+            let pixels: Vec<(u8, u8, u8)> = rgb().collect();
+            write_ppm(&pixels, width, height, path).map_err(|e| e.to_string())
+        }
+        OutputFormat::Exr => {
+            let result = if aovs {
+                let aovs = Aovs {
+                    depth: &depth,
+                    normal: &normal,
+                };
+                write_exr_with_aovs(&colors, &aovs, width, height, path)
+            } else {
+                write_exr(&colors, width, height, path)
+            };
+            result.map_err(|e| e.to_string())
+        }
+    };
+
+    match result {
+        Ok(()) => eprintln!("Saved {}", path.display()),
+        Err(err) => eprintln!("Unable to save {}: {}", path.display(), err),
     }
 }
 
 enum RenderMessage {
     Pixel(Pixel),
-    Progress(u32), // Number of pixels rendered so far
+    Tile(Vec<Pixel>), // All pixels of a finished tile
+    // Number of pixels rendered so far, for the window's progress text.
+    #[cfg_attr(not(feature = "window"), allow(dead_code))]
+    Progress(u32),
     Done,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct Pixel {
     pub x: u32,
     pub y: u32,
-    pub color: [u8; 4],
+    // Sum of all samples taken so far, not yet averaged.
+    pub color: Color,
+    pub sample_count: u32,
+    // Depth and normal of the surface seen through the pixel center.
+    pub depth: f64,
+    pub normal: Vec3,
+}
+
+// Averages the accumulated samples, tone maps them and quantizes to 8-bit
+// RGBA.
+fn to_rgba(color: Color, sample_count: u32, tm: ToneMap) -> [u8; 4] {
+    let mapped = tone_map(color / sample_count as f64, tm);
+
+    [
+        (255.99 * mapped.x()) as u8,
+        (255.99 * mapped.y()) as u8,
+        (255.99 * mapped.z()) as u8,
+        255,
+    ]
 }
 
-fn render(s: Sender<RenderMessage>) {
+struct Scene {
+    world: HittableList,
+    // Sampled directly at every diffuse bounce. Empty for scenes lit by
+    // their background.
+    lights: LightList,
+    background: Box<dyn Background>,
+    camera: Box<dyn RayGenerator>,
+    config: RenderConfig,
+    // How sub-pixel positions are picked.
+    sampler_kind: SamplerKind,
+    mode: RenderMode,
+    // Stops sampling pixels once they converge, if set.
+    adaptive: Option<AdaptiveSampler>,
+    // Renders with photon mapping instead of path tracing, if set.
+    #[cfg(feature = "photon-mapping")]
+    photon_map: Option<PhotonMap>,
+}
+
+// Sets up the preset named `path`, loads the scene file at `path`, or sets
+// up the default scene if None.
+fn build_scene(path: Option<&Path>) -> Result<Scene, String> {
+    if let Some(path) = path {
+        if let Some(scene) = path.to_str().and_then(preset_scene) {
+            return scene;
+        }
+        return load_scene_file(path);
+    }
+
     // World
-    let world = sebi_scene();
+    let config = RenderConfig::default();
+    let world = scenes::sebi_scene();
+    // The sky's sun is sampled as a light, so it lights the spheres, not
+    // just the sky.
+    let sky = RayleighSky::default();
+    let mut lights = LightList::default();
+    lights.add(Arc::new(sky.sun_light(3.0)));
+    let background = Box::new(sky);
 
     let lookfrom = Point3::new(4.5, 2.5, 18.0);
     let lookat = Point3::new(4.5, 1.8, 0.0);
@@ -143,248 +576,520 @@ fn render(s: Sender<RenderMessage>) {
         lookat,
         vup,
         50.0,
-        ASPECT_RATIO,
+        config.aspect_ratio(),
         aperture,
         dist_to_focus,
         Some((0.0, 1.0)),
     );
 
+    Ok(Scene::with_defaults(
+        world, lights, background, camera, config,
+    ))
+}
+
+// Scenes built into the binary that --scene accepts by name. None if there
+// is no preset called `name`, or the error if the preset can't be set up.
+fn preset_scene(name: &str) -> Option<Result<Scene, String>> {
+    let default_config = RenderConfig::default();
+    let aspect_ratio = default_config.aspect_ratio();
+    let black = || Box::new(ConstantBackground(Color::new(0.0, 0.0, 0.0)));
+
+    let scene = match name {
+        "cornell" => {
+            let (world, camera) = scenes::cornell_box(aspect_ratio);
+            // The room is lit by a small light only, so it needs more
+            // samples than the default scene to settle.
+            let config = RenderConfig {
+                samples: 500,
+                ..default_config
+            };
+            Scene::with_defaults(world, scenes::cornell_lights(), black(), camera, config)
+        }
+        "cornell-smoke" => {
+            let (world, camera) = scenes::cornell_smoke(aspect_ratio);
+            let config = RenderConfig {
+                samples: 500,
+                ..default_config
+            };
+            Scene::with_defaults(world, scenes::cornell_lights(), black(), camera, config)
+        }
+        "earth" => {
+            let (world, camera) = match scenes::earth(aspect_ratio) {
+                Ok(earth) => earth,
+                Err(err) => return Some(Err(err)),
+            };
+            let lights = scenes::earth_lights();
+            Scene::with_defaults(world, lights, black(), camera, default_config)
+        }
+        "pool" => {
+            let (world, camera) = scenes::pool(aspect_ratio);
+            let lights = scenes::pool_lights();
+            Scene::with_defaults(world, lights, black(), camera, default_config)
+        }
+        "sdf-blob" => {
+            let (world, camera) = scenes::sdf_blob(aspect_ratio);
+            let sky = RayleighSky::default();
+            let mut lights = LightList::default();
+            lights.add(Arc::new(sky.sun_light(3.0)));
+            Scene::with_defaults(world, lights, Box::new(sky), camera, default_config)
+        }
+        "sun" => {
+            let (world, camera) = scenes::sun(aspect_ratio);
+            // The real sun's size, for crisp shadows.
+            let lights = scenes::sun_lights(0.0047);
+            let sky = Box::new(scenes::sun_sky());
+            Scene::with_defaults(world, lights, sky, camera, default_config)
+        }
+        "god-rays" => {
+            let (world, camera) = scenes::god_rays(aspect_ratio);
+            // Light scattered by the fog is noisy, like a small light.
+            let config = RenderConfig {
+                samples: 500,
+                ..default_config
+            };
+            Scene::with_defaults(world, scenes::god_rays_lights(), black(), camera, config)
+        }
+        "prism" => {
+            // The light is a box, which can't be sampled directly.
+            let (world, camera) = scenes::prism(aspect_ratio);
+            let config = RenderConfig {
+                samples: 500,
+                ..default_config
+            };
+            Scene::with_defaults(world, LightList::default(), black(), camera, config)
+        }
+        "rect-light" => {
+            let (world, camera) = scenes::rect_light(aspect_ratio);
+            let lights = scenes::rect_light_lights();
+            Scene::with_defaults(world, lights, black(), camera, default_config)
+        }
+        "mandelbulb" => {
+            let (world, camera) = scenes::mandelbulb(aspect_ratio);
+            let sky = RayleighSky::default();
+            let mut lights = LightList::default();
+            lights.add(Arc::new(sky.sun_light(3.0)));
+            Scene::with_defaults(world, lights, Box::new(sky), camera, default_config)
+        }
+        "marble-wood" => {
+            let (world, camera) = scenes::marble_wood(aspect_ratio);
+            let sky = RayleighSky::default();
+            let mut lights = LightList::default();
+            lights.add(Arc::new(sky.sun_light(3.0)));
+            Scene::with_defaults(world, lights, Box::new(sky), camera, default_config)
+        }
+        "ggx" => {
+            let (world, camera) = scenes::ggx(aspect_ratio);
+            let sky = RayleighSky::default();
+            let mut lights = LightList::default();
+            lights.add(Arc::new(sky.sun_light(3.0)));
+            Scene::with_defaults(world, lights, Box::new(sky), camera, default_config)
+        }
+        "thin-film" => {
+            let (world, camera) = scenes::thin_film(aspect_ratio);
+            let sky = RayleighSky::default();
+            let mut lights = LightList::default();
+            lights.add(Arc::new(sky.sun_light(3.0)));
+            Scene::with_defaults(world, lights, Box::new(sky), camera, default_config)
+        }
+        "velvet" => {
+            let (world, camera) = scenes::velvet(aspect_ratio);
+            let sky = RayleighSky::default();
+            let mut lights = LightList::default();
+            lights.add(Arc::new(sky.sun_light(3.0)));
+            Scene::with_defaults(world, lights, Box::new(sky), camera, default_config)
+        }
+        "normal-map" => {
+            let (world, camera) = scenes::normal_map(aspect_ratio);
+            let lights = scenes::normal_map_lights();
+            Scene::with_defaults(world, lights, black(), camera, default_config)
+        }
+        _ => return None,
+    };
+
+    Some(Ok(scene))
+}
+
+// Scene files are JSON, or TOML if their name ends in .toml.
+#[cfg(feature = "serde")]
+fn load_scene_file(path: &Path) -> Result<Scene, String> {
+    let loaded = if path.extension().is_some_and(|ext| ext == "toml") {
+        load_toml(path)
+    } else {
+        load_scene(path)
+    };
+
+    let error = |err: &dyn std::fmt::Display| format!("Unable to load {}: {}", path.display(), err);
+    let loaded = loaded.map_err(|err| error(&err))?;
+    let background = loaded.background.build().map_err(|err| error(&err))?;
+
+    Ok(Scene::with_defaults(
+        loaded.world,
+        loaded.lights,
+        background,
+        loaded.camera,
+        loaded.config,
+    ))
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_scene_file(_path: &Path) -> Result<Scene, String> {
+    Err("Scene files need the serde feature".to_string())
+}
+
+// Puts all bounded objects into a BVH. Unbounded ones, like planes, can't
+// be part of it and are tested separately.
+fn accelerate(list: HittableList) -> HittableList {
+    let (bounded, unbounded): (Vec<_>, Vec<_>) = list
+        .objects
+        .into_iter()
+        .partition(|object| object.bounding_box(0.0, 1.0).is_some());
+
+    let mut world = HittableList { objects: unbounded };
+    if !bounded.is_empty() {
+        let bvh = BvhNode::build_parallel(bounded, 0.0, 1.0);
+        world.add(FlatBvh::from(bvh));
+    }
+
+    world
+}
+
+impl Scene {
+    // A scene of `world`, put into a BVH, with the default sampler and mode
+    // and neither adaptive sampling nor a photon map. prepare_scene applies
+    // the command line's choices afterwards.
+    fn with_defaults(
+        world: HittableList,
+        lights: LightList,
+        background: Box<dyn Background>,
+        camera: impl RayGenerator + 'static,
+        config: RenderConfig,
+    ) -> Scene {
+        Scene {
+            world: accelerate(world),
+            lights,
+            background,
+            camera: Box::new(camera),
+            config,
+            sampler_kind: SamplerKind::default(),
+            mode: RenderMode::default(),
+            adaptive: None,
+            #[cfg(feature = "photon-mapping")]
+            photon_map: None,
+        }
+    }
+
+    // Traces one sample through pixel (i, j), counted from the bottom left.
+    fn sample(&self, sampler: &mut impl Sampler, i: u32, j: u32) -> Color {
+        let (du, dv) = sampler.next_2d();
+        let u = (i as f64 + du) / (self.config.width - 1) as f64;
+        let v = (j as f64 + dv) / (self.config.height - 1) as f64;
+        let ray = self.camera.get_ray(u, v);
+        #[cfg(feature = "photon-mapping")]
+        if let Some(photon_map) = &self.photon_map {
+            return photon_map.radiance(
+                &ray,
+                &self.world,
+                &self.lights,
+                self.background.as_ref(),
+                self.config.max_depth as i32,
+            );
+        }
+        ray.color(
+            self.mode,
+            &self.world,
+            &self.lights,
+            self.background.as_ref(),
+            self.config.max_depth as i32,
+        )
+    }
+
+    // Depth and normal of the first surface seen through the center of
+    // pixel (i, j), for the EXR output's extra layers.
+    fn primary_hit(&self, i: u32, j: u32) -> (f64, Vec3) {
+        let u = (i as f64 + 0.5) / (self.config.width - 1) as f64;
+        let v = (j as f64 + 0.5) / (self.config.height - 1) as f64;
+        let ray = self.camera.get_ray(u, v);
+        match self.world.hit(&ray, 0.001, f64::MAX) {
+            Some(hit) => (hit.t, hit.normal),
+            None => (f64::INFINITY, Vec3::new(0.0, 0.0, 0.0)),
+        }
+    }
+
+    // A fresh sampler for the samples of one pixel, or for each batch of
+    // them when sampling adaptively.
+    fn sampler(&self) -> AnySampler {
+        let samples = match &self.adaptive {
+            Some(adaptive) => adaptive.min_samples,
+            None => self.config.samples,
+        };
+        AnySampler::new(self.sampler_kind, samples)
+    }
+}
+
+// Renders the tiles that `checkpoint` doesn't have yet, saving each one to
+// out.checkpoint as it finishes. The pixels of the saved tiles are sent
+// as they are: every pixel has its own random stream, so they are the same
+// as if they were rendered again.
+fn render(scene: &Scene, checkpoint: Checkpoint, s: Sender<RenderMessage>) {
+    let RenderConfig {
+        width,
+        height,
+        samples,
+        ..
+    } = scene.config;
+
     eprintln!("Start Render!");
+    let start = Instant::now();
+
+    let path = Path::new(CHECKPOINT_PATH);
+    let mut writer = CheckpointWriter::create(path, checkpoint.seed, &checkpoint.settings)
+        .map_err(|err| {
+            // Builds without checkpoints render without saying so each time.
+            if err.kind() != io::ErrorKind::Unsupported {
+                eprintln!("Unable to write {}: {}", path.display(), err);
+            }
+        })
+        .ok();
+
+    let mut finished = vec![false; tiles(width, height, TILE_SIZE).len()];
+    let mut resumed_pixels = 0;
+    for (index, pixels) in checkpoint.tiles {
+        match finished.get_mut(index as usize) {
+            Some(done) if !*done => *done = true,
+            _ => continue,
+        }
+        save_tile(&mut writer, index, &pixels);
+        resumed_pixels += pixels.len() as u32;
+        let _ = s.send(RenderMessage::Tile(pixels));
+    }
+
+    let pixel_count = Arc::new(AtomicU32::new(resumed_pixels));
+    let writer = Mutex::new(writer);
+
+    let render_pixel = |i, j| {
+        let mut sampler = scene.sampler();
+        let (color, sample_count) = match &scene.adaptive {
+            Some(adaptive) => {
+                let stats = adaptive.sample_pixel(|| scene.sample(&mut sampler, i, j));
+                (stats.mean() * stats.count() as f64, stats.count())
+            }
+            None => {
+                let color: Color = (0..samples)
+                    .map(|_| scene.sample(&mut sampler, i, j))
+                    .sum();
+                (color, samples)
+            }
+        };
+
+        let (depth, normal) = scene.primary_hit(i, j);
+        Pixel {
+            x: i,
+            y: height - 1 - j,
+            color,
+            sample_count,
+            depth,
+            normal,
+        }
+    };
+    let on_tile = |index, pixels: Vec<Pixel>| {
+        save_tile(&mut writer.lock().unwrap(), index as u32, &pixels);
 
-    let pixel_count = Arc::new(AtomicU32::new(0));
+        let tile_pixels = pixels.len() as u32;
+        let result = s.send(RenderMessage::Tile(pixels));
 
-    (0..IMAGE_HEIGHT).into_par_iter().rev().for_each(|j| {
-        (0..IMAGE_WIDTH).for_each(|i| {
-            let color: Color = (0..SAMPLES_PER_PIXEL)
-                .map(|_| {
-                    let u = (i as f64 + random_float()) / (IMAGE_WIDTH - 1) as f64;
-                    let v = (j as f64 + random_float()) / (IMAGE_HEIGHT - 1) as f64;
-                    let ray = camera.get_ray(u, v);
-                    ray.color(&world, MAX_DEPTH)
-                })
-                .sum();
+        let count = pixel_count.fetch_add(tile_pixels, Ordering::Relaxed) + tile_pixels;
+        let _ = s.send(RenderMessage::Progress(count));
 
+        // If send fails, the window was closed or moved on to another
+        // render, so the remaining tiles are skipped
+        result.map_err(|_| ())
+    };
+    let rendered = render_tiles(
+        width,
+        height,
+        |index| finished[index],
+        render_pixel,
+        on_tile,
+    );
+    if rendered.is_err() {
+        return;
+    }
+
+    eprintln!("Render took {:.2?}", start.elapsed());
+    eprintln!("{}", RENDER_STATS.summary(start.elapsed()));
+
+    // Nothing is left to resume.
+    drop(writer);
+    let _ = fs::remove_file(path);
+
+    // Send completion message
+    let _ = s.send(RenderMessage::Done);
+}
+
+// Adds a finished tile to the checkpoint. Stops checkpointing if writing
+// fails, since the file can't be resumed from beyond the failed tile.
+fn save_tile(writer: &mut Option<CheckpointWriter>, index: u32, pixels: &[Pixel]) {
+    let Some(out) = writer else {
+        return;
+    };
+    if let Err(err) = out.write_tile(index, pixels) {
+        eprintln!("Unable to write {}: {}", CHECKPOINT_PATH, err);
+        *writer = None;
+    }
+}
+
+// Renders the image in one pass per sample that each add one sample to
+// every pixel, sending the running sums after each pass. The first passes
+// are noisy but cover the whole image, and it sharpens as passes go by.
+fn render_progressive(scene: &Scene, s: Sender<RenderMessage>) {
+    let RenderConfig {
+        width,
+        height,
+        samples,
+        ..
+    } = scene.config;
+
+    eprintln!("Start progressive Render!");
+    let start = Instant::now();
+
+    let pixel_total = (width * height) as usize;
+    let mut accumulated = vec![Color::new(0.0, 0.0, 0.0); pixel_total];
+    // One sampler per pixel, so each pass takes the next stratum or point of
+    // the sequence.
+    let (mut samplers, primary_hits): (Vec<AnySampler>, Vec<(f64, Vec3)>) =
+        (0..pixel_total as u32)
+            .into_par_iter()
+            .map(|index| {
+                rng::seed_stream(index as u64);
+                let sampler = scene.sampler();
+                (sampler, scene.primary_hit(index % width, index / width))
+            })
+            .unzip();
+
+    for pass in 1..=samples {
+        accumulated
+            .par_chunks_mut(width as usize)
+            .zip(samplers.par_chunks_mut(width as usize))
+            .enumerate()
+            .for_each(|(j, (row, row_samplers))| {
+                for (i, (sum, sampler)) in row.iter_mut().zip(row_samplers).enumerate() {
+                    // Every pass of every pixel has its own stream. Pass 0
+                    // is the one of the samplers and primary hits above.
+                    let index = (j * width as usize + i) as u64;
+                    rng::seed_stream(pass as u64 * pixel_total as u64 + index);
+                    *sum += scene.sample(sampler, i as u32, j as u32);
+                }
+            });
+
+        for (index, color) in accumulated.iter().enumerate() {
+            let i = index as u32 % width;
+            let j = index as u32 / width;
             let result = s.send(RenderMessage::Pixel(Pixel {
                 x: i,
-                y: IMAGE_HEIGHT - j,
-                color: [
-                    (255.99
-                        * (color.x() / SAMPLES_PER_PIXEL as f64)
-                            .sqrt()
-                            .max(0.0)
-                            .min(1.0)) as u8,
-                    (255.99
-                        * (color.y() / SAMPLES_PER_PIXEL as f64)
-                            .sqrt()
-                            .max(0.0)
-                            .min(1.0)) as u8,
-                    (255.99
-                        * (color.z() / SAMPLES_PER_PIXEL as f64)
-                            .sqrt()
-                            .max(0.0)
-                            .min(1.0)) as u8,
-                    255,
-                ],
+                y: height - 1 - j,
+                color: *color,
+                sample_count: pass,
+                depth: primary_hits[index].0,
+                normal: primary_hits[index].1,
             }));
-            
-            // Update counter and send progress every 100 pixels
-            let count = pixel_count.fetch_add(1, Ordering::Relaxed) + 1;
-            if count % 100 == 0 {
-                let _ = s.send(RenderMessage::Progress(count));
-            }
-            
+
             // If send fails, window was closed, so we can stop rendering
             if result.is_err() {
                 return;
             }
-        });
-    });
+        }
+    }
+
+    eprintln!("Render took {:.2?}", start.elapsed());
+    eprintln!("{}", RENDER_STATS.summary(start.elapsed()));
 
-    // Send completion message
     let _ = s.send(RenderMessage::Done);
 }
 
-fn sebi_scene() -> HittableList {
-    let mut world = HittableList::default();
-    let m1 = Lambertian::new(Color::new(0.5, 0.5, 0.5));
-    world.add(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        m1,
-    ));
-
-    let sphere_radius = 0.3;
-    let spacing = 0.7;
-    let primary_mat = Metal::new(Color::new(0.8, 0.2, 0.2), 0.1);
-    let secondary_mat = Dielectric::new(1.5);
-    
-    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0 + spacing * 1.3, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0, sphere_radius + spacing * 0.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 0.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 0.7, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(1.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2 + spacing, sphere_radius + spacing * 1.5, 0.0), sphere_radius, secondary_mat));
-    world.add(Sphere::new(Point3::new(7.8, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2 + spacing * 1.5, sphere_radius + spacing * 2.5, 0.0), sphere_radius, secondary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2 + spacing * 0.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, secondary_mat));
-    world.add(Sphere::new(Point3::new(10.6 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2, sphere_radius + spacing * 2.5, 0.0), sphere_radius, secondary_mat));
-    world.add(Sphere::new(Point3::new(6.5, 0.7, -4.5), 0.7, Metal::new(Color::new(0.2, 0.8, 0.3), 0.1)));
-    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(1.5 + spacing * 0.5, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(0.5, 1.0, -4.0), 1.0, Dielectric::new(1.5)));
-    world.add(Sphere::new(Point3::new(10.6, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0 + spacing * 0.7, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(2.0, 0.55, -8.0), 0.55, Metal::new(Color::new(0.95, 0.6, 0.2), 0.15)));
-    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(8.5, 0.8, -3.5), 0.8, Metal::new(Color::new(0.95, 0.85, 0.3), 0.0)));
-    world.add(Sphere::new(Point3::new(1.5 + spacing, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.1, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0, 0.6, -7.0), 0.6, Lambertian::new(Color::new(0.8, 0.3, 0.7))));
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 1.4, sphere_radius + spacing * 0.5, 0.0), sphere_radius, primary_mat));
-    
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.8, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.8, sphere_radius + spacing * 0.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 1.5, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0, 0.4, -6.5), 0.4, Lambertian::new(Color::new(0.9, 0.4, 0.8))));
-    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.8, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(1.5 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 0.5, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2 + spacing * 1.5, sphere_radius + spacing * 2.0, 0.0), sphere_radius, secondary_mat));
-    world.add(Sphere::new(Point3::new(-7.0, 0.6, -4.0), 0.6, Lambertian::new(Color::new(0.2, 0.4, 0.8))));
-    world.add(Sphere::new(Point3::new(-2.0, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(3.0, 0.5, -6.0), 0.5, Metal::new(Color::new(0.4, 0.9, 0.4), 0.0)));
-    world.add(Sphere::new(Point3::new(-6.0 + spacing * 1.4, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2 + spacing, sphere_radius + spacing * 3.0, 0.0), sphere_radius, secondary_mat));
-    world.add(Sphere::new(Point3::new(-2.0 + spacing, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(13.2 + spacing * 0.75, sphere_radius + spacing * 0.3, 0.0), sphere_radius * 0.8, secondary_mat));
-    world.add(Sphere::new(Point3::new(-3.5, 0.7, -7.5), 0.7, Metal::new(Color::new(0.9, 0.5, 0.1), 0.2)));
-    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-9.0, 0.5, -5.0), 0.5, Dielectric::new(1.5)));
-    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-4.0, 0.8, -5.0), 0.8, Lambertian::new(Color::new(0.3, 0.6, 0.9))));
-    world.add(Sphere::new(Point3::new(1.5 + spacing * 1.5, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-1.0, 0.65, -5.5), 0.65, Metal::new(Color::new(0.9, 0.8, 0.2), 0.05)));
-    world.add(Sphere::new(Point3::new(1.5 + spacing, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(-2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(1.5 + spacing, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(5.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat));
-    world.add(Sphere::new(Point3::new(7.8 + spacing * 1.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat));
-    world
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[allow(dead_code)]
-fn test_scene() -> HittableList {
-    let mut world = HittableList::default();
-
-    let material_ground = Lambertian::new(Color::new(0.8, 0.8, 0.0));
-    let material_center = Lambertian::new(Color::new(0.1, 0.2, 0.5));
-    let material_left = Dielectric::new(1.5);
-    let material_right = Metal::new(Color::new(0.8, 0.6, 0.2), 1.0);
-
-    world.add(Sphere::new(
-        Point3::new(0.0, -100.5, -1.0),
-        100.0,
-        material_ground,
-    ));
-    world.add(Sphere::new(
-        Point3::new(0.0, 0.0, -1.0),
-        0.5,
-        material_center,
-    ));
-    world.add(Sphere::new(
-        Point3::new(-1.0, 0.0, -1.0),
-        0.5,
-        material_left,
-    ));
-    world.add(Sphere::new(
-        Point3::new(-1.0, 0.0, -1.0),
-        -0.45,
-        material_left,
-    ));
-    world.add(Sphere::new(
-        Point3::new(1.0, 0.0, -1.0),
-        0.5,
-        material_right,
-    ));
+    fn parse(args: &[&str]) -> Result<Options, clap::Error> {
+        parse_args_from(std::iter::once("tracy").chain(args.iter().copied()))
+    }
 
-    world
-}
+    #[test]
+    fn render_settings_come_from_the_scene_unless_given() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.width, None);
+        assert_eq!(options.samples, None);
+        assert_eq!(options.sampler, SamplerKind::default());
+        assert_eq!(options.mode, RenderMode::default());
 
-#[allow(dead_code)]
-fn random_scene() -> HittableList {
-    let mut world = HittableList::default();
-
-    let ground_material = Lambertian::new(Color::new(0.5, 0.5, 0.5));
-    world.add(Sphere::new(
-        Point3::new(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material,
-    ));
-
-    for a in -11..11 {
-        let a = f64::from(a);
-
-        for b in -11..11 {
-            let b = f64::from(b);
-
-            let choose_mat = random_float();
-            let center = Point3::new(a + 0.9 * random_float(), 0.2, b + 0.9 * random_float());
-
-            if (center - Point3::new(4.0, 0.2, 0.0)).length() > 0.9 {
-                if choose_mat < 0.8 {
-                    // diffuse
-                    let albedo = Color::random() * Color::random();
-                    let material = Lambertian::new(albedo);
-                    let center2 = center + Point3::new(0.0, random_float_between(0.0, 0.5), 0.0);
-                    world.add(MovingSphere::new(center, center2, 0.0, 1.0, 0.2, material));
-                } else if choose_mat < 0.95 {
-                    // metal
-                    let albedo = Color::random_between(0.5, 1.0);
-                    let fuzz = random_float_between(0.0, 0.5);
-                    let material = Metal::new(albedo, fuzz);
-                    world.add(Sphere::new(center, 0.2, material));
-                } else {
-                    // glass
-                    let material = Dielectric::new(1.5);
-                    world.add(Sphere::new(center, 0.2, material));
-                }
-            }
+        let options = parse(&["--width", "640", "--samples", "16", "--max-depth", "8"]).unwrap();
+        let config = override_config(RenderConfig::default(), &options);
+        assert_eq!((config.width, config.height), (640, 427));
+        assert_eq!((config.samples, config.max_depth), (16, 8));
+    }
+
+    #[test]
+    fn invalid_render_settings_are_rejected() {
+        for args in [
+            ["--width", "0"],
+            ["--height", "1"],
+            ["--samples", "0"],
+            ["--max-depth", "0"],
+            ["--width", "wide"],
+            ["--adaptive", "0"],
+        ] {
+            let err = parse(&args).err().unwrap();
+            assert_eq!(err.kind(), ErrorKind::ValueValidation, "{:?}", args);
         }
     }
 
-    let material1 = Dielectric::new(1.5);
-    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, material1));
+    #[test]
+    fn jobs_default_to_one_thread_per_core() {
+        assert_eq!(parse(&[]).unwrap().jobs, 0);
+        assert_eq!(parse(&["--jobs", "3"]).unwrap().jobs, 3);
+        assert!(parse(&["--jobs", "all"]).is_err());
+    }
+
+    #[test]
+    fn output_format_follows_the_extension() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.output_format(), Ok(OutputFormat::Png));
+        assert_eq!(options.output_path(), PathBuf::from("out.png"));
 
-    let material2 = Lambertian::new(Color::new(0.4, 0.2, 0.1));
-    world.add(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, material2));
+        for (path, format) in [
+            ("render.exr", OutputFormat::Exr),
+            ("render.ppm", OutputFormat::Ppm),
+            ("render.PNG", OutputFormat::Png),
+        ] {
+            let options = parse(&["--output", path]).unwrap();
+            assert_eq!(options.output_format(), Ok(format));
+            assert_eq!(options.output_path(), PathBuf::from(path));
+        }
+    }
 
-    let material3 = Metal::new(Color::new(0.7, 0.6, 0.5), 0.0);
-    world.add(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3));
+    #[test]
+    fn format_overrides_the_extension() {
+        let options = parse(&["--output", "render.img", "--format", "ppm"]).unwrap();
+        assert_eq!(options.output_format(), Ok(OutputFormat::Ppm));
 
-    world
+        let options = parse(&["--format", "EXR"]).unwrap();
+        assert_eq!(options.output_path(), PathBuf::from("out.exr"));
+    }
+
+    #[test]
+    fn unknown_formats_list_the_supported_ones() {
+        let err = parse(&["--output", "render.jpg"]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+        assert!(err.to_string().contains(".png, .ppm and .exr"));
+
+        let err = parse(&["--format", "jpg"]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("png, ppm, exr"));
+    }
+
+    #[test]
+    fn resume_conflicts_with_progressive() {
+        let err = parse(&["--resume", "--progressive"]).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
 }
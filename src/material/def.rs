@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use crate::{hittable::medium::god_ray::GodRayMedium, texture::def::TextureDef, Color, Vec3};
+
+use super::{
+    dielectric::Dielectric,
+    diffuse_light::DiffuseLight,
+    ggx::GGX,
+    henyey_greenstein::HenyeyGreenstein,
+    isotropic::Isotropic,
+    kubelka_munk::{KubelkaMunk, Layer},
+    lambertian::Lambertian,
+    metal::Metal,
+    normal_map::NormalMappedMaterial,
+    oren_nayar::OrenNayar,
+    sss::SubSurfaceScattering,
+    stochastic_transparency::StochasticTransparency,
+    thin_film::ThinFilm,
+    velvet::Velvet,
+    Material,
+};
+
+// Plain description of a built-in material. Scenes store materials as
+// `Arc<dyn Material>`, which can't be inspected or serialized; this enum can,
+// and converts back and forth with `build` and `Material::definition`.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum MaterialDef {
+    Lambertian {
+        albedo: TextureDef,
+    },
+    Metal {
+        albedo: TextureDef,
+        fuzz: f64,
+    },
+    Dielectric {
+        index_of_refraction: f64,
+        #[cfg_attr(feature = "serde", serde(default = "clear"))]
+        absorption: Color,
+        #[cfg_attr(feature = "serde", serde(default))]
+        cauchy_b: f64,
+    },
+    DiffuseLight {
+        emit: TextureDef,
+    },
+    Isotropic {
+        albedo: TextureDef,
+    },
+    HenyeyGreenstein {
+        g: f64,
+        albedo: TextureDef,
+    },
+    GodRay {
+        direction: Vec3,
+        intensity: f64,
+        falloff: f64,
+        density: f64,
+    },
+    OrenNayar {
+        albedo: TextureDef,
+        sigma_degrees: f64,
+    },
+    Ggx {
+        albedo: TextureDef,
+        roughness: f64,
+        f0: Color,
+    },
+    SubSurfaceScattering {
+        albedo: TextureDef,
+        scatter_distance: f64,
+    },
+    Velvet {
+        color: TextureDef,
+        sheen: f64,
+    },
+    ThinFilm {
+        base_ior: f64,
+        film_ior: f64,
+        thickness_nm: f64,
+    },
+    KubelkaMunk {
+        layers: Vec<Layer>,
+        substrate: Color,
+    },
+    NormalMapped {
+        inner: Box<MaterialDef>,
+        normal_map: TextureDef,
+    },
+    StochasticTransparency {
+        inner: Box<MaterialDef>,
+        opacity: f64,
+    },
+}
+
+#[cfg(feature = "serde")]
+fn clear() -> Color {
+    Color::new(0.0, 0.0, 0.0)
+}
+
+impl MaterialDef {
+    pub fn build(&self) -> Arc<dyn Material> {
+        match self {
+            MaterialDef::Lambertian { albedo } => {
+                Arc::new(Lambertian::from_texture(albedo.build()))
+            }
+            MaterialDef::Metal { albedo, fuzz } => {
+                Arc::new(Metal::from_texture(albedo.build(), *fuzz))
+            }
+            MaterialDef::Dielectric {
+                index_of_refraction,
+                absorption,
+                cauchy_b,
+            } => Arc::new(Dielectric {
+                cauchy_b: *cauchy_b,
+                ..Dielectric::with_absorption(*index_of_refraction, *absorption)
+            }),
+            MaterialDef::DiffuseLight { emit } => {
+                Arc::new(DiffuseLight::from_texture(emit.build()))
+            }
+            MaterialDef::Isotropic { albedo } => Arc::new(Isotropic::from_texture(albedo.build())),
+            MaterialDef::HenyeyGreenstein { g, albedo } => {
+                Arc::new(HenyeyGreenstein::from_texture(albedo.build(), *g))
+            }
+            MaterialDef::GodRay {
+                direction,
+                intensity,
+                falloff,
+                density,
+            } => Arc::new(GodRayMedium::new(
+                *direction, *intensity, *falloff, *density,
+            )),
+            MaterialDef::OrenNayar {
+                albedo,
+                sigma_degrees,
+            } => Arc::new(OrenNayar::from_texture(albedo.build(), *sigma_degrees)),
+            MaterialDef::Ggx {
+                albedo,
+                roughness,
+                f0,
+            } => Arc::new(GGX::from_texture(albedo.build(), *roughness, *f0)),
+            MaterialDef::SubSurfaceScattering {
+                albedo,
+                scatter_distance,
+            } => Arc::new(SubSurfaceScattering::from_texture(
+                albedo.build(),
+                *scatter_distance,
+            )),
+            MaterialDef::Velvet { color, sheen } => {
+                Arc::new(Velvet::from_texture(color.build(), *sheen))
+            }
+            MaterialDef::ThinFilm {
+                base_ior,
+                film_ior,
+                thickness_nm,
+            } => Arc::new(ThinFilm::new(*base_ior, *film_ior, *thickness_nm)),
+            MaterialDef::KubelkaMunk { layers, substrate } => {
+                Arc::new(KubelkaMunk::new(layers.clone(), *substrate))
+            }
+            MaterialDef::NormalMapped { inner, normal_map } => {
+                Arc::new(NormalMappedMaterial::new(inner.build(), normal_map.build()))
+            }
+            MaterialDef::StochasticTransparency { inner, opacity } => {
+                Arc::new(StochasticTransparency::new(inner.build(), *opacity))
+            }
+        }
+    }
+
+    // Whether surfaces of this material give off light, so they can be
+    // sampled as lights.
+    pub fn emits(&self) -> bool {
+        match self {
+            MaterialDef::DiffuseLight { .. } => true,
+            MaterialDef::NormalMapped { inner, .. }
+            | MaterialDef::StochasticTransparency { inner, .. } => inner.emits(),
+            _ => false,
+        }
+    }
+
+    // None if the material, or one of its textures, isn't built in.
+    pub fn from_material(material: &dyn Material) -> Option<Self> {
+        material.definition()
+    }
+}
+
+impl From<&MaterialDef> for Arc<dyn Material> {
+    fn from(def: &MaterialDef) -> Self {
+        def.build()
+    }
+}
+
+impl From<MaterialDef> for Arc<dyn Material> {
+    fn from(def: MaterialDef) -> Self {
+        def.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn emits_looks_through_wrapping_materials() {
+        let light = MaterialDef::DiffuseLight {
+            emit: TextureDef::Solid {
+                color: Color::from(4.0),
+            },
+        };
+        let see_through = MaterialDef::StochasticTransparency {
+            inner: Box::new(light.clone()),
+            opacity: 0.5,
+        };
+        let grey = MaterialDef::Lambertian {
+            albedo: TextureDef::Solid {
+                color: Color::from(0.5),
+            },
+        };
+
+        assert!(light.emits());
+        assert!(see_through.emits());
+        assert!(!grey.emits());
+    }
+
+    // Checks that `material` and `other` scatter a ray alike.
+    #[cfg(feature = "serde")]
+    fn assert_same_scattering(material: &dyn Material, other: &dyn Material) {
+        use crate::{hittable::HitRecord, ray::Ray, rng, Point3, Vec3};
+
+        let rec = |material| HitRecord {
+            p: Point3::from(0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            t: 1.0,
+            u: 0.5,
+            v: 0.5,
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 1.0),
+            front_face: true,
+        };
+        let ray_in = Ray::new(Point3::new(-1.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.0), None);
+        for seed in 0..10 {
+            rng::seed_thread(seed);
+            let a = material.scatter(&ray_in, &rec(material)).unwrap();
+            rng::seed_thread(seed);
+            let b = other.scatter(&ray_in, &rec(other)).unwrap();
+            assert_eq!(a.ray.direction, b.ray.direction);
+            assert_eq!(a.attenuation, b.attenuation);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn materials_round_trip_through_json() {
+        use crate::material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal};
+
+        let lambertian = Lambertian::new(Color::new(0.1, 0.2, 0.5));
+        let parsed: Lambertian =
+            serde_json::from_str(&serde_json::to_string(&lambertian).unwrap()).unwrap();
+        assert_same_scattering(&lambertian, &parsed);
+
+        let metal = Metal::new(Color::new(0.8, 0.6, 0.2), 0.3);
+        let parsed: Metal = serde_json::from_str(&serde_json::to_string(&metal).unwrap()).unwrap();
+        assert_eq!(parsed.fuzz, metal.fuzz);
+        assert_same_scattering(&metal, &parsed);
+
+        let mut glass = Dielectric::with_absorption(1.5, Color::new(0.0, 0.2, 0.4));
+        glass.cauchy_b = 4200.0;
+        let parsed: Dielectric =
+            serde_json::from_str(&serde_json::to_string(&glass).unwrap()).unwrap();
+        assert_eq!(
+            (
+                parsed.index_of_refraction,
+                parsed.absorption,
+                parsed.cauchy_b
+            ),
+            (glass.index_of_refraction, glass.absorption, glass.cauchy_b)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn definitions_convert_to_materials_and_back() {
+        let solid = |r, g, b| TextureDef::Solid {
+            color: Color::new(r, g, b),
+        };
+        let definitions: Vec<MaterialDef> = serde_json::from_str(
+            r#"[
+                { "type": "lambertian", "albedo": { "type": "solid", "color": [0.1, 0.2, 0.5] } },
+                { "type": "metal", "albedo": { "type": "solid", "color": [0.8, 0.6, 0.2] }, "fuzz": 0.3 },
+                { "type": "dielectric", "index_of_refraction": 1.5 }
+            ]"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            &definitions[..],
+            [
+                MaterialDef::Lambertian { .. },
+                MaterialDef::Metal { .. },
+                MaterialDef::Dielectric { .. }
+            ]
+        ));
+
+        let checker = MaterialDef::Lambertian {
+            albedo: TextureDef::Checker {
+                odd: Box::new(solid(0.2, 0.3, 0.1)),
+                even: Box::new(solid(0.9, 0.9, 0.9)),
+                scale: 10.0,
+            },
+        };
+        for definition in definitions.into_iter().chain([checker]) {
+            let material: Arc<dyn Material> = definition.clone().into();
+            let described = material.definition().unwrap();
+            assert_eq!(
+                serde_json::to_value(&described).unwrap(),
+                serde_json::to_value(&definition).unwrap()
+            );
+            assert_same_scattering(material.as_ref(), described.build().as_ref());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn hit_records_serialize_without_their_material() {
+        use crate::{hittable::HitRecord, material::lambertian::Lambertian, Point3, Vec3};
+
+        let material = Lambertian::new(Color::from(0.5));
+        let rec = HitRecord {
+            p: Point3::new(1.0, 2.0, 3.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material: &material,
+            t: 2.5,
+            u: 0.25,
+            v: 0.75,
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 1.0),
+            front_face: true,
+        };
+        let json = serde_json::to_value(&rec).unwrap();
+
+        assert_eq!(json["p"], serde_json::json!([1.0, 2.0, 3.0]));
+        assert_eq!(json["t"], 2.5);
+        assert_eq!(
+            (json["u"].clone(), json["v"].clone()),
+            (0.25.into(), 0.75.into())
+        );
+        assert_eq!(json["front_face"], true);
+        assert!(json.get("material").is_none());
+    }
+}
@@ -1,34 +1,59 @@
 use crate::{hittable::HitRecord, random_float, ray::Ray, Color, Vec3};
 
-use super::Material;
+use super::{def::MaterialDef, thin_film::WAVELENGTHS, Material, ScatterRecord};
 
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dielectric {
+    // A of the Cauchy equation n = A + B / wavelength^2, which is the index
+    // of refraction when B is 0.
     pub index_of_refraction: f64,
+    // Beer-Lambert absorption coefficient per unit length traveled inside
+    // the material. Zero is clear glass.
+    pub absorption: Color,
+    // B of the Cauchy equation in nm^2, around 4000 for crown glass. Zero
+    // doesn't disperse light.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub cauchy_b: f64,
 }
 
 impl Dielectric {
     pub fn new(index_of_refraction: f64) -> Self {
+        Self::with_absorption(index_of_refraction, Color::new(0.0, 0.0, 0.0))
+    }
+
+    pub fn with_absorption(index_of_refraction: f64, absorption: Color) -> Self {
         Self {
             index_of_refraction,
+            absorption,
+            cauchy_b: 0.0,
+        }
+    }
+
+    // Glass that bends short wavelengths more than long ones, splitting
+    // white light into its colors.
+    pub fn with_dispersion(cauchy_a: f64, cauchy_b: f64) -> Self {
+        Self {
+            cauchy_b,
+            ..Self::new(cauchy_a)
         }
     }
 
+    pub fn ior_at_wavelength(&self, wavelength_nm: f64) -> f64 {
+        self.index_of_refraction + self.cauchy_b / (wavelength_nm * wavelength_nm)
+    }
+
     fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
         // Use Schlick's approximation for reflectance.
         let mut r0 = (1.0 - ref_idx) / (1.0 + ref_idx);
         r0 = r0 * r0;
         return r0 + (1.0 - r0) * f64::powi(1.0 - cosine, 5);
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Ray, Color)> {
-        let refraction_ratio = if rec.front_face {
-            1.0 / self.index_of_refraction
-        } else {
-            self.index_of_refraction
-        };
+    // Reflects or refracts with index of refraction `ior`, whatever the
+    // wavelength.
+    fn scatter_with_ior(&self, ray_in: &Ray, rec: &HitRecord, ior: f64) -> ScatterRecord {
+        let refraction_ratio = if rec.front_face { 1.0 / ior } else { ior };
 
         let unit_direction = Vec3::unit_vector(&ray_in.direction);
         let cos_theta = f64::min((-unit_direction).dot(rec.normal), 1.0);
@@ -42,8 +67,177 @@ impl Material for Dielectric {
                 unit_direction.refract(rec.normal, refraction_ratio)
             };
 
-        let attenuation = Color::new(1.0, 1.0, 1.0);
+        // Hitting the back of a surface means the ray got here through the
+        // material, so it was absorbed along the way.
+        let attenuation = if rec.front_face {
+            Color::new(1.0, 1.0, 1.0)
+        } else {
+            let distance = rec.t * ray_in.direction.length();
+            Color::new(
+                f64::exp(-self.absorption.x() * distance),
+                f64::exp(-self.absorption.y() * distance),
+                f64::exp(-self.absorption.z() * distance),
+            )
+        };
         let scattered = Ray::new(rec.p, direction, Some(ray_in.time));
-        Some((scattered, attenuation))
+        ScatterRecord::specular(scattered, attenuation)
+    }
+}
+
+impl Material for Dielectric {
+    // Dispersive glass sends each of red, green and blue its own way, as if
+    // the image was rendered once per channel at the wavelengths of
+    // `thin_film` and composited. The first hit on such glass picks one
+    // channel at random, and the path carries on with only that one, three
+    // times as bright so the average over the choices is the same. Later
+    // hits bend it by the index of that channel.
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        if self.cauchy_b == 0.0 {
+            return Some(self.scatter_with_ior(ray_in, rec, self.index_of_refraction));
+        }
+
+        let (channel, weight) = match ray_in.channel {
+            Some(channel) => (channel, 1.0),
+            None => (((random_float() * 3.0) as usize).min(2), 3.0),
+        };
+        let mut scatter =
+            self.scatter_with_ior(ray_in, rec, self.ior_at_wavelength(WAVELENGTHS[channel]));
+        let mut only = Color::new(0.0, 0.0, 0.0);
+        only[channel] = weight * scatter.attenuation[channel];
+        scatter.attenuation = only;
+        scatter.ray.channel = Some(channel);
+        Some(scatter)
+    }
+
+    #[cfg(feature = "spectral")]
+    fn scatter_spectral(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        Some(self.scatter_with_ior(ray_in, rec, self.ior_at_wavelength(wavelength)))
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::Dielectric {
+            index_of_refraction: self.index_of_refraction,
+            absorption: self.absorption,
+            cauchy_b: self.cauchy_b,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assert_vec3_approx_eq,
+        background::constant::ConstantBackground,
+        hittable::{aabox::AaBox, light_list::LightList, sphere::Sphere, Hittable},
+        ray::RenderMode,
+        rng, Point3,
+    };
+
+    fn prism_glass() -> Sphere<Dielectric> {
+        Sphere::new(
+            Point3::from(0.0),
+            1.0,
+            Dielectric::with_dispersion(1.5, 12000.0),
+        )
+    }
+
+    // A color that is `value` in `channel` and black in the others.
+    fn only(channel: usize, value: f64) -> Color {
+        let mut color = Color::from(0.0);
+        color[channel] = value;
+        color
+    }
+
+    #[test]
+    fn dispersion_picks_the_channel_once_per_path() {
+        rng::seed_thread(3);
+        let glass = prism_glass();
+        let ray = Ray::new(Point3::new(0.2, 0.1, -5.0), Vec3::new(0.0, 0.0, 1.0), None);
+        let hit = glass.hit(&ray, 0.001, f64::MAX).unwrap();
+
+        for _ in 0..20 {
+            let first = glass.material.scatter(&ray, &hit).unwrap();
+            let channel = first.ray.channel.unwrap();
+            assert_vec3_approx_eq!(first.attenuation, only(channel, 3.0), 1e-12);
+
+            // Light reflected off the outside doesn't hit the glass again.
+            let inside = ray.continued_by(first.ray);
+            let Some(hit) = glass.hit(&inside, 0.001, f64::MAX) else {
+                continue;
+            };
+            let second = glass.material.scatter(&inside, &hit).unwrap();
+            assert_eq!(second.ray.channel, Some(channel));
+            let kept = second.attenuation[channel];
+            assert!(kept <= 1.0);
+            assert_vec3_approx_eq!(second.attenuation, only(channel, kept), 1e-12);
+        }
+    }
+
+    #[test]
+    fn dispersive_glass_keeps_white_light_white() {
+        rng::seed_thread(4);
+        let glass = prism_glass();
+        let background = ConstantBackground(Color::from(1.0));
+        let samples = 20000;
+        let sum: Color = (0..samples)
+            .map(|_| {
+                let ray = Ray::new(Point3::new(0.3, 0.2, -5.0), Vec3::new(0.0, 0.0, 1.0), None);
+                ray.color(
+                    RenderMode::PathTrace,
+                    &glass,
+                    &LightList::default(),
+                    &background,
+                    50,
+                )
+            })
+            .sum();
+
+        let average = sum / samples as f64;
+        assert_vec3_approx_eq!(average, Color::from(1.0), 0.05);
+    }
+
+    #[test]
+    fn light_is_absorbed_along_its_path_through_the_glass() {
+        rng::seed_thread(5);
+        let absorption = Color::new(0.8, 0.1, 0.8);
+        // A slab two units thick along z.
+        let slab = |glass| {
+            AaBox::new(
+                Point3::new(-1.5, 0.0, -1.0),
+                Point3::new(1.5, 2.0, 1.0),
+                glass,
+            )
+        };
+        let tinted = slab(Dielectric::with_absorption(1.5, absorption));
+        let clear = slab(Dielectric::new(1.5));
+        let ray = Ray::new(Point3::new(0.2, 1.0, -5.0), Vec3::new(0.0, 0.0, 2.0), None);
+        // exp(-absorption * 2) for a straight path through the slab.
+        let transmittance = Color::new(f64::exp(-1.6), f64::exp(-0.2), f64::exp(-1.6));
+
+        let mut crossed = 0;
+        for _ in 0..20 {
+            for (glass, expected) in [(&tinted, transmittance), (&clear, Color::from(1.0))] {
+                let hit = glass.hit(&ray, 0.001, f64::MAX).unwrap();
+                let entering = glass.material.scatter(&ray, &hit).unwrap();
+                assert_eq!(entering.attenuation, Color::from(1.0));
+                if entering.ray.direction.z() < 0.0 {
+                    // Reflected off the front.
+                    continue;
+                }
+
+                let inside = ray.continued_by(entering.ray);
+                let hit = glass.hit(&inside, 0.001, f64::MAX).unwrap();
+                let leaving = glass.material.scatter(&inside, &hit).unwrap();
+                assert_vec3_approx_eq!(leaving.attenuation, expected, 1e-12);
+                crossed += 1;
+            }
+        }
+        assert!(crossed > 30);
     }
 }
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    ray::Ray,
+    texture::{solid_color::SolidColor, Texture},
+    Color, Point3,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+#[derive(Debug, Clone)]
+pub struct DiffuseLight {
+    pub emit: Arc<dyn Texture>,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(emit)))
+    }
+
+    pub fn from_texture(emit: Arc<dyn Texture>) -> Self {
+        Self { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray_in: &Ray, _rec: &HitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Color {
+        self.emit.value(u, v, p)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::DiffuseLight {
+            emit: self.emit.definition()?,
+        })
+    }
+}
@@ -0,0 +1,142 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    hittable::HitRecord,
+    math::onb::Onb,
+    random_float,
+    ray::Ray,
+    texture::{solid_color::SolidColor, Texture},
+    Color, Vec3,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Cook-Torrance microfacet reflection with the GGX normal distribution,
+// the separable Smith shadowing term and Schlick's Fresnel approximation.
+// `f0` is the reflectance at normal incidence (e.g. ~(0.95, 0.64, 0.54)
+// for copper), tinted by `albedo`. Roughness runs from 0 (mirror) to 1.
+#[derive(Debug, Clone)]
+pub struct GGX {
+    pub albedo: Arc<dyn Texture>,
+    pub roughness: f64,
+    pub f0: Color,
+}
+
+impl GGX {
+    pub fn new(albedo: Color, roughness: f64, f0: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), roughness, f0)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, roughness: f64, f0: Color) -> Self {
+        Self {
+            albedo,
+            // A perfectly smooth GGX lobe degenerates to a delta, so keep a
+            // tiny amount of roughness.
+            roughness: roughness.clamp(1e-3, 1.0),
+            f0,
+        }
+    }
+
+    // Smith masking for a single direction with cosine n_dot_v.
+    fn smith_g1(n_dot_v: f64, alpha2: f64) -> f64 {
+        2.0 * n_dot_v / (n_dot_v + f64::sqrt(alpha2 + (1.0 - alpha2) * n_dot_v * n_dot_v))
+    }
+}
+
+impl Material for GGX {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let n = rec.normal;
+        let wo = -ray_in.direction.unit_vector();
+        let n_dot_o = n.dot(wo);
+        if n_dot_o <= 0.0 {
+            return None;
+        }
+
+        // Importance-sample a microfacet normal h from the GGX distribution
+        // D(h) * cos(theta_h), in a frame where n is the z axis.
+        let alpha = self.roughness * self.roughness;
+        let alpha2 = alpha * alpha;
+        let xi1 = random_float();
+        let xi2 = random_float();
+        let cos_theta = f64::sqrt((1.0 - xi1) / (1.0 + (alpha2 - 1.0) * xi1));
+        let sin_theta = f64::sqrt(1.0 - cos_theta * cos_theta);
+        let phi = 2.0 * PI * xi2;
+
+        let h = Onb::build_from_w(n).local(Vec3::new(
+            sin_theta * phi.cos(),
+            sin_theta * phi.sin(),
+            cos_theta,
+        ));
+
+        // Mirror the view direction about the sampled microfacet.
+        let o_dot_h = wo.dot(h);
+        let wi = h * (2.0 * o_dot_h) - wo;
+        let n_dot_i = n.dot(wi);
+        if n_dot_i <= 0.0 || o_dot_h <= 0.0 {
+            return None;
+        }
+
+        // With pdf(wi) = D * (n.h) / (4 * (o.h)), the estimator
+        // BRDF * (n.i) / pdf simplifies to F * G * (o.h) / ((n.o) * (n.h)):
+        // D cancels out.
+        let n_dot_h = n.dot(h);
+        let f0 = self.f0 * self.albedo.value(rec.u, rec.v, rec.p);
+        let fresnel = f0 + (Color::new(1.0, 1.0, 1.0) - f0) * f64::powi(1.0 - o_dot_h, 5);
+        let g = Self::smith_g1(n_dot_o, alpha2) * Self::smith_g1(n_dot_i, alpha2);
+        let attenuation = fresnel * (g * o_dot_h / (n_dot_o * n_dot_h));
+
+        Some(ScatterRecord::new(
+            Ray::new(rec.p, wi, Some(ray_in.time)),
+            attenuation,
+        ))
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::Ggx {
+            albedo: self.albedo.definition()?,
+            roughness: self.roughness,
+            f0: self.f0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng, Point3};
+
+    #[test]
+    fn white_surfaces_never_amplify_light_at_normal_incidence() {
+        let white = Color::new(1.0, 1.0, 1.0);
+        let surface = GGX::new(white, 0.0, white);
+        let rec = HitRecord {
+            p: Point3::from(0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material: &surface,
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 1.0),
+            front_face: true,
+        };
+        let ray_in = Ray::new(Point3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0), None);
+
+        rng::seed_thread(7);
+        for roughness in [0.0, 0.1, 0.3, 0.6, 1.0] {
+            let material = GGX::new(white, roughness, white);
+            for _ in 0..10_000 {
+                // Samples reflected below the surface are absorbed.
+                if let Some(scattered) = material.scatter(&ray_in, &rec) {
+                    let attenuation = scattered.attenuation;
+                    assert!(
+                        attenuation.x() <= 1.0 + 1e-9,
+                        "{roughness}: {attenuation:?}"
+                    );
+                    assert_eq!(attenuation.x(), attenuation.y());
+                    assert_eq!(attenuation.x(), attenuation.z());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    hittable::HitRecord,
+    math::onb::Onb,
+    ray::Ray,
+    sampling,
+    texture::{solid_color::SolidColor, Texture},
+    Color,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Phase function of a participating medium that prefers some directions
+// over others, from Henyey and Greenstein's one-parameter model. `g` is the
+// average cosine of the angle light is turned by, between -1 and 1: 0
+// scatters equally in all directions like `Isotropic`, positive values
+// mostly forwards as fog and clouds do, and negative ones mostly back
+// towards where the light came from.
+#[derive(Debug, Clone)]
+pub struct HenyeyGreenstein {
+    pub g: f64,
+    pub albedo: Arc<dyn Texture>,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Color, g: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), g)
+    }
+
+    // `g` is kept just inside (-1, 1), where the distribution stops being
+    // one.
+    pub fn from_texture(albedo: Arc<dyn Texture>, g: f64) -> Self {
+        Self {
+            g: g.clamp(-0.999, 0.999),
+            albedo,
+        }
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    // The direction is drawn from the phase function exactly, so the
+    // attenuation is just the albedo.
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let direction =
+            Onb::build_from_w(ray_in.direction).local(sampling::henyey_greenstein(self.g));
+        Some(ScatterRecord::new(
+            Ray::new(rec.p, direction, Some(ray_in.time)),
+            self.albedo.value(rec.u, rec.v, rec.p),
+        ))
+    }
+
+    fn scattering_pdf(&self, ray_in: &Ray, _rec: &HitRecord, scattered: &Ray) -> f64 {
+        let cos_theta = ray_in
+            .direction
+            .unit_vector()
+            .dot(scattered.direction.unit_vector());
+        phase(self.g, cos_theta)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::HenyeyGreenstein {
+            g: self.g,
+            albedo: self.albedo.definition()?,
+        })
+    }
+}
+
+// Henyey-Greenstein phase function with asymmetry `g`, per unit solid
+// angle, for light turned by an angle with cosine `cos_theta`.
+pub fn phase(g: f64, cos_theta: f64) -> f64 {
+    let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng;
+
+    const ASYMMETRIES: [f64; 7] = [-0.9, -0.5, -0.1, 0.0, 0.3, 0.8, 0.95];
+
+    #[test]
+    fn phase_integrates_to_one() {
+        // Over the sphere, by the cosine, with the midpoint rule.
+        let steps = 200_000;
+        let d_cos = 2.0 / steps as f64;
+        for g in ASYMMETRIES {
+            let integral: f64 = (0..steps)
+                .map(|i| {
+                    let cos_theta = -1.0 + (i as f64 + 0.5) * d_cos;
+                    2.0 * PI * phase(g, cos_theta) * d_cos
+                })
+                .sum();
+            assert!((integral - 1.0).abs() < 1e-4, "g = {}: {}", g, integral);
+        }
+    }
+
+    #[test]
+    fn sampled_directions_average_to_g() {
+        rng::seed_thread(6);
+        let samples = 200_000;
+        for g in ASYMMETRIES {
+            let mean_cos = (0..samples)
+                .map(|_| sampling::henyey_greenstein(g).z())
+                .sum::<f64>()
+                / samples as f64;
+            assert!((mean_cos - g).abs() < 0.01, "g = {}: {}", g, mean_cos);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    ray::Ray,
+    sampling,
+    texture::{solid_color::SolidColor, Texture},
+    Color,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Phase function of a participating medium that scatters equally in all
+// directions.
+#[derive(Debug, Clone)]
+pub struct Isotropic {
+    pub albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
+        Self { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        Some(ScatterRecord::new(
+            Ray::new(rec.p, sampling::uniform_sphere(), Some(ray_in.time)),
+            self.albedo.value(rec.u, rec.v, rec.p),
+        ))
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::Isotropic {
+            albedo: self.albedo.definition()?,
+        })
+    }
+}
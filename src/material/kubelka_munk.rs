@@ -0,0 +1,250 @@
+use crate::{
+    hittable::HitRecord,
+    pdf::{cosine::CosinePdf, Pdf},
+    ray::Ray,
+    Color, Vec3,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// One coat of paint or glaze: how strongly it absorbs (K) and scatters (S)
+// light per unit of thickness, for each color channel.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layer {
+    pub absorption: Color,
+    pub scattering: Color,
+    pub thickness: f64,
+}
+
+impl Layer {
+    pub fn new(absorption: Color, scattering: Color, thickness: f64) -> Self {
+        Self {
+            absorption,
+            scattering,
+            thickness,
+        }
+    }
+
+    // Mixes pigments the way paint mixes, subtractively: the coefficients of
+    // the mixture are the averages of the pigments' weighted by their
+    // amounts. The mixture is as thick as the weighted average, too.
+    pub fn mix(pigments: &[(Layer, f64)]) -> Self {
+        let total: f64 = pigments.iter().map(|(_, amount)| amount).sum();
+        let weighted = |property: fn(&Layer) -> Color| -> Color {
+            pigments
+                .iter()
+                .map(|(layer, amount)| property(layer) * (amount / total))
+                .sum()
+        };
+
+        Self {
+            absorption: weighted(|layer| layer.absorption),
+            scattering: weighted(|layer| layer.scattering),
+            thickness: pigments
+                .iter()
+                .map(|(layer, amount)| layer.thickness * amount / total)
+                .sum(),
+        }
+    }
+
+    // Kubelka and Munk's two-flux solution for the reflectance and the
+    // transmittance of a layer with nothing behind it, in one channel.
+    fn reflectance_transmittance(k: f64, s: f64, d: f64) -> (f64, f64) {
+        if s <= 0.0 {
+            // Nothing scatters back; the layer only filters.
+            return (0.0, f64::exp(-k * d));
+        }
+
+        let a = 1.0 + k / s;
+        let b = (a * a - 1.0).sqrt();
+        if b < 1e-6 {
+            // No absorption, the limit of the formulas below as b goes to 0.
+            let sd = s * d;
+            return (sd / (a * sd + 1.0), 1.0 / (a * sd + 1.0));
+        }
+
+        // R = sinh(bSd) / (a sinh(bSd) + b cosh(bSd)) and T = b / (same),
+        // divided through by e^(bSd) so thick layers don't overflow.
+        let x = b * s * d;
+        let e2 = f64::exp(-2.0 * x);
+        let denominator = a * (1.0 - e2) + b * (1.0 + e2);
+        (
+            (1.0 - e2) / denominator,
+            2.0 * b * f64::exp(-x) / denominator,
+        )
+    }
+}
+
+// Paint and glazes: a stack of layers over an opaque substrate, reflecting
+// diffusely with the color the Kubelka-Munk model gives the whole stack.
+// Unlike blending albedos, which adds light, mixing pigments with
+// `Layer::mix` or stacking glazes only ever absorbs more of it: yellow over
+// blue comes out green rather than grey.
+//
+// `layers` go from the top coat down. A stack that neither absorbs nor
+// scatters, or one without layers, is a plain Lambertian with the
+// substrate's color.
+#[derive(Debug, Clone)]
+pub struct KubelkaMunk {
+    pub layers: Vec<Layer>,
+    pub substrate: Color,
+    // Reflectance of the whole stack, which doesn't change from hit to hit.
+    reflectance: Color,
+}
+
+impl KubelkaMunk {
+    pub fn new(layers: Vec<Layer>, substrate: Color) -> Self {
+        let reflectance = Self::stack_reflectance(&layers, substrate);
+        Self {
+            layers,
+            substrate,
+            reflectance,
+        }
+    }
+
+    pub fn reflectance(&self) -> Color {
+        self.reflectance
+    }
+
+    // Adds the layers onto the substrate from the bottom up. Light reflected
+    // by what lies below bounces between it and the new layer, giving
+    // R + T^2 R_below / (1 - R R_below).
+    fn stack_reflectance(layers: &[Layer], substrate: Color) -> Color {
+        let mut channels = [0.0; 3];
+        for (c, channel) in channels.iter_mut().enumerate() {
+            *channel = layers.iter().rev().fold(substrate[c], |below, layer| {
+                let (r, t) = Layer::reflectance_transmittance(
+                    layer.absorption[c],
+                    layer.scattering[c],
+                    layer.thickness,
+                );
+                r + t * t * below / (1.0 - r * below)
+            });
+        }
+        Vec3::from(channels)
+    }
+}
+
+impl Material for KubelkaMunk {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let pdf = CosinePdf::new(rec.normal);
+        let scatter_direction = pdf.generate();
+
+        Some(
+            ScatterRecord::new(
+                Ray::new(rec.p, scatter_direction, Some(ray_in.time)),
+                self.reflectance,
+            )
+            .with_pdf(pdf.value(scatter_direction)),
+        )
+    }
+
+    fn scattering_pdf(&self, _ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        CosinePdf::new(rec.normal).value(scattered.direction)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::KubelkaMunk {
+            layers: self.layers.clone(),
+            substrate: self.substrate,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, rng, Point3};
+
+    fn hit(material: &dyn Material) -> HitRecord<'_> {
+        HitRecord {
+            p: Point3::from(0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 1.0),
+            front_face: true,
+        }
+    }
+
+    // Checks that `material` scatters exactly like a Lambertian of `albedo`.
+    fn assert_lambertian(material: &KubelkaMunk, albedo: Color) {
+        let lambertian = Lambertian::new(albedo);
+        let (km_hit, lambertian_hit) = (hit(material), hit(&lambertian));
+        let ray_in = Ray::new(Point3::new(0.0, 1.0, 1.0), Vec3::new(0.0, -1.0, -1.0), None);
+        for seed in 0..20 {
+            rng::seed_thread(seed);
+            let a = material.scatter(&ray_in, &km_hit).unwrap();
+            rng::seed_thread(seed);
+            let b = lambertian.scatter(&ray_in, &lambertian_hit).unwrap();
+            assert_vec3_approx_eq!(a.ray.direction, b.ray.direction, 1e-12);
+            assert_vec3_approx_eq!(a.attenuation, b.attenuation, 1e-12);
+            assert_eq!(a.pdf, b.pdf);
+            assert_eq!(
+                material.scattering_pdf(&ray_in, &km_hit, &a.ray),
+                lambertian.scattering_pdf(&ray_in, &lambertian_hit, &b.ray)
+            );
+        }
+    }
+
+    #[test]
+    fn layers_without_scattering_are_lambertian() {
+        let substrate = Color::new(0.8, 0.6, 0.4);
+        let clear = Layer::new(Color::from(0.0), Color::from(0.0), 2.0);
+        assert_lambertian(&KubelkaMunk::new(vec![clear], substrate), substrate);
+        assert_lambertian(&KubelkaMunk::new(vec![], substrate), substrate);
+
+        // A tinted layer filters the light on the way in and out.
+        let absorption = Color::new(0.1, 0.5, 1.0);
+        let tinted = Layer::new(absorption, Color::from(0.0), 0.5);
+        let filtered = Vec3::from([0, 1, 2].map(|c| substrate[c] * f64::exp(-absorption[c])));
+        assert_lambertian(&KubelkaMunk::new(vec![tinted], substrate), filtered);
+    }
+
+    #[test]
+    fn thick_layers_hide_the_substrate() {
+        let (k, s): (f64, f64) = (0.3, 2.0);
+        let r_infinity = 1.0 + k / s - ((k / s) * (k / s) + 2.0 * k / s).sqrt();
+        let layer = Layer::new(Color::from(k), Color::from(s), 50.0);
+        for substrate in [Color::from(0.0), Color::from(1.0)] {
+            let reflectance = KubelkaMunk::new(vec![layer], substrate).reflectance();
+            assert_vec3_approx_eq!(reflectance, Color::from(r_infinity), 1e-9);
+        }
+    }
+
+    #[test]
+    fn layers_that_only_scatter_keep_all_light() {
+        for d in [0.01, 0.5, 3.0] {
+            let (r, t) = Layer::reflectance_transmittance(0.0, 1.5, d);
+            assert!((r + t - 1.0).abs() < 1e-12);
+        }
+        // Over a white substrate nothing is lost either.
+        let layer = Layer::new(Color::from(0.0), Color::from(1.5), 0.7);
+        let reflectance = KubelkaMunk::new(vec![layer], Color::from(1.0)).reflectance();
+        assert_vec3_approx_eq!(reflectance, Color::from(1.0), 1e-12);
+    }
+
+    #[test]
+    fn yellow_and_blue_paint_mix_to_green() {
+        let white = Color::from(1.0);
+        let yellow = Layer::new(Color::new(0.1, 0.1, 5.0), white, 10.0);
+        let blue = Layer::new(Color::new(5.0, 0.5, 0.1), white, 10.0);
+        let green = Layer::mix(&[(yellow, 1.0), (blue, 1.0)]);
+        let reflectance = KubelkaMunk::new(vec![green], Color::from(0.0)).reflectance();
+
+        assert!(reflectance.y() > 2.0 * reflectance.x());
+        assert!(reflectance.y() > 2.0 * reflectance.z());
+        // Darker than either paint, unlike an additive blend.
+        for paint in [yellow, blue] {
+            let alone = KubelkaMunk::new(vec![paint], Color::from(0.0)).reflectance();
+            assert!(
+                reflectance.x() + reflectance.y() + reflectance.z()
+                    < alone.x() + alone.y() + alone.z()
+            );
+        }
+    }
+}
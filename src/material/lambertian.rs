@@ -1,30 +1,60 @@
-use crate::{hittable::HitRecord, ray::Ray, Color, Vec3};
+use std::sync::Arc;
 
-use super::Material;
+use crate::{
+    hittable::HitRecord,
+    pdf::{cosine::CosinePdf, Pdf},
+    ray::Ray,
+    texture::{solid_color::SolidColor, Texture},
+    Color,
+};
 
-#[derive(Clone, Copy)]
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Lambertian {
-    pub albedo: Color,
+    #[cfg_attr(feature = "serde", serde(with = "crate::texture::def::serde_texture"))]
+    pub albedo: Arc<dyn Texture>,
 }
 
 impl Lambertian {
     pub fn new(albedo: Color) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)))
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>) -> Self {
         Self { albedo }
     }
 }
 
+impl From<Color> for Lambertian {
+    fn from(albedo: Color) -> Self {
+        Self::new(albedo)
+    }
+}
+
 impl Material for Lambertian {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Ray, Color)> {
-        let mut scatter_direction: Vec3 = rec.normal + Vec3::random_unit_vector();
-
-        // Catch degenerate scatter direction
-        if scatter_direction.near_zero() {
-            scatter_direction = rec.normal;
-        }
-
-        Some((
-            Ray::new(rec.p, scatter_direction, Some(ray_in.time)),
-            self.albedo,
-        ))
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let pdf = CosinePdf::new(rec.normal);
+        let scatter_direction = pdf.generate();
+
+        Some(
+            ScatterRecord::new(
+                Ray::new(rec.p, scatter_direction, Some(ray_in.time)),
+                self.albedo.value(rec.u, rec.v, rec.p),
+            )
+            .with_pdf(pdf.value(scatter_direction)),
+        )
+    }
+
+    // Ideal diffuse reflection: the BRDF times the cosine is albedo * cos / pi.
+    fn scattering_pdf(&self, _ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        CosinePdf::new(rec.normal).value(scattered.direction)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::Lambertian {
+            albedo: self.albedo.definition()?,
+        })
     }
 }
@@ -1,34 +1,58 @@
-use crate::{hittable::HitRecord, ray::Ray, Color, Vec3};
+use std::sync::Arc;
 
-use super::Material;
+use crate::{
+    hittable::HitRecord,
+    ray::Ray,
+    sampling,
+    texture::{solid_color::SolidColor, Texture},
+    Color,
+};
 
-#[derive(Clone, Copy)]
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metal {
-    pub albedo: Color,
+    #[cfg_attr(feature = "serde", serde(with = "crate::texture::def::serde_texture"))]
+    pub albedo: Arc<dyn Texture>,
     pub fuzz: f64,
 }
 
 impl Metal {
     pub fn new(albedo: Color, fuzz: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), fuzz)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, fuzz: f64) -> Self {
         Self {
-            albedo: albedo,
+            albedo,
             fuzz: f64::min(fuzz, 1.0),
         }
     }
 }
 
 impl Material for Metal {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Ray, Color)> {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
         let reflected = ray_in.direction.unit_vector().reflect(rec.normal);
         let scattered = Ray::new(
             rec.p,
-            reflected + Vec3::random_in_unit_sphere() * self.fuzz,
+            reflected + sampling::in_unit_sphere() * self.fuzz,
             Some(ray_in.time),
         );
         if scattered.direction.dot(rec.normal) > 0.0 {
-            Some((scattered, self.albedo))
+            Some(ScatterRecord::specular(
+                scattered,
+                self.albedo.value(rec.u, rec.v, rec.p),
+            ))
         } else {
             None
         }
     }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::Metal {
+            albedo: self.albedo.definition()?,
+            fuzz: self.fuzz,
+        })
+    }
 }
@@ -1,9 +1,145 @@
-use crate::{hittable::HitRecord, ray::Ray, Color};
+use std::sync::Arc;
 
+use crate::{hittable::HitRecord, ray::Ray, Color, Point3};
+
+use self::def::MaterialDef;
+
+pub mod def;
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod ggx;
+pub mod henyey_greenstein;
+pub mod isotropic;
+pub mod kubelka_munk;
 pub mod lambertian;
 pub mod metal;
+pub mod normal_map;
+pub mod oren_nayar;
+pub mod sss;
+pub mod stochastic_transparency;
+pub mod thin_film;
+pub mod velvet;
+
+// How a ray continues after hitting a material.
+pub struct ScatterRecord {
+    pub ray: Ray,
+    pub attenuation: Color,
+    // Density the direction of `ray` was drawn with, if the material samples
+    // it from a distribution. The attenuation is then weighted by
+    // scattering_pdf / pdf. None for mirrors, glass and materials whose
+    // attenuation already accounts for how they sample.
+    pub pdf: Option<f64>,
+    // Whether `ray` is a mirror reflection or a refraction (fuzzed or not),
+    // as metal and glass produce. Lights aren't sampled directly from such
+    // bounces; the scattered ray alone accounts for whatever it hits.
+    pub is_specular: bool,
+}
+
+impl ScatterRecord {
+    pub fn new(ray: Ray, attenuation: Color) -> Self {
+        Self {
+            ray,
+            attenuation,
+            pdf: None,
+            is_specular: false,
+        }
+    }
+
+    pub fn specular(ray: Ray, attenuation: Color) -> Self {
+        Self {
+            is_specular: true,
+            ..Self::new(ray, attenuation)
+        }
+    }
+
+    pub fn with_pdf(self, pdf: f64) -> Self {
+        Self {
+            pdf: Some(pdf),
+            ..self
+        }
+    }
+}
+
+// Copies a material behind a trait object, so `Box<dyn Material>` can be
+// cloned. Every material that is Clone gets it, which all built-in ones
+// are.
+pub trait CloneMaterial {
+    fn clone_box(&self) -> Box<dyn Material>;
+}
+
+impl<M: Material + Clone + 'static> CloneMaterial for M {
+    fn clone_box(&self) -> Box<dyn Material> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Material> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+pub trait Material: CloneMaterial + Send + Sync {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord>;
+
+    // Same as `scatter`, for a spectral path of light at `wavelength`
+    // nanometers. The attenuation stays RGB and is read at the wavelength,
+    // so only materials that bend or filter wavelengths differently need
+    // to look at it.
+    #[cfg(feature = "spectral")]
+    fn scatter_spectral(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        _wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        self.scatter(ray_in, rec)
+    }
+
+    // Density, per unit solid angle, of `scatter` sending the ray out along
+    // `scattered`. Direct light sampling needs it, so materials that leave
+    // it at 0 (mirrors, glass, ...) never sample lights directly.
+    fn scattering_pdf(&self, _ray_in: &Ray, _rec: &HitRecord, _scattered: &Ray) -> f64 {
+        0.0
+    }
+
+    // Light given off by the surface itself. Only lights emit.
+    fn emitted(&self, _u: f64, _v: f64, _p: Point3) -> Color {
+        Color::new(0.0, 0.0, 0.0)
+    }
+
+    // Description of the material, or None if it can't be described by a
+    // `MaterialDef` (custom materials, image textures, ...).
+    fn definition(&self) -> Option<MaterialDef> {
+        None
+    }
+}
+
+// Lets many primitives share one material, e.g. the triangles of a mesh.
+impl<M: Material + ?Sized + 'static> Material for Arc<M> {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        (**self).scatter(ray_in, rec)
+    }
+
+    #[cfg(feature = "spectral")]
+    fn scatter_spectral(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        (**self).scatter_spectral(ray_in, rec, wavelength)
+    }
+
+    fn scattering_pdf(&self, ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        (**self).scattering_pdf(ray_in, rec, scattered)
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Color {
+        (**self).emitted(u, v, p)
+    }
 
-pub trait Material: Sync {
-    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<(Ray, Color)>;
+    fn definition(&self) -> Option<MaterialDef> {
+        (**self).definition()
+    }
 }
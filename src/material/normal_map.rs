@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use crate::{hittable::HitRecord, math::onb::Onb, ray::Ray, texture::Texture, Color, Point3, Vec3};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Gives `inner` the fine detail of a tangent-space normal map, such as
+// bumps, scratches or the grooves between tiles, without any geometry for
+// them. Each texel stores a direction with x along the surface's tangent
+// (where u grows), y along its bitangent (where v grows) and z along the
+// normal, mapped from [-1, 1] to the color range [0, 1] the usual way, so
+// the flat (0.5, 0.5, 1) leaves the surface as it is.
+//
+// The shading normal is replaced before `inner` scatters, which moves
+// highlights and shading but not silhouettes or shadows. Primitives without
+// tangents get an arbitrary frame around their normal, so maps without a
+// preferred direction still work on them.
+#[derive(Debug, Clone)]
+pub struct NormalMappedMaterial<M: Material> {
+    pub inner: M,
+    pub normal_map: Arc<dyn Texture>,
+}
+
+impl<M: Material> NormalMappedMaterial<M> {
+    pub fn new(inner: M, normal_map: Arc<dyn Texture>) -> Self {
+        Self { inner, normal_map }
+    }
+
+    // `rec` with its normal replaced by the one from the map.
+    fn perturbed<'a>(&self, rec: &HitRecord<'a>) -> HitRecord<'a> {
+        let texel = self.normal_map.value(rec.u, rec.v, rec.p) * 2.0 - Color::from(1.0);
+        let normal = rec.normal;
+
+        // Tangents made perpendicular to the normal, which they aren't on
+        // triangles or under shading normals, keeping their handedness.
+        let (tangent, bitangent) = if rec.tangent.near_zero() {
+            let frame = Onb::build_from_w(normal);
+            (frame.u, frame.v)
+        } else {
+            let tangent = (rec.tangent - normal * normal.dot(rec.tangent)).unit_vector();
+            let bitangent = normal.cross(tangent);
+            if bitangent.dot(rec.bitangent) < 0.0 {
+                (tangent, -bitangent)
+            } else {
+                (tangent, bitangent)
+            }
+        };
+
+        let mapped = tangent * texel.x() + bitangent * texel.y() + normal * texel.z();
+        let mut rec = rec.clone();
+        // Texels pointing into the surface, or nowhere, leave it flat.
+        if mapped.dot(normal) > 1e-6 {
+            rec.normal = mapped.unit_vector();
+        }
+        rec
+    }
+}
+
+impl<M: Material + Clone + 'static> Material for NormalMappedMaterial<M> {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        self.inner.scatter(ray_in, &self.perturbed(rec))
+    }
+
+    #[cfg(feature = "spectral")]
+    fn scatter_spectral(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        self.inner
+            .scatter_spectral(ray_in, &self.perturbed(rec), wavelength)
+    }
+
+    fn scattering_pdf(&self, ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.inner
+            .scattering_pdf(ray_in, &self.perturbed(rec), scattered)
+    }
+
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Color {
+        self.inner.emitted(u, v, p)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::NormalMapped {
+            inner: Box::new(self.inner.definition()?),
+            normal_map: self.normal_map.definition()?,
+        })
+    }
+}
+
+// A texel of a tangent-space normal map pointing along `normal`, given in
+// tangent space and normalized, e.g. for building maps procedurally.
+pub fn encode_normal(normal: Vec3) -> Color {
+    (normal.unit_vector() + Color::from(1.0)) * 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+    use crate::{
+        assert_vec3_approx_eq,
+        hittable::{rect::XzRect, Hittable},
+        material::lambertian::Lambertian,
+        texture::solid_color::SolidColor,
+    };
+
+    // Four waves along u, tilting the normal by up to 45 degrees.
+    struct Waves;
+
+    impl Texture for Waves {
+        fn value(&self, u: f64, _v: f64, _p: Point3) -> Color {
+            encode_normal(Vec3::new((4.0 * 2.0 * PI * u).cos(), 0.0, 1.0))
+        }
+    }
+
+    fn mapped(normal_map: impl Texture + 'static) -> NormalMappedMaterial<Lambertian> {
+        NormalMappedMaterial::new(Lambertian::new(Color::from(0.8)), Arc::new(normal_map))
+    }
+
+    fn hit(material: &dyn Material, tangent: Vec3, bitangent: Vec3) -> HitRecord<'_> {
+        HitRecord {
+            p: Point3::from(0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            tangent,
+            bitangent,
+            front_face: true,
+        }
+    }
+
+    #[test]
+    fn flat_texels_leave_the_normal_alone() {
+        let material = mapped(SolidColor::new(encode_normal(Vec3::new(0.0, 0.0, 1.0))));
+        let rec = hit(
+            &material,
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+        assert_vec3_approx_eq!(material.perturbed(&rec).normal, rec.normal, 1e-12);
+
+        // Nor do texels pointing into the surface.
+        let material = mapped(SolidColor::new(encode_normal(Vec3::new(1.0, 0.0, -1.0))));
+        let rec = hit(
+            &material,
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+        assert_eq!(material.perturbed(&rec).normal, rec.normal);
+    }
+
+    #[test]
+    fn texels_tilt_the_normal_along_the_tangents() {
+        let texel = Vec3::new(0.6, -0.3, 1.0);
+        let material = mapped(SolidColor::new(encode_normal(texel)));
+        // Tangents that are neither unit length nor perpendicular to the
+        // normal, as on triangles.
+        let rec = hit(
+            &material,
+            Vec3::new(2.0, 0.5, 0.0),
+            Vec3::new(0.0, -0.2, 3.0),
+        );
+
+        let expected = (Vec3::new(1.0, 0.0, 0.0) * texel.x()
+            + Vec3::new(0.0, 0.0, 1.0) * texel.y()
+            + rec.normal * texel.z())
+        .unit_vector();
+        assert_vec3_approx_eq!(material.perturbed(&rec).normal, expected, 1e-6);
+
+        // Without tangents the map still tilts it by the same angle.
+        let rec = hit(&material, Vec3::from(0.0), Vec3::from(0.0));
+        let tilted = material.perturbed(&rec).normal;
+        assert!((tilted.dot(rec.normal) - texel.unit_vector().z()).abs() < 1e-6);
+        assert!((tilted.length() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn a_sine_wave_map_makes_a_flat_rect_look_bumpy() {
+        let rect = XzRect::new(-2.0, 2.0, -2.0, 2.0, 0.0, mapped(Waves));
+        let light = Vec3::new(-1.0, 1.0, 0.0).unit_vector();
+
+        let brightness: Vec<f64> = (0..64)
+            .map(|i| {
+                let x = -2.0 + 4.0 * (i as f64 + 0.5) / 64.0;
+                let down = Ray::new(Point3::new(x, 1.0, 0.3), Vec3::new(0.0, -1.0, 0.0), None);
+                let rec = rect.hit(&down, 0.001, f64::INFINITY).unwrap();
+                let normal = rect.material.perturbed(&rec).normal;
+
+                // Tilted back and forth along x only, as the waves go.
+                let phase = 4.0 * 2.0 * PI * rec.u;
+                let expected = Vec3::new(phase.cos(), 1.0, 0.0).unit_vector();
+                assert_vec3_approx_eq!(normal, expected, 1e-6);
+                normal.dot(light)
+            })
+            .collect();
+
+        // The geometry is flat, yet the shading rises and falls four times.
+        let peaks = brightness
+            .windows(3)
+            .filter(|w| w[0] < w[1] && w[1] >= w[2])
+            .count();
+        assert_eq!(peaks, 4);
+        let (min, max) = brightness
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(min, max), &b| {
+                (min.min(b), max.max(b))
+            });
+        assert!(max - min > 0.5);
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    pdf::{cosine::CosinePdf, Pdf},
+    ray::Ray,
+    texture::{solid_color::SolidColor, Texture},
+    Color, Vec3,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Oren-Nayar model of a rough diffuse surface made of V-shaped facets
+// whose slopes have standard deviation `sigma_degrees`. It brightens
+// towards retro-reflection, so rough surfaces look less flat than
+// Lambertian ones. sigma = 0 is exactly Lambertian.
+#[derive(Debug, Clone)]
+pub struct OrenNayar {
+    pub albedo: Arc<dyn Texture>,
+    pub sigma_degrees: f64,
+    a: f64,
+    b: f64,
+}
+
+impl OrenNayar {
+    pub fn new(albedo: Color, sigma_degrees: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), sigma_degrees)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, sigma_degrees: f64) -> Self {
+        let sigma = sigma_degrees.to_radians();
+        let sigma2 = sigma * sigma;
+
+        Self {
+            albedo,
+            sigma_degrees,
+            a: 1.0 - 0.5 * sigma2 / (sigma2 + 0.33),
+            b: 0.45 * sigma2 / (sigma2 + 0.09),
+        }
+    }
+
+    // The Oren-Nayar BRDF over Lambertian's albedo / pi, for light
+    // arriving along `wi` and leaving along `wo`.
+    fn weight(&self, wi: Vec3, wo: Vec3, normal: Vec3) -> f64 {
+        let cos_i = wi.dot(normal).clamp(0.0, 1.0);
+        let cos_o = wo.dot(normal).clamp(0.0, 1.0);
+
+        // Cosine of the azimuth between the two directions, measured in the
+        // tangent plane.
+        let wi_tangent = wi - normal * cos_i;
+        let wo_tangent = wo - normal * cos_o;
+        let cos_phi = if wi_tangent.near_zero() || wo_tangent.near_zero() {
+            0.0
+        } else {
+            wi_tangent.unit_vector().dot(wo_tangent.unit_vector())
+        };
+
+        // alpha = max(theta_i, theta_o), beta = min(theta_i, theta_o).
+        let cos_alpha = f64::min(cos_i, cos_o);
+        let cos_beta = f64::max(cos_i, cos_o);
+        let sin_alpha = f64::sqrt(1.0 - cos_alpha * cos_alpha);
+        let tan_beta = if cos_beta > 0.0 {
+            f64::sqrt(1.0 - cos_beta * cos_beta) / cos_beta
+        } else {
+            0.0
+        };
+
+        self.a + self.b * f64::max(0.0, cos_phi) * sin_alpha * tan_beta
+    }
+}
+
+impl Material for OrenNayar {
+    // Cosine-weighted, like Lambertian. The rest of the BRDF is in
+    // scattering_pdf, so lights sampled directly are weighted by it too.
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let pdf = CosinePdf::new(rec.normal);
+        let scatter_direction = pdf.generate();
+
+        Some(
+            ScatterRecord::new(
+                Ray::new(rec.p, scatter_direction, Some(ray_in.time)),
+                self.albedo.value(rec.u, rec.v, rec.p),
+            )
+            .with_pdf(pdf.value(scatter_direction)),
+        )
+    }
+
+    // The BRDF times the cosine over the albedo: Lambertian's cos / pi,
+    // scaled by the Oren-Nayar term.
+    fn scattering_pdf(&self, ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        let wi = scattered.direction.unit_vector();
+        let wo = -ray_in.direction.unit_vector();
+        let cosine = CosinePdf::new(rec.normal).value(scattered.direction);
+        if cosine <= 0.0 {
+            return 0.0;
+        }
+
+        self.weight(wi, wo, rec.normal) * cosine
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::OrenNayar {
+            albedo: self.albedo.definition()?,
+            sigma_degrees: self.sigma_degrees,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, rng, sampling, Point3};
+
+    fn hit(material: &dyn Material) -> HitRecord<'_> {
+        HitRecord {
+            p: Point3::from(0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 1.0),
+            front_face: true,
+        }
+    }
+
+    #[test]
+    fn smooth_surfaces_are_lambertian() {
+        let albedo = Color::new(0.8, 0.5, 0.2);
+        let smooth = OrenNayar::new(albedo, 0.0);
+        assert_eq!((smooth.a, smooth.b), (1.0, 0.0));
+
+        let lambertian = Lambertian::new(albedo);
+        let (smooth_hit, lambertian_hit) = (hit(&smooth), hit(&lambertian));
+        let ray_in = Ray::new(
+            Point3::new(-1.0, 1.0, 0.3),
+            Vec3::new(1.0, -1.0, -0.3),
+            None,
+        );
+        for seed in 0..20 {
+            rng::seed_thread(seed);
+            let a = smooth.scatter(&ray_in, &smooth_hit).unwrap();
+            rng::seed_thread(seed);
+            let b = lambertian.scatter(&ray_in, &lambertian_hit).unwrap();
+            assert_vec3_approx_eq!(a.ray.direction, b.ray.direction, 1e-12);
+            assert_vec3_approx_eq!(a.attenuation, b.attenuation, 1e-12);
+            assert_eq!(a.pdf, b.pdf);
+
+            let scattered = Ray::new(Point3::from(0.0), sampling::uniform_sphere(), None);
+            let (pdf, lambertian_pdf) = (
+                smooth.scattering_pdf(&ray_in, &smooth_hit, &scattered),
+                lambertian.scattering_pdf(&ray_in, &lambertian_hit, &scattered),
+            );
+            assert!((pdf - lambertian_pdf).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn rough_surfaces_reflect_back_towards_the_light() {
+        let rough = OrenNayar::new(Color::from(0.8), 30.0);
+        let rec = hit(&rough);
+        // Seen at a grazing angle, lit from behind the viewer and from the
+        // opposite side.
+        let ray_in = Ray::new(Point3::new(-1.0, 0.3, 0.0), Vec3::new(1.0, -0.3, 0.0), None);
+        let towards = Ray::new(Point3::from(0.0), Vec3::new(-1.0, 0.5, 0.0), None);
+        let away = Ray::new(Point3::from(0.0), Vec3::new(1.0, 0.5, 0.0), None);
+
+        let cosine = CosinePdf::new(rec.normal).value(towards.direction);
+        assert!(rough.scattering_pdf(&ray_in, &rec, &towards) > 1.2 * cosine);
+        assert!((rough.scattering_pdf(&ray_in, &rec, &away) - rough.a * cosine).abs() < 1e-12);
+    }
+}
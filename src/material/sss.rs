@@ -0,0 +1,214 @@
+use std::sync::Arc;
+
+use crate::{
+    hittable::HitRecord,
+    pdf::{cosine::CosinePdf, Pdf},
+    random_float,
+    ray::Ray,
+    sampling,
+    texture::{solid_color::SolidColor, Texture},
+    Color,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Index of refraction of the surface, about that of wax.
+const INDEX_OF_REFRACTION: f64 = 1.44;
+
+// Translucent material like wax, marble or skin. Light that enters the
+// surface scatters below it and leaves again some distance away, which
+// softens details and lets light bleed through thin parts.
+//
+// The inside is a homogeneous medium that light travels `scatter_distance`
+// through on average between scattering events, traced as a random walk
+// (Chiang et al., "Practical and Controllable Subsurface Scattering for
+// Production Path Tracing", 2016). Materials don't see the scene, so each
+// step of the walk is a bounce of the path: a ray inside the object next
+// hits its back faces, and the material then decides whether it scattered
+// on the way there. A path that reaches the surface leaves it diffusely,
+// where lights are sampled as for a diffuse surface. Walks through large or
+// dense objects take many bounces, so they need a high enough max depth
+// not to lose light.
+//
+// `albedo` is the color of the object as a whole after all the scattering
+// inside, not of each scattering event. Every shape using the material
+// needs to be closed.
+#[derive(Debug, Clone)]
+pub struct SubSurfaceScattering {
+    pub albedo: Arc<dyn Texture>,
+    pub scatter_distance: f64,
+}
+
+impl SubSurfaceScattering {
+    pub fn new(albedo: Color, scatter_distance: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(albedo)), scatter_distance)
+    }
+
+    pub fn from_texture(albedo: Arc<dyn Texture>, scatter_distance: f64) -> Self {
+        Self {
+            albedo,
+            scatter_distance,
+        }
+    }
+
+    fn reflectance(cosine: f64) -> f64 {
+        // Use Schlick's approximation for reflectance.
+        let r0 = ((1.0 - INDEX_OF_REFRACTION) / (1.0 + INDEX_OF_REFRACTION)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cosine).powi(5)
+    }
+
+    // Light is scattered rather than absorbed with this probability at
+    // each event, so that the light leaving the object after many events
+    // has the color `albedo`. The fit is from Chiang et al.
+    fn single_scattering_albedo(albedo: Color) -> Color {
+        let invert = |a: f64| {
+            let a = a.clamp(0.0, 0.999);
+            let s = 4.09712 + 4.20863 * a - (9.59217 + 41.6808 * a + 17.7126 * a * a).sqrt();
+            1.0 - s * s
+        };
+        Color::new(invert(albedo.x()), invert(albedo.y()), invert(albedo.z()))
+    }
+
+    // Light enters from outside, or is reflected off the smooth surface.
+    fn enter(&self, ray_in: &Ray, rec: &HitRecord) -> ScatterRecord {
+        let unit_direction = ray_in.direction.unit_vector();
+        let cos_theta = f64::min(-unit_direction.dot(rec.normal), 1.0);
+        let direction = if random_float() < Self::reflectance(cos_theta) {
+            unit_direction.reflect(rec.normal)
+        } else {
+            unit_direction.refract(rec.normal, 1.0 / INDEX_OF_REFRACTION)
+        };
+
+        ScatterRecord::specular(
+            Ray::new(rec.p, direction, Some(ray_in.time)),
+            Color::new(1.0, 1.0, 1.0),
+        )
+    }
+
+    // One step of the walk along `ray_in`, which started inside the object
+    // and reached its surface at `rec`: it scatters somewhere along the way,
+    // or leaves through the surface.
+    fn walk(&self, ray_in: &Ray, rec: &HitRecord) -> ScatterRecord {
+        let unit_direction = ray_in.direction.unit_vector();
+        let free_flight = -self.scatter_distance * (1.0 - random_float()).ln();
+        let travelled = rec.t * ray_in.direction.length();
+
+        if free_flight < travelled {
+            let p = ray_in.origin + unit_direction * free_flight;
+            let albedo = self.albedo.value(rec.u, rec.v, p);
+            return ScatterRecord::specular(
+                Ray::new(p, sampling::uniform_sphere(), Some(ray_in.time)),
+                Self::single_scattering_albedo(albedo),
+            );
+        }
+
+        // The normal faces back into the object, where the ray came from.
+        let lobe = CosinePdf::new(-rec.normal);
+        let direction = lobe.generate();
+        ScatterRecord::new(
+            Ray::new(rec.p, direction, Some(ray_in.time)),
+            Color::new(1.0, 1.0, 1.0),
+        )
+        .with_pdf(lobe.value(direction))
+    }
+}
+
+impl Material for SubSurfaceScattering {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        if rec.front_face {
+            Some(self.enter(ray_in, rec))
+        } else {
+            Some(self.walk(ray_in, rec))
+        }
+    }
+
+    // Paths leave the surface like they would a white diffuse one, on its
+    // outside.
+    fn scattering_pdf(&self, _ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        if rec.front_face {
+            return 0.0;
+        }
+        CosinePdf::new(-rec.normal).value(scattered.direction)
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::SubSurfaceScattering {
+            albedo: self.albedo.definition()?,
+            scatter_distance: self.scatter_distance,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        background::constant::ConstantBackground,
+        hittable::{light_list::LightList, sphere::Sphere, sun::SunLight, Hittable},
+        material::lambertian::Lambertian,
+        ray::RenderMode,
+        rng, Point3, Vec3,
+    };
+
+    // Average light seen looking at the center of `world` from -z.
+    fn brightness(world: &dyn Hittable, lights: &LightList, background: Color) -> f64 {
+        rng::seed_thread(5);
+        let background = ConstantBackground(background);
+        let samples = 4000;
+        let sum: Color = (0..samples)
+            .map(|_| {
+                let ray = Ray::new(Point3::new(0.0, 0.0, -5.0), Vec3::new(0.0, 0.0, 1.0), None);
+                ray.color(RenderMode::PathTrace, world, lights, &background, 100_000)
+            })
+            .sum();
+        sum.x() / samples as f64
+    }
+
+    fn wax(albedo: f64, scatter_distance: f64) -> Sphere<SubSurfaceScattering> {
+        Sphere::new(
+            Point3::from(0.0),
+            1.0,
+            SubSurfaceScattering::new(Color::from(albedo), scatter_distance),
+        )
+    }
+
+    #[test]
+    fn single_scattering_albedo_grows_from_zero_to_one() {
+        let albedo = |a: f64| SubSurfaceScattering::single_scattering_albedo(Color::from(a)).x();
+        assert!(albedo(0.0).abs() < 1e-4);
+        assert!(albedo(1.0) > 0.9999);
+        for a in [0.1, 0.3, 0.5, 0.7, 0.9] {
+            assert!(albedo(a) > a && albedo(a) < albedo(a + 0.1));
+        }
+    }
+
+    #[test]
+    fn lit_evenly_objects_take_on_their_albedo() {
+        let lights = LightList::default();
+        assert!((brightness(&wax(1.0, 0.05), &lights, Color::from(1.0)) - 1.0).abs() < 1e-3);
+        for albedo in [0.5, 0.8] {
+            let seen = brightness(&wax(albedo, 0.05), &lights, Color::from(1.0));
+            assert!(
+                (seen - albedo).abs() < 0.05,
+                "albedo {} looks {}",
+                albedo,
+                seen
+            );
+        }
+    }
+
+    #[test]
+    fn light_shines_through_from_behind() {
+        let mut lights = LightList::default();
+        lights.add(Arc::new(SunLight::new(
+            Vec3::new(0.0, 0.0, 1.0),
+            0.05,
+            Color::from(3.0),
+        )));
+        let black = Color::from(0.0);
+
+        let diffuse = Sphere::new(Point3::from(0.0), 1.0, Lambertian::new(Color::from(0.8)));
+        assert_eq!(brightness(&diffuse, &lights, black), 0.0);
+        assert!(brightness(&wax(0.8, 0.5), &lights, black) > 0.05);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{hittable::HitRecord, random_float, ray::Ray, Color, Point3};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Makes `inner` partly see-through, like a cutout leaf or a screen door.
+// Each hit picks at random: with probability `opacity` the surface scatters
+// as `inner` does, otherwise the ray carries on unchanged as if nothing was
+// there. On average a fraction 1 - opacity of the light gets through, with
+// no sorting of surfaces along the ray, and the choice needs no reweighting
+// because the probabilities are the weights.
+//
+// Shadow rays towards lights still stop at the surface, so light behind it
+// is only found by the rays passing through, which is noisier but unbiased.
+#[derive(Debug, Clone)]
+pub struct StochasticTransparency<M: Material> {
+    pub inner: M,
+    pub opacity: f64,
+}
+
+impl<M: Material> StochasticTransparency<M> {
+    pub fn new(inner: M, opacity: f64) -> Self {
+        Self {
+            inner,
+            opacity: opacity.clamp(0.0, 1.0),
+        }
+    }
+
+    // Specular, so the same direction isn't also sampled for lights.
+    fn pass_through(ray_in: &Ray, rec: &HitRecord) -> ScatterRecord {
+        ScatterRecord::specular(
+            Ray::new(rec.p, ray_in.direction, Some(ray_in.time)),
+            Color::new(1.0, 1.0, 1.0),
+        )
+    }
+}
+
+impl<M: Material + Clone + 'static> Material for StochasticTransparency<M> {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        if random_float() < self.opacity {
+            return self.inner.scatter(ray_in, rec);
+        }
+        Some(Self::pass_through(ray_in, rec))
+    }
+
+    #[cfg(feature = "spectral")]
+    fn scatter_spectral(
+        &self,
+        ray_in: &Ray,
+        rec: &HitRecord,
+        wavelength: f64,
+    ) -> Option<ScatterRecord> {
+        if random_float() < self.opacity {
+            return self.inner.scatter_spectral(ray_in, rec, wavelength);
+        }
+        Some(Self::pass_through(ray_in, rec))
+    }
+
+    // Only asked about rays `inner` scattered, as passing through is
+    // specular.
+    fn scattering_pdf(&self, ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        self.inner.scattering_pdf(ray_in, rec, scattered)
+    }
+
+    // Emission can't be chosen at random per hit, so it's scaled instead.
+    fn emitted(&self, u: f64, v: f64, p: Point3) -> Color {
+        self.inner.emitted(u, v, p) * self.opacity
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::StochasticTransparency {
+            inner: Box::new(self.inner.definition()?),
+            opacity: self.opacity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hittable::{sphere::Sphere, Hittable},
+        material::{diffuse_light::DiffuseLight, lambertian::Lambertian},
+        rng, Vec3,
+    };
+
+    // Fraction of `n` rays through the middle of a sphere of `opacity`
+    // that come out the far side, passing both its surfaces.
+    fn transmission(opacity: f64, n: u32) -> f64 {
+        let material = StochasticTransparency::new(Lambertian::new(Color::from(0.0)), opacity);
+        let sphere = Sphere::new(Point3::from(0.0), 1.0, material);
+        let through = (0..n)
+            .filter(|_| {
+                let mut ray = Ray::new(Point3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0), None);
+                while let Some(rec) = sphere.hit(&ray, 0.001, f64::INFINITY) {
+                    let scattered = rec.material.scatter(&ray, &rec).unwrap();
+                    if !scattered.is_specular {
+                        return false;
+                    }
+                    assert_eq!(scattered.ray.direction, ray.direction);
+                    assert_eq!(scattered.attenuation, Color::from(1.0));
+                    ray = scattered.ray;
+                }
+                true
+            })
+            .count();
+
+        through as f64 / n as f64
+    }
+
+    #[test]
+    fn transmission_matches_the_opacity() {
+        rng::seed_thread(14);
+        for opacity in [0.3, 0.5, 0.8] {
+            let expected = (1.0 - opacity) * (1.0 - opacity);
+            let measured = transmission(opacity, 100_000);
+            assert!(
+                (measured - expected).abs() < 0.01,
+                "opacity {}: {} instead of {}",
+                opacity,
+                measured,
+                expected
+            );
+        }
+        assert_eq!(transmission(0.0, 100), 1.0);
+        assert_eq!(transmission(1.0, 100), 0.0);
+    }
+
+    #[test]
+    fn emission_is_scaled_by_the_opacity() {
+        let light = StochasticTransparency::new(DiffuseLight::new(Color::from(4.0)), 0.25);
+        assert_eq!(light.emitted(0.0, 0.0, Point3::from(0.0)), Color::from(1.0));
+        // Out of range opacities are clamped.
+        assert_eq!(
+            StochasticTransparency::new(light.inner.clone(), 1.5).opacity,
+            1.0
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use std::f64::consts::PI;
+
+use crate::{hittable::HitRecord, random_float, ray::Ray, Color, Vec3};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Wavelengths in nanometers the red, green and blue channels are
+// evaluated at.
+pub(super) const WAVELENGTHS: [f64; 3] = [650.0, 550.0, 450.0];
+
+// A transparent surface coated with a thin, clear film, like a soap bubble
+// (base_ior 1.0) or oil on water. Light reflected off the top and the
+// bottom of the film interferes, so the reflectance depends on wavelength,
+// film thickness and angle, which gives the banded rainbow colors. The
+// film is treated as infinitely thin for ray directions; only the base
+// bends transmitted rays.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThinFilm {
+    pub base_ior: f64,
+    pub film_ior: f64,
+    pub thickness_nm: f64,
+}
+
+impl ThinFilm {
+    pub fn new(base_ior: f64, film_ior: f64, thickness_nm: f64) -> Self {
+        Self {
+            base_ior,
+            film_ior,
+            thickness_nm,
+        }
+    }
+
+    // Reflectance of the film between media n_in and n_out for light
+    // arriving at cos_in, per channel. This is the transfer matrix of a
+    // single layer multiplied out (the Airy formula), averaged over s and
+    // p polarization.
+    fn reflectance(&self, cos_in: f64, n_in: f64, n_out: f64) -> Color {
+        let n_film = self.film_ior;
+        let sin2_in = 1.0 - cos_in * cos_in;
+        let sin2_film = sin2_in * (n_in / n_film).powi(2);
+        let sin2_out = sin2_in * (n_in / n_out).powi(2);
+        if sin2_film >= 1.0 || sin2_out >= 1.0 {
+            return Color::new(1.0, 1.0, 1.0);
+        }
+        let cos_film = f64::sqrt(1.0 - sin2_film);
+        let cos_out = f64::sqrt(1.0 - sin2_out);
+
+        // Fresnel amplitude coefficients at the top and bottom of the film.
+        let rs_top = fresnel_s(n_in, cos_in, n_film, cos_film);
+        let rs_bottom = fresnel_s(n_film, cos_film, n_out, cos_out);
+        let rp_top = fresnel_p(n_in, cos_in, n_film, cos_film);
+        let rp_bottom = fresnel_p(n_film, cos_film, n_out, cos_out);
+
+        let mut reflectance = [0.0; 3];
+        for (r, wavelength) in reflectance.iter_mut().zip(WAVELENGTHS) {
+            // Phase difference of one round trip through the film.
+            let phase = 4.0 * PI * n_film * self.thickness_nm * cos_film / wavelength;
+            *r = 0.5 * (airy(rs_top, rs_bottom, phase) + airy(rp_top, rp_bottom, phase));
+        }
+        Color::new(reflectance[0], reflectance[1], reflectance[2])
+    }
+}
+
+fn fresnel_s(n1: f64, cos1: f64, n2: f64, cos2: f64) -> f64 {
+    (n1 * cos1 - n2 * cos2) / (n1 * cos1 + n2 * cos2)
+}
+
+fn fresnel_p(n1: f64, cos1: f64, n2: f64, cos2: f64) -> f64 {
+    (n2 * cos1 - n1 * cos2) / (n2 * cos1 + n1 * cos2)
+}
+
+// |r1 + r2 e^(i phase)|^2 / |1 + r1 r2 e^(i phase)|^2 for real r1 and r2.
+fn airy(r1: f64, r2: f64, phase: f64) -> f64 {
+    let cross = 2.0 * r1 * r2 * phase.cos();
+    (r1 * r1 + r2 * r2 + cross) / (1.0 + r1 * r1 * r2 * r2 + cross)
+}
+
+impl Material for ThinFilm {
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let (n_in, n_out) = if rec.front_face {
+            (1.0, self.base_ior)
+        } else {
+            (self.base_ior, 1.0)
+        };
+
+        let unit_direction = Vec3::unit_vector(&ray_in.direction);
+        let cos_theta = f64::min((-unit_direction).dot(rec.normal), 1.0);
+        let reflectance = self.reflectance(cos_theta, n_in, n_out);
+
+        // Pick reflection or transmission by the average reflectance and
+        // reweight each channel by how far it is from that average. The
+        // film doesn't absorb, so whatever isn't reflected gets through.
+        let p_reflect = (reflectance.x() + reflectance.y() + reflectance.z()) / 3.0;
+        let (direction, attenuation) = if p_reflect >= 1.0 || random_float() < p_reflect {
+            (unit_direction.reflect(rec.normal), reflectance / p_reflect)
+        } else {
+            let transmittance = Color::new(1.0, 1.0, 1.0) - reflectance;
+            (
+                unit_direction.refract(rec.normal, n_in / n_out),
+                transmittance / (1.0 - p_reflect),
+            )
+        };
+
+        let scattered = Ray::new(rec.p, direction, Some(ray_in.time));
+        Some(ScatterRecord::specular(scattered, attenuation))
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::ThinFilm {
+            base_ior: self.base_ior,
+            film_ior: self.film_ior,
+            thickness_nm: self.thickness_nm,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Amplitude reflected off water in air at normal incidence.
+    const R: f64 = (1.33 - 1.0) / (1.33 + 1.0);
+
+    fn bubble(thickness_nm: f64) -> ThinFilm {
+        ThinFilm::new(1.0, 1.33, thickness_nm)
+    }
+
+    #[test]
+    fn reflectance_matches_the_airy_formula_at_normal_incidence() {
+        let reflectance = bubble(400.0).reflectance(1.0, 1.0, 1.0);
+        for (channel, wavelength) in WAVELENGTHS.into_iter().enumerate() {
+            let phase = 4.0 * PI * 1.33 * 400.0 / wavelength;
+            let expected =
+                2.0 * R * R * (1.0 - phase.cos()) / (1.0 + R.powi(4) - 2.0 * R * R * phase.cos());
+            assert!((reflectance[channel] - expected).abs() < 1e-12);
+        }
+        // Green mostly cancels out, so head on the bubble looks magenta.
+        assert!(reflectance.y() < 0.1 * reflectance.x(), "{reflectance:?}");
+        assert!(reflectance.y() < 0.1 * reflectance.z(), "{reflectance:?}");
+    }
+
+    #[test]
+    fn quarter_and_half_wave_films_reflect_most_and_least() {
+        let green = WAVELENGTHS[1];
+
+        // The two reflections cancel out when the round trip is one
+        // wavelength...
+        let half_wave = bubble(green / (2.0 * 1.33)).reflectance(1.0, 1.0, 1.0);
+        assert!(half_wave.y() < 1e-12);
+        assert!(half_wave.x() > 0.01 && half_wave.z() > 0.01);
+
+        // ...and add up when it's half of one.
+        let quarter_wave = bubble(green / (4.0 * 1.33)).reflectance(1.0, 1.0, 1.0);
+        let peak = (2.0 * R / (1.0 + R * R)).powi(2);
+        assert!((quarter_wave.y() - peak).abs() < 1e-12);
+        assert!(quarter_wave.x() < peak && quarter_wave.z() < peak);
+    }
+
+    #[test]
+    fn the_colors_shift_with_the_view_angle() {
+        let film = bubble(400.0);
+        let head_on = film.reflectance(1.0, 1.0, 1.0);
+        let oblique = film.reflectance(0.5, 1.0, 1.0);
+        assert!((head_on - oblique).length() > 0.01);
+    }
+}
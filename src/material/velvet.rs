@@ -0,0 +1,181 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{
+    hittable::HitRecord,
+    pdf::{cosine::CosinePdf, Pdf},
+    ray::Ray,
+    texture::{solid_color::SolidColor, Texture},
+    Color, Vec3,
+};
+
+use super::{def::MaterialDef, Material, ScatterRecord};
+
+// Width of the velvet microfacet distribution. Smaller values push the
+// sheen further towards grazing angles.
+const SIGMA: f64 = 0.35;
+
+// Cloth and fabric: a diffuse base plus a sheen lobe from fibers standing
+// up from the surface, which brightens the surface at grazing angles and
+// fades towards the normal. The lobe is Ashikhmin and Premoze's velvet
+// BRDF, an inverted Gaussian microfacet distribution with their smooth
+// denominator in place of a shadowing term. `sheen` in [0, 1] blends from
+// plain Lambertian to pure sheen.
+#[derive(Debug, Clone)]
+pub struct Velvet {
+    pub color: Arc<dyn Texture>,
+    pub sheen: f64,
+}
+
+impl Velvet {
+    pub fn new(color: Color, sheen: f64) -> Self {
+        Self::from_texture(Arc::new(SolidColor::new(color)), sheen)
+    }
+
+    pub fn from_texture(color: Arc<dyn Texture>, sheen: f64) -> Self {
+        Self {
+            color,
+            sheen: sheen.clamp(0.0, 1.0),
+        }
+    }
+
+    // Velvet distribution of microfacet normals with cosine cos_h to the
+    // surface normal.
+    fn distribution(cos_h: f64) -> f64 {
+        let cos2 = cos_h * cos_h;
+        let sin2 = 1.0 - cos2;
+        if sin2 <= 0.0 {
+            return 1.0 / (PI * (1.0 + 4.0 * SIGMA * SIGMA));
+        }
+        let cot2 = cos2 / sin2;
+
+        (1.0 + 4.0 * f64::exp(-cot2 / (SIGMA * SIGMA)) / (sin2 * sin2))
+            / (PI * (1.0 + 4.0 * SIGMA * SIGMA))
+    }
+
+    // The BRDF over Lambertian's color / pi, for light arriving along
+    // `wi` and leaving along `wo`: the diffuse part counts once, the sheen
+    // lobe pi times its value.
+    fn weight(&self, wi: Vec3, wo: Vec3, normal: Vec3) -> f64 {
+        let cos_i = wi.dot(normal).clamp(0.0, 1.0);
+        let cos_o = wo.dot(normal).clamp(0.0, 1.0);
+
+        let sheen = if cos_i > 0.0 && cos_o > 0.0 {
+            let h = (wi + wo).unit_vector();
+            Self::distribution(h.dot(normal)) / (4.0 * (cos_i + cos_o - cos_i * cos_o))
+        } else {
+            0.0
+        };
+
+        (1.0 - self.sheen) + self.sheen * PI * sheen
+    }
+}
+
+impl Material for Velvet {
+    // Cosine-weighted, like Lambertian. The rest of the BRDF is in
+    // scattering_pdf, so lights sampled directly are weighted by it too.
+    fn scatter(&self, ray_in: &Ray, rec: &HitRecord) -> Option<ScatterRecord> {
+        let pdf = CosinePdf::new(rec.normal);
+        let scatter_direction = pdf.generate();
+
+        Some(
+            ScatterRecord::new(
+                Ray::new(rec.p, scatter_direction, Some(ray_in.time)),
+                self.color.value(rec.u, rec.v, rec.p),
+            )
+            .with_pdf(pdf.value(scatter_direction)),
+        )
+    }
+
+    // The BRDF times the cosine over the color: Lambertian's cos / pi,
+    // scaled by the blend of diffuse and sheen.
+    fn scattering_pdf(&self, ray_in: &Ray, rec: &HitRecord, scattered: &Ray) -> f64 {
+        let wi = scattered.direction.unit_vector();
+        let wo = -ray_in.direction.unit_vector();
+        let cosine = CosinePdf::new(rec.normal).value(scattered.direction);
+        if cosine <= 0.0 {
+            return 0.0;
+        }
+
+        self.weight(wi, wo, rec.normal) * cosine
+    }
+
+    fn definition(&self) -> Option<MaterialDef> {
+        Some(MaterialDef::Velvet {
+            color: self.color.definition()?,
+            sheen: self.sheen,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, material::lambertian::Lambertian, rng, sampling, Point3};
+
+    fn hit(material: &dyn Material) -> HitRecord<'_> {
+        HitRecord {
+            p: Point3::from(0.0),
+            normal: Vec3::new(0.0, 1.0, 0.0),
+            material,
+            t: 1.0,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 0.0, 1.0),
+            front_face: true,
+        }
+    }
+
+    #[test]
+    fn velvet_without_sheen_is_lambertian() {
+        let color = Color::new(0.6, 0.05, 0.08);
+        let plain = Velvet::new(color, 0.0);
+        let lambertian = Lambertian::new(color);
+        let (plain_hit, lambertian_hit) = (hit(&plain), hit(&lambertian));
+        let ray_in = Ray::new(
+            Point3::new(-1.0, 1.0, 0.3),
+            Vec3::new(1.0, -1.0, -0.3),
+            None,
+        );
+        for seed in 0..20 {
+            rng::seed_thread(seed);
+            let a = plain.scatter(&ray_in, &plain_hit).unwrap();
+            rng::seed_thread(seed);
+            let b = lambertian.scatter(&ray_in, &lambertian_hit).unwrap();
+            assert_vec3_approx_eq!(a.ray.direction, b.ray.direction, 1e-12);
+            assert_vec3_approx_eq!(a.attenuation, b.attenuation, 1e-12);
+            assert_eq!(a.pdf, b.pdf);
+
+            let scattered = Ray::new(Point3::from(0.0), sampling::uniform_sphere(), None);
+            let (pdf, lambertian_pdf) = (
+                plain.scattering_pdf(&ray_in, &plain_hit, &scattered),
+                lambertian.scattering_pdf(&ray_in, &lambertian_hit, &scattered),
+            );
+            assert!((pdf - lambertian_pdf).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn sheen_brightens_towards_grazing_angles() {
+        let velvet = Velvet::new(Color::from(0.5), 1.0);
+        let rec = hit(&velvet);
+        let light = Ray::new(Point3::from(0.0), Vec3::new(0.0, 1.0, 1.0), None);
+        let lambertian = CosinePdf::new(rec.normal).value(light.direction);
+        let relative_brightness = |elevation_degrees: f64| {
+            let (sin, cos) = elevation_degrees.to_radians().sin_cos();
+            let ray_in = Ray::new(Point3::new(cos, sin, 0.0), Vec3::new(-cos, -sin, 0.0), None);
+            velvet.scattering_pdf(&ray_in, &rec, &light) / lambertian
+        };
+
+        // Compared to Lambertian, the surface gets brighter as the view
+        // moves from the normal down towards the surface.
+        let ratios = [80.0, 60.0, 40.0, 20.0, 5.0].map(relative_brightness);
+        assert!(ratios[0] < 1.0, "{ratios:?}");
+        assert!(ratios.windows(2).all(|w| w[0] < w[1]), "{ratios:?}");
+
+        // Light arriving from below the surface is not reflected.
+        let below = Ray::new(Point3::from(0.0), Vec3::new(0.0, -1.0, 1.0), None);
+        let ray_in = Ray::new(Point3::new(1.0, 1.0, 0.0), Vec3::new(-1.0, -1.0, 0.0), None);
+        assert_eq!(velvet.scattering_pdf(&ray_in, &rec, &below), 0.0);
+    }
+}
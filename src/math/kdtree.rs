@@ -0,0 +1,270 @@
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+use crate::{Point3, Vec3};
+
+// Anything with a place in space, which a `KdTree` can store.
+pub trait HasPosition {
+    fn position(&self) -> Point3;
+}
+
+impl HasPosition for Point3 {
+    fn position(&self) -> Point3 {
+        *self
+    }
+}
+
+// Balanced k-d tree, stored implicitly: the items of a subtree are a range
+// of `items`, split at its middle item along the axis stored for it, with
+// the items before it on the smaller side and those after it on the larger
+// one. Building takes O(n log n) and the tree is immutable afterwards.
+#[derive(Debug, Clone)]
+pub struct KdTree<T: HasPosition> {
+    items: Vec<T>,
+    axes: Vec<u8>,
+}
+
+impl<T: HasPosition> Default for KdTree<T> {
+    fn default() -> Self {
+        Self::build(Vec::new())
+    }
+}
+
+impl<T: HasPosition> KdTree<T> {
+    pub fn build(mut items: Vec<T>) -> Self {
+        let mut axes = vec![0; items.len()];
+        split(&mut items, &mut axes);
+        Self { items, axes }
+    }
+
+    // The stored items, in the tree's order rather than the one they were
+    // given in.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    // The `k` items closest to `query`, nearest first.
+    pub fn k_nearest(&self, query: Point3, k: usize) -> Vec<&T> {
+        self.k_nearest_within(query, k, f64::INFINITY)
+            .into_iter()
+            .map(|(_, item)| item)
+            .collect()
+    }
+
+    // Up to `k` items within `radius` of `query`, nearest first, with their
+    // squared distances. The candidates are kept in a max-heap, whose top
+    // is the farthest of them and limits how far the search still looks.
+    pub fn k_nearest_within(&self, query: Point3, k: usize, radius: f64) -> Vec<(f64, &T)> {
+        let mut heap = BinaryHeap::with_capacity(k + 1);
+        if k > 0 {
+            let mut max_distance_squared = radius * radius;
+            self.visit(
+                0,
+                self.items.len(),
+                query,
+                &mut max_distance_squared,
+                &mut |index, d| {
+                    heap.push(Candidate(d, index));
+                    if heap.len() > k {
+                        heap.pop();
+                    }
+                    // A full heap only takes items closer than its farthest.
+                    (heap.len() == k).then(|| heap.peek().unwrap().0)
+                },
+            );
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Candidate(d, index)| (d, &self.items[index]))
+            .collect()
+    }
+
+    // All items within `radius` of `query`, in no particular order.
+    pub fn within_radius(&self, query: Point3, radius: f64) -> Vec<&T> {
+        let mut found = Vec::new();
+        let mut max_distance_squared = radius * radius;
+        self.visit(
+            0,
+            self.items.len(),
+            query,
+            &mut max_distance_squared,
+            &mut |index, _| {
+                found.push(index);
+                None
+            },
+        );
+
+        found.into_iter().map(|index| &self.items[index]).collect()
+    }
+
+    // Calls `found` with the index and squared distance of every item in
+    // lo..hi closer to `query` than `max_distance_squared`. `found` may
+    // return a new, smaller bound, which prunes the rest of the search.
+    fn visit(
+        &self,
+        lo: usize,
+        hi: usize,
+        query: Point3,
+        max_distance_squared: &mut f64,
+        found: &mut impl FnMut(usize, f64) -> Option<f64>,
+    ) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        let position = self.items[mid].position();
+        let axis = self.axes[mid] as usize;
+        let offset = query[axis] - position[axis];
+
+        // The side of the split the query is on first, then the other one
+        // if the sphere searched still reaches across.
+        let (near, far) = if offset < 0.0 {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.visit(near.0, near.1, query, max_distance_squared, found);
+
+        let distance_squared = Vec3::distance_squared(query, position);
+        let bound = if distance_squared < *max_distance_squared {
+            found(mid, distance_squared)
+        } else {
+            None
+        };
+        if let Some(bound) = bound {
+            *max_distance_squared = bound;
+        }
+
+        if offset * offset < *max_distance_squared {
+            self.visit(far.0, far.1, query, max_distance_squared, found);
+        }
+    }
+}
+
+// Puts the median along the axis the items are spread widest in at the
+// middle, with the smaller items before it and the larger ones after, and
+// does the same for both halves.
+fn split<T: HasPosition>(items: &mut [T], axes: &mut [u8]) {
+    if items.len() <= 1 {
+        return;
+    }
+
+    let (min, max) = items.iter().fold(
+        (Point3::from(f64::INFINITY), Point3::from(f64::NEG_INFINITY)),
+        |(min, max), item| {
+            let p = item.position();
+            (Vec3::min_components(min, p), Vec3::max_components(max, p))
+        },
+    );
+    let extent = max - min;
+    let axis = (0..3)
+        .max_by(|&a, &b| extent[a].total_cmp(&extent[b]))
+        .unwrap();
+
+    let mid = items.len() / 2;
+    items.select_nth_unstable_by(mid, |a, b| {
+        a.position()[axis].total_cmp(&b.position()[axis])
+    });
+    axes[mid] = axis as u8;
+
+    let (items_lo, items_hi) = items.split_at_mut(mid);
+    let (axes_lo, axes_hi) = axes.split_at_mut(mid);
+    split(items_lo, axes_lo);
+    split(&mut items_hi[1..], &mut axes_hi[1..]);
+}
+
+// An item found by a k-nearest search: its squared distance and index,
+// ordered by distance.
+#[derive(PartialEq)]
+struct Candidate(f64, usize);
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{random_float_between, rng};
+
+    // Squared distances from `query` to all of `points`, nearest first.
+    fn linear_scan(points: &[Point3], query: Point3) -> Vec<f64> {
+        let mut distances: Vec<f64> = points
+            .iter()
+            .map(|&p| Vec3::distance_squared(query, p))
+            .collect();
+        distances.sort_by(f64::total_cmp);
+        distances
+    }
+
+    fn distances(query: Point3, found: &[&Point3]) -> Vec<f64> {
+        found
+            .iter()
+            .map(|&&p| Vec3::distance_squared(query, p))
+            .collect()
+    }
+
+    // Random points in a 10 unit cube, with some repeated, and some on a
+    // grid so that many are exactly as far from a query as others.
+    fn random_points(count: usize) -> Vec<Point3> {
+        let mut points: Vec<Point3> = (0..count)
+            .map(|_| Vec3::random_between(-5.0, 5.0))
+            .collect();
+        points.extend_from_within(..count / 10);
+        for x in -3..3 {
+            for z in -3..3 {
+                points.push(Point3::new(x as f64, 0.0, z as f64));
+            }
+        }
+        points
+    }
+
+    #[test]
+    fn searches_find_what_a_linear_scan_finds() {
+        rng::seed_thread(8);
+        for count in [0, 1, 2, 7, 100, 2000] {
+            let points = random_points(count);
+            let tree = KdTree::build(points.clone());
+            assert_eq!(tree.len(), points.len());
+
+            for _ in 0..100 {
+                let query = Vec3::random_between(-6.0, 6.0);
+                let expected = linear_scan(&points, query);
+
+                for k in [1, 5, 40, points.len() + 3] {
+                    let found = tree.k_nearest(query, k);
+                    let wanted = &expected[..k.min(expected.len())];
+                    assert_eq!(distances(query, &found), wanted);
+                }
+
+                let radius = random_float_between(0.0, 3.0);
+                let inside = expected.partition_point(|&d| d < radius * radius);
+                let within = tree.k_nearest_within(query, 10, radius);
+                let found: Vec<f64> = within.iter().map(|&(d, _)| d).collect();
+                assert_eq!(found, expected[..inside.min(10)]);
+
+                let mut found = distances(query, &tree.within_radius(query, radius));
+                found.sort_by(f64::total_cmp);
+                assert_eq!(found, expected[..inside]);
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::ops::Mul;
+
+use crate::{math::quat::Quat, Point3, Vec3};
+
+// A 4x4 matrix acting on column vectors, stored row by row. Points are
+// transformed with an implicit w of 1 and directions with a w of 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mat4 {
+    pub m: [[f64; 4]; 4],
+}
+
+impl Mat4 {
+    pub fn new(m: [[f64; 4]; 4]) -> Self {
+        Self { m }
+    }
+
+    pub fn identity() -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        Self { m }
+    }
+
+    // Scales by `s` first, then rotates by `r` and finally moves by `t`. `r`
+    // needs to be a unit quaternion.
+    pub fn from_rotation_translation_scale(r: Quat, t: Vec3, s: Vec3) -> Self {
+        let rotation = r.to_rotation_matrix().m;
+
+        let mut m = [[0.0; 4]; 4];
+        for i in 0..3 {
+            for j in 0..3 {
+                m[i][j] = rotation[i][j] * s[j];
+            }
+            m[i][3] = t[i];
+        }
+        m[3][3] = 1.0;
+
+        Self { m }
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.m[j][i];
+            }
+        }
+        Self { m }
+    }
+
+    // Gauss-Jordan elimination with partial pivoting. Singular matrices,
+    // such as scales by 0, give non-finite entries.
+    pub fn inverse(&self) -> Self {
+        let mut a = self.m;
+        let mut inverse = Self::identity().m;
+
+        for column in 0..4 {
+            let pivot = (column..4)
+                .max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))
+                .unwrap();
+            a.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let scale = 1.0 / a[column][column];
+            for j in 0..4 {
+                a[column][j] *= scale;
+                inverse[column][j] *= scale;
+            }
+            for row in 0..4 {
+                if row == column {
+                    continue;
+                }
+                let factor = a[row][column];
+                for j in 0..4 {
+                    a[row][j] -= factor * a[column][j];
+                    inverse[row][j] -= factor * inverse[column][j];
+                }
+            }
+        }
+
+        Self { m: inverse }
+    }
+
+    pub fn transform_point3(&self, p: Point3) -> Point3 {
+        let m = &self.m;
+        let w = m[3][0] * p[0] + m[3][1] * p[1] + m[3][2] * p[2] + m[3][3];
+        let q = self.transform_dir(p) + Vec3::new(m[0][3], m[1][3], m[2][3]);
+        // Only projective matrices have a w other than 1.
+        if w == 1.0 {
+            q
+        } else {
+            q / w
+        }
+    }
+
+    // Ignores the translation. Normals need the inverse transpose of the
+    // matrix that transforms points instead.
+    pub fn transform_dir(&self, d: Vec3) -> Vec3 {
+        let m = &self.m;
+        Vec3::new(
+            m[0][0] * d[0] + m[0][1] * d[1] + m[0][2] * d[2],
+            m[1][0] * d[0] + m[1][1] * d[1] + m[1][2] * d[2],
+            m[2][0] * d[0] + m[2][1] * d[1] + m[2][2] * d[2],
+        )
+    }
+}
+
+// Applies `rhs` first, then `self`.
+impl Mul for Mat4 {
+    type Output = Mat4;
+
+    fn mul(self, rhs: Mat4) -> Mat4 {
+        let mut m = [[0.0; 4]; 4];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.m[i][k] * rhs.m[k][j]).sum();
+            }
+        }
+        Mat4 { m }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod kdtree;
+pub mod mat3;
+pub mod mat4;
+pub mod onb;
+pub mod quat;
+pub mod roots;
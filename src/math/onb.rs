@@ -0,0 +1,92 @@
+use crate::Vec3;
+
+// Orthonormal basis with w along a given direction, usually a surface
+// normal. Turns directions sampled around the z axis into world space.
+#[derive(Debug, Clone, Copy)]
+pub struct Onb {
+    pub u: Vec3,
+    pub v: Vec3,
+    pub w: Vec3,
+}
+
+impl Onb {
+    // Builds the basis around `n` with the branchless construction of Duff
+    // et al., "Building an Orthonormal Basis, Revisited" (2017), which stays
+    // accurate for normals close to -z.
+    pub fn build_from_w(n: Vec3) -> Self {
+        let w = n.unit_vector();
+        let sign = 1.0_f64.copysign(w.z());
+        let a = -1.0 / (sign + w.z());
+        let b = w.x() * w.y() * a;
+        let u = Vec3::new(1.0 + sign * w.x() * w.x() * a, sign * b, -sign * w.x());
+        let v = Vec3::new(b, sign + w.y() * w.y() * a, -w.y());
+
+        Self { u, v, w }
+    }
+
+    // Converts `a`, given in the basis' coordinates, to world space.
+    pub fn local(&self, a: Vec3) -> Vec3 {
+        self.u * a.x() + self.v * a.y() + self.w * a.z()
+    }
+
+    // World space direction at polar angle `theta` from w and azimuth `phi`
+    // around it, measured from u towards v.
+    pub fn local_from_angles(&self, theta: f64, phi: f64) -> Vec3 {
+        let (sin_theta, cos_theta) = theta.sin_cos();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        self.local(Vec3::new(
+            sin_theta * cos_phi,
+            sin_theta * sin_phi,
+            cos_theta,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_2;
+
+    use super::*;
+    use crate::assert_vec3_approx_eq;
+
+    fn normals() -> Vec<Vec3> {
+        vec![
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, -1.0),
+            Vec3::new(1e-9, -1e-9, -1.0),
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::new(-0.3, 0.1, -5.0),
+        ]
+    }
+
+    #[test]
+    fn bases_are_orthonormal_and_right_handed() {
+        for n in normals() {
+            let onb = Onb::build_from_w(n);
+            assert_vec3_approx_eq!(onb.w, n.unit_vector(), 1e-12);
+            for axis in [onb.u, onb.v, onb.w] {
+                assert!((axis.length() - 1.0).abs() < 1e-12, "{n}: {axis}");
+            }
+            assert!(onb.u.dot(onb.v).abs() < 1e-12, "{n}");
+            assert!(onb.v.dot(onb.w).abs() < 1e-12, "{n}");
+            assert!(onb.w.dot(onb.u).abs() < 1e-12, "{n}");
+            assert_vec3_approx_eq!(onb.u.cross(onb.v), onb.w, 1e-12);
+        }
+    }
+
+    #[test]
+    fn local_directions_map_onto_the_basis() {
+        for n in normals() {
+            let onb = Onb::build_from_w(n);
+            assert_vec3_approx_eq!(onb.local(Vec3::new(0.0, 0.0, 1.0)), onb.w, 1e-12);
+            assert_vec3_approx_eq!(onb.local(Vec3::new(1.0, 0.0, 0.0)), onb.u, 1e-12);
+            assert_vec3_approx_eq!(onb.local(Vec3::new(0.0, 2.0, 0.0)), onb.v * 2.0, 1e-12);
+
+            assert_vec3_approx_eq!(onb.local_from_angles(0.0, 1.0), onb.w, 1e-12);
+            assert_vec3_approx_eq!(onb.local_from_angles(FRAC_PI_2, 0.0), onb.u, 1e-12);
+            assert_vec3_approx_eq!(onb.local_from_angles(FRAC_PI_2, FRAC_PI_2), onb.v, 1e-12);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use std::ops::Mul;
+
+use crate::{math::mat3::Mat3, Vec3};
+
+// A rotation, as the unit quaternion w + xi + yj + zk. Unlike matrices,
+// rotations stored this way can be interpolated with `slerp`, which is what
+// animating them needs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quat {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Quat {
+    pub fn identity() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        }
+    }
+
+    // Rotation by `radians` around `axis`, counterclockwise when looking
+    // down the axis towards the origin. The axis doesn't need to be a unit
+    // vector.
+    pub fn from_axis_angle(axis: Vec3, radians: f64) -> Self {
+        let (sin, cos) = (radians / 2.0).sin_cos();
+        let axis = axis.unit_vector() * sin;
+        Self {
+            x: axis.x(),
+            y: axis.y(),
+            z: axis.z(),
+            w: cos,
+        }
+    }
+
+    // Scales the quaternion back to unit length, which chains of
+    // multiplications slowly drift away from.
+    pub fn normalize(&self) -> Self {
+        let length = self.dot(*self).sqrt();
+        Self {
+            x: self.x / length,
+            y: self.y / length,
+            z: self.z / length,
+            w: self.w / length,
+        }
+    }
+
+    pub fn dot(&self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z + self.w * other.w
+    }
+
+    // Spherical linear interpolation from a (t = 0) to b (t = 1): rotates at
+    // a constant angular speed, along the shorter way around.
+    pub fn slerp(a: Quat, b: Quat, t: f64) -> Quat {
+        // q and -q are the same rotation. Picking the one closer to a takes
+        // the shorter arc.
+        let mut cos_theta = a.dot(b);
+        let b = if cos_theta < 0.0 {
+            cos_theta = -cos_theta;
+            Quat {
+                x: -b.x,
+                y: -b.y,
+                z: -b.z,
+                w: -b.w,
+            }
+        } else {
+            b
+        };
+
+        let (wa, wb) = if cos_theta > 0.9995 {
+            // Nearly the same rotation, where sin(theta) is too small to
+            // divide by. A straight line is just as good there.
+            (1.0 - t, t)
+        } else {
+            let theta = cos_theta.acos();
+            let sin_theta = theta.sin();
+            (
+                ((1.0 - t) * theta).sin() / sin_theta,
+                (t * theta).sin() / sin_theta,
+            )
+        };
+
+        Quat {
+            x: wa * a.x + wb * b.x,
+            y: wa * a.y + wb * b.y,
+            z: wa * a.z + wb * b.z,
+            w: wa * a.w + wb * b.w,
+        }
+        .normalize()
+    }
+
+    pub fn to_rotation_matrix(&self) -> Mat3 {
+        let Quat { x, y, z, w } = *self;
+        Mat3::new([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ])
+    }
+
+    // Same as multiplying by the rotation matrix, without building it:
+    // v + 2w (q x v) + 2 q x (q x v), with q the vector part.
+    pub fn rotate_vec3(&self, v: Vec3) -> Vec3 {
+        let q = Vec3::new(self.x, self.y, self.z);
+        let t = q.cross(v) * 2.0;
+        v + t * self.w + q.cross(t)
+    }
+}
+
+// The rotation by `rhs` followed by the one by `self`.
+impl Mul for Quat {
+    type Output = Quat;
+
+    fn mul(self, rhs: Quat) -> Quat {
+        Quat {
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use super::*;
+    use crate::{assert_vec3_approx_eq, random_float_between, rng, sampling};
+
+    // Rotations around random axes by random angles, and a vector for each.
+    fn random_rotations(count: usize) -> Vec<(Quat, Vec3)> {
+        rng::seed_thread(11);
+        (0..count)
+            .map(|_| {
+                let angle = random_float_between(-PI, PI);
+                let q = Quat::from_axis_angle(sampling::uniform_sphere(), angle);
+                (q, Vec3::random_between(-2.0, 2.0))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn axis_angle_rotates_counterclockwise() {
+        let quarter = Quat::from_axis_angle(Vec3::new(0.0, 0.0, 2.0), FRAC_PI_2);
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        assert_vec3_approx_eq!(quarter.rotate_vec3(x), Vec3::new(0.0, 1.0, 0.0), 1e-12);
+        assert_vec3_approx_eq!(Quat::identity().rotate_vec3(x), x, 0.0);
+    }
+
+    #[test]
+    fn rotation_matrix_rotates_like_the_quaternion() {
+        for (q, v) in random_rotations(100) {
+            assert_vec3_approx_eq!(q.to_rotation_matrix().transform(v), q.rotate_vec3(v), 1e-12);
+        }
+    }
+
+    #[test]
+    fn products_rotate_by_the_right_factor_first() {
+        let rotations = random_rotations(100);
+        for pair in rotations.windows(2) {
+            let ((a, v), (b, _)) = (pair[0], pair[1]);
+            assert_vec3_approx_eq!(
+                (a * b).rotate_vec3(v),
+                a.rotate_vec3(b.rotate_vec3(v)),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn slerp_starts_and_ends_at_its_rotations() {
+        let rotations = random_rotations(100);
+        for pair in rotations.windows(2) {
+            let ((a, v), (b, _)) = (pair[0], pair[1]);
+            assert_vec3_approx_eq!(
+                Quat::slerp(a, b, 0.0).rotate_vec3(v),
+                a.rotate_vec3(v),
+                1e-12
+            );
+            assert_vec3_approx_eq!(
+                Quat::slerp(a, b, 1.0).rotate_vec3(v),
+                b.rotate_vec3(v),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn slerp_turns_at_a_constant_speed_the_short_way() {
+        let z = Vec3::new(0.0, 0.0, 1.0);
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        let quarter = Quat::from_axis_angle(z, FRAC_PI_2);
+        for t in [0.25, 0.5, 0.8] {
+            let expected = Quat::from_axis_angle(z, t * FRAC_PI_2).rotate_vec3(x);
+            assert_vec3_approx_eq!(
+                Quat::slerp(Quat::identity(), quarter, t).rotate_vec3(x),
+                expected,
+                1e-12
+            );
+        }
+
+        // 270 degrees one way is 90 the other, so halfway is at -45.
+        let three_quarters = Quat::from_axis_angle(z, 3.0 * FRAC_PI_2);
+        assert_vec3_approx_eq!(
+            Quat::slerp(Quat::identity(), three_quarters, 0.5).rotate_vec3(x),
+            Quat::from_axis_angle(z, -FRAC_PI_4).rotate_vec3(x),
+            1e-12
+        );
+    }
+}
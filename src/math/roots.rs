@@ -0,0 +1,209 @@
+// Real roots of polynomials up to degree four, in closed form. Adapted from
+// Jochen Schwarze's solvers in Graphics Gems I ("Cubic and Quartic Roots").
+// Coefficients are given lowest degree first: c[0] + c[1] x + c[2] x^2 ...
+
+use std::f64::consts::PI;
+
+const EPSILON: f64 = 1e-9;
+
+fn is_zero(x: f64) -> bool {
+    x.abs() < EPSILON
+}
+
+// Up to four real roots, in no particular order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Roots {
+    values: [f64; 4],
+    len: usize,
+}
+
+impl Roots {
+    fn push(&mut self, x: f64) {
+        self.values[self.len] = x;
+        self.len += 1;
+    }
+
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values[..self.len]
+    }
+}
+
+// c[0] + c[1] x + c[2] x^2 = 0.
+pub fn solve_quadratic(c: [f64; 3]) -> Roots {
+    let mut roots = Roots::default();
+    // Normal form x^2 + 2 p x + q = 0.
+    let p = c[1] / (2.0 * c[2]);
+    let q = c[0] / c[2];
+    let d = p * p - q;
+
+    if is_zero(d) {
+        roots.push(-p);
+    } else if d > 0.0 {
+        let sqrt_d = d.sqrt();
+        roots.push(sqrt_d - p);
+        roots.push(-sqrt_d - p);
+    }
+
+    roots
+}
+
+// c[0] + c[1] x + c[2] x^2 + c[3] x^3 = 0.
+pub fn solve_cubic(c: [f64; 4]) -> Roots {
+    let mut roots = Roots::default();
+    // Normal form x^3 + A x^2 + B x + C = 0.
+    let a = c[2] / c[3];
+    let b = c[1] / c[3];
+    let cc = c[0] / c[3];
+
+    // Substitute x = y - A/3 to eliminate the quadric term: y^3 + 3 p y + 2 q.
+    let sq_a = a * a;
+    let p = (-sq_a / 3.0 + b) / 3.0;
+    let q = (2.0 / 27.0 * a * sq_a - a * b / 3.0 + cc) / 2.0;
+
+    let cb_p = p * p * p;
+    let d = q * q + cb_p;
+
+    if is_zero(d) {
+        if is_zero(q) {
+            // One triple root.
+            roots.push(0.0);
+        } else {
+            // One single and one double root.
+            let u = (-q).cbrt();
+            roots.push(2.0 * u);
+            roots.push(-u);
+        }
+    } else if d < 0.0 {
+        // Three real roots.
+        let phi = (-q / (-cb_p).sqrt()).clamp(-1.0, 1.0).acos() / 3.0;
+        let t = 2.0 * (-p).sqrt();
+        roots.push(t * phi.cos());
+        roots.push(-t * (phi + PI / 3.0).cos());
+        roots.push(-t * (phi - PI / 3.0).cos());
+    } else {
+        // One real root.
+        let sqrt_d = d.sqrt();
+        roots.push((sqrt_d - q).cbrt() - (sqrt_d + q).cbrt());
+    }
+
+    let sub = a / 3.0;
+    for x in roots.values[..roots.len].iter_mut() {
+        *x -= sub;
+    }
+
+    roots
+}
+
+// c[0] + c[1] x + c[2] x^2 + c[3] x^3 + c[4] x^4 = 0, with Ferrari's method.
+pub fn solve_quartic(c: [f64; 5]) -> Roots {
+    let mut roots = Roots::default();
+    // Normal form x^4 + A x^3 + B x^2 + C x + D = 0.
+    let a = c[3] / c[4];
+    let b = c[2] / c[4];
+    let cc = c[1] / c[4];
+    let d = c[0] / c[4];
+
+    // Substitute x = y - A/4 to eliminate the cubic term:
+    // y^4 + p y^2 + q y + r = 0.
+    let sq_a = a * a;
+    let p = -3.0 / 8.0 * sq_a + b;
+    let q = sq_a * a / 8.0 - a * b / 2.0 + cc;
+    let r = -3.0 / 256.0 * sq_a * sq_a + sq_a * b / 16.0 - a * cc / 4.0 + d;
+
+    if is_zero(r) {
+        // No absolute term: y (y^3 + p y + q) = 0.
+        roots = solve_cubic([q, p, 0.0, 1.0]);
+        roots.push(0.0);
+    } else {
+        // Take one root of the resolvent cubic and split the quartic into
+        // two quadratics.
+        let z = solve_cubic([r * p / 2.0 - q * q / 8.0, -r, -p / 2.0, 1.0]).as_slice()[0];
+
+        let u = z * z - r;
+        let v = 2.0 * z - p;
+        let u = if is_zero(u) {
+            0.0
+        } else if u > 0.0 {
+            u.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if is_zero(v) {
+            0.0
+        } else if v > 0.0 {
+            v.sqrt()
+        } else {
+            return roots;
+        };
+        let v = if q < 0.0 { -v } else { v };
+
+        for &x in solve_quadratic([z - u, v, 1.0]).as_slice() {
+            roots.push(x);
+        }
+        for &x in solve_quadratic([z + u, -v, 1.0]).as_slice() {
+            roots.push(x);
+        }
+    }
+
+    let sub = a / 4.0;
+    for x in roots.values[..roots.len].iter_mut() {
+        *x -= sub;
+    }
+
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(roots: Roots) -> Vec<f64> {
+        let mut roots = roots.as_slice().to_vec();
+        roots.sort_by(f64::total_cmp);
+        roots
+    }
+
+    fn assert_roots(roots: Roots, expected: &[f64]) {
+        let roots = sorted(roots);
+        assert_eq!(roots.len(), expected.len(), "{:?}", roots);
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9, "{:?}", roots);
+        }
+    }
+
+    #[test]
+    fn quadratics() {
+        // (x - 1)(x - 3)
+        assert_roots(solve_quadratic([3.0, -4.0, 1.0]), &[1.0, 3.0]);
+        // 2 (x + 0.5)^2
+        assert_roots(solve_quadratic([0.5, 2.0, 2.0]), &[-0.5]);
+        assert_roots(solve_quadratic([1.0, 0.0, 1.0]), &[]);
+    }
+
+    #[test]
+    fn cubics() {
+        // (x - 1)(x - 2)(x + 3)
+        assert_roots(solve_cubic([6.0, -7.0, 0.0, 1.0]), &[-3.0, 1.0, 2.0]);
+        // x^3 + x has only the root 0.
+        assert_roots(solve_cubic([0.0, 1.0, 0.0, 1.0]), &[0.0]);
+        // 2 (x - 1)^3
+        assert_roots(solve_cubic([-2.0, 6.0, -6.0, 2.0]), &[1.0]);
+    }
+
+    #[test]
+    fn quartics() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        assert_roots(
+            solve_quartic([24.0, -50.0, 35.0, -10.0, 1.0]),
+            &[1.0, 2.0, 3.0, 4.0],
+        );
+        // (x^2 - 4)(x^2 + 1)
+        assert_roots(solve_quartic([-4.0, 0.0, -3.0, 0.0, 1.0]), &[-2.0, 2.0]);
+        // x (x - 1)(x + 1)(x - 2), with no absolute term.
+        assert_roots(
+            solve_quartic([0.0, 2.0, -1.0, -2.0, 1.0]),
+            &[-1.0, 0.0, 1.0, 2.0],
+        );
+        assert_roots(solve_quartic([1.0, 0.0, 0.0, 0.0, 1.0]), &[]);
+    }
+}
@@ -0,0 +1,121 @@
+use std::{error::Error, fmt, path::Path};
+
+use exr::prelude::{
+    AnyChannel, AnyChannels, Encoding, FlatSamples, Image, ImageAttributes, IntegerBounds, Layer,
+    LayerAttributes, WritableImage,
+};
+
+use crate::{Color, Vec3};
+
+#[derive(Debug)]
+pub enum ExrError {
+    Size { expected: usize, actual: usize },
+    Exr(exr::error::Error),
+}
+
+impl fmt::Display for ExrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExrError::Size { expected, actual } => {
+                write!(f, "expected {} values, got {}", expected, actual)
+            }
+            ExrError::Exr(err) => write!(f, "unable to write EXR: {}", err),
+        }
+    }
+}
+
+impl Error for ExrError {}
+
+impl From<exr::error::Error> for ExrError {
+    fn from(err: exr::error::Error) -> Self {
+        ExrError::Exr(err)
+    }
+}
+
+// Per-pixel data about the first surface seen through each pixel, stored
+// next to the beauty image. Rows are top row first, like the pixels.
+pub struct Aovs<'a> {
+    // Ray parameter t of the primary hit, infinite where nothing was hit.
+    pub depth: &'a [f64],
+    // World space shading normal of the primary hit, zero where nothing was
+    // hit.
+    pub normal: &'a [Vec3],
+}
+
+// Writes linear, not gamma corrected, colors as a 32-bit float RGB EXR file,
+// keeping the full dynamic range of the render.
+pub fn write_exr(pixels: &[Color], width: u32, height: u32, path: &Path) -> Result<(), ExrError> {
+    check_size(pixels.len(), width, height)?;
+
+    let width = width as usize;
+    exr::prelude::write_rgb_file(path, width, height as usize, |x, y| {
+        let color = pixels[y * width + x];
+        (color.x() as f32, color.y() as f32, color.z() as f32)
+    })?;
+
+    Ok(())
+}
+
+// Like `write_exr`, but adds a "depth" layer with a Z channel and a
+// "normal" layer with X, Y and Z channels.
+pub fn write_exr_with_aovs(
+    pixels: &[Color],
+    aovs: &Aovs,
+    width: u32,
+    height: u32,
+    path: &Path,
+) -> Result<(), ExrError> {
+    check_size(pixels.len(), width, height)?;
+    check_size(aovs.depth.len(), width, height)?;
+    check_size(aovs.normal.len(), width, height)?;
+
+    let size = (width as usize, height as usize);
+    let channel = |name: &str, values: Vec<f32>| AnyChannel::new(name, FlatSamples::F32(values));
+    let component = |values: &[Vec3], i: usize| values.iter().map(|v| v[i] as f32).collect();
+    let layer = |name: &str, channels: Vec<AnyChannel<FlatSamples>>| {
+        Layer::new(
+            size,
+            LayerAttributes::named(name),
+            Encoding::FAST_LOSSLESS,
+            AnyChannels::sort(channels.into()),
+        )
+    };
+
+    let beauty = layer(
+        "rgb",
+        vec![
+            channel("R", component(pixels, 0)),
+            channel("G", component(pixels, 1)),
+            channel("B", component(pixels, 2)),
+        ],
+    );
+    let depth = layer(
+        "depth",
+        vec![channel("Z", aovs.depth.iter().map(|&d| d as f32).collect())],
+    );
+    let normal = layer(
+        "normal",
+        vec![
+            channel("X", component(aovs.normal, 0)),
+            channel("Y", component(aovs.normal, 1)),
+            channel("Z", component(aovs.normal, 2)),
+        ],
+    );
+
+    let image = Image::from_layers(
+        ImageAttributes::new(IntegerBounds::from_dimensions(size)),
+        vec![beauty, depth, normal],
+    );
+    image.write().to_file(path)?;
+
+    Ok(())
+}
+
+fn check_size(actual: usize, width: u32, height: u32) -> Result<(), ExrError> {
+    let expected = (width * height) as usize;
+    if actual != expected {
+        return Err(ExrError::Size { expected, actual });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use std::path::Path;
+
+use image::{ColorType, ImageError};
+
+pub mod exr;
+pub mod ppm;
+
+// Writes tightly packed 8-bit RGB rows, top row first, as a PNG file. The
+// pixels are stored as given, so they should already be gamma corrected.
+pub fn save_png(pixels: &[u8], width: u32, height: u32, path: &Path) -> Result<(), ImageError> {
+    image::save_buffer(path, pixels, width, height, ColorType::Rgb8)
+}
@@ -0,0 +1,55 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+// Writes a binary (P6) PPM file. Pixels are rows top row first and are
+// stored as given, so they should already be gamma corrected.
+pub fn write_ppm(pixels: &[(u8, u8, u8)], width: u32, height: u32, path: &Path) -> io::Result<()> {
+    check_size(pixels, width, height)?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    for &(r, g, b) in pixels {
+        out.write_all(&[r, g, b])?;
+    }
+
+    out.flush()
+}
+
+// Writes a plain text (P3) PPM file, one pixel per line. Handy for looking
+// at the values with a text editor.
+pub fn write_ppm_ascii(
+    pixels: &[(u8, u8, u8)],
+    width: u32,
+    height: u32,
+    path: &Path,
+) -> io::Result<()> {
+    check_size(pixels, width, height)?;
+
+    let mut out = BufWriter::new(File::create(path)?);
+    write!(out, "P3\n{} {}\n255\n", width, height)?;
+    for &(r, g, b) in pixels {
+        writeln!(out, "{} {} {}", r, g, b)?;
+    }
+
+    out.flush()
+}
+
+fn check_size(pixels: &[(u8, u8, u8)], width: u32, height: u32) -> io::Result<()> {
+    if pixels.len() != (width * height) as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "expected {} pixels for a {}x{} image, got {}",
+                width * height,
+                width,
+                height,
+                pixels.len()
+            ),
+        ));
+    }
+
+    Ok(())
+}
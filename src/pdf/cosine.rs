@@ -0,0 +1,30 @@
+use std::f64::consts::PI;
+
+use crate::{math::onb::Onb, sampling, Vec3};
+
+use super::Pdf;
+
+// Directions in the hemisphere around a normal, with a density proportional
+// to the cosine of their angle to it. This matches a Lambertian surface.
+pub struct CosinePdf {
+    pub uvw: Onb,
+}
+
+impl CosinePdf {
+    pub fn new(normal: Vec3) -> Self {
+        Self {
+            uvw: Onb::build_from_w(normal),
+        }
+    }
+}
+
+impl Pdf for CosinePdf {
+    fn value(&self, direction: Vec3) -> f64 {
+        let cosine = direction.unit_vector().dot(self.uvw.w);
+        (cosine / PI).max(0.0)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.uvw.local(sampling::cosine_hemisphere())
+    }
+}
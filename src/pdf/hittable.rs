@@ -0,0 +1,26 @@
+use crate::{hittable::Hittable, Point3, Vec3};
+
+use super::Pdf;
+
+// Directions from `origin` towards random points on `objects`, usually the
+// lights of a scene.
+pub struct HittablePdf<'a> {
+    pub objects: &'a dyn Hittable,
+    pub origin: Point3,
+}
+
+impl<'a> HittablePdf<'a> {
+    pub fn new(objects: &'a dyn Hittable, origin: Point3) -> Self {
+        Self { objects, origin }
+    }
+}
+
+impl Pdf for HittablePdf<'_> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.objects.pdf_value(self.origin, direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        self.objects.random_direction(self.origin)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{random_float, Vec3};
+
+use super::Pdf;
+
+// Samples `p0` with probability `weight` and `p1` otherwise. The density of
+// the result is the correspondingly weighted sum of both.
+pub struct MixturePdf<A: Pdf, B: Pdf> {
+    pub p0: A,
+    pub p1: B,
+    pub weight: f64,
+}
+
+impl<A: Pdf, B: Pdf> MixturePdf<A, B> {
+    pub fn new(p0: A, p1: B, weight: f64) -> Self {
+        Self { p0, p1, weight }
+    }
+
+    // Fraction of the density at `direction` contributed by p0. For one
+    // sample drawn from each PDF and an even mixture, this is the balance
+    // heuristic's weight for the sample from p0.
+    pub fn share_of_p0(&self, direction: Vec3) -> f64 {
+        let value = self.value(direction);
+        if value <= 0.0 {
+            return 0.0;
+        }
+
+        self.weight * self.p0.value(direction) / value
+    }
+}
+
+impl<A: Pdf, B: Pdf> Pdf for MixturePdf<A, B> {
+    fn value(&self, direction: Vec3) -> f64 {
+        self.weight * self.p0.value(direction) + (1.0 - self.weight) * self.p1.value(direction)
+    }
+
+    fn generate(&self) -> Vec3 {
+        if random_float() < self.weight {
+            self.p0.generate()
+        } else {
+            self.p1.generate()
+        }
+    }
+}
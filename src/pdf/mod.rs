@@ -0,0 +1,13 @@
+use crate::Vec3;
+
+pub mod cosine;
+pub mod hittable;
+pub mod mixture;
+
+// A distribution of directions, for importance sampling.
+pub trait Pdf {
+    // Density, per unit solid angle, of `generate` returning `direction`.
+    fn value(&self, direction: Vec3) -> f64;
+
+    fn generate(&self) -> Vec3;
+}
@@ -0,0 +1,169 @@
+use crate::{Color, Vec3};
+
+// Edge-preserving blur: every pixel becomes the weighted average of its
+// neighbours, where the weight falls off with the distance to them (a
+// Gaussian of width `sigma_spatial` pixels) and with how different their
+// color is (a Gaussian of width `sigma_range`). Noise gets averaged out
+// while pixels across an edge, which differ a lot, hardly mix.
+//
+// `image` holds `width` * `height` pixels row by row, in linear color. Both
+// widths must be positive.
+pub fn bilateral_filter(
+    image: &[Color],
+    width: u32,
+    height: u32,
+    sigma_spatial: f64,
+    sigma_range: f64,
+) -> Vec<Color> {
+    filter(image, width, height, sigma_spatial, sigma_range, |a, b| {
+        (image[a] - image[b]).length_squared()
+    })
+}
+
+// Like `bilateral_filter`, but tells edges apart by the surface normals
+// seen through the pixels (e.g. the AOV from the first hit) instead of
+// their color, with `sigma_range` measured between unit normals. Noise is
+// no edge to the normals, so this smooths more aggressively within a
+// surface and keeps the boundaries between surfaces sharp. Pixels without
+// a surface have a zero normal, which sets them apart from every surface.
+pub fn bilateral_filter_with_normals(
+    image: &[Color],
+    normals: &[Vec3],
+    width: u32,
+    height: u32,
+    sigma_spatial: f64,
+    sigma_range: f64,
+) -> Vec<Color> {
+    assert_eq!(
+        normals.len(),
+        image.len(),
+        "Normal buffer must match the image"
+    );
+    filter(image, width, height, sigma_spatial, sigma_range, |a, b| {
+        (normals[a] - normals[b]).length_squared()
+    })
+}
+
+// `range_distance` gives the squared range distance between two pixels,
+// by index.
+fn filter(
+    image: &[Color],
+    width: u32,
+    height: u32,
+    sigma_spatial: f64,
+    sigma_range: f64,
+    range_distance: impl Fn(usize, usize) -> f64,
+) -> Vec<Color> {
+    assert_eq!(
+        image.len(),
+        (width * height) as usize,
+        "Image must have width * height pixels"
+    );
+    // At zero the Gaussians divide by zero, and every pixel turns NaN.
+    assert!(sigma_spatial > 0.0, "Spatial sigma must be positive");
+    assert!(sigma_range > 0.0, "Range sigma must be positive");
+
+    // The spatial Gaussian is negligible beyond two standard deviations.
+    let radius = (2.0 * sigma_spatial).ceil() as i64;
+    let spatial = -0.5 / (sigma_spatial * sigma_spatial);
+    let range = -0.5 / (sigma_range * sigma_range);
+    let (width, height) = (width as i64, height as i64);
+
+    let mut filtered = Vec::with_capacity(image.len());
+    for y in 0..height {
+        for x in 0..width {
+            let center = (y * width + x) as usize;
+            let mut sum = Color::new(0.0, 0.0, 0.0);
+            let mut total_weight = 0.0;
+            for ny in (y - radius).max(0)..=(y + radius).min(height - 1) {
+                for nx in (x - radius).max(0)..=(x + radius).min(width - 1) {
+                    let neighbour = (ny * width + nx) as usize;
+                    let distance_squared = ((nx - x) * (nx - x) + (ny - y) * (ny - y)) as f64;
+                    let weight = (distance_squared * spatial
+                        + range_distance(center, neighbour) * range)
+                        .exp();
+                    sum += image[neighbour] * weight;
+                    total_weight += weight;
+                }
+            }
+            // The center pixel always has weight 1, so this never divides
+            // by zero.
+            filtered.push(sum / total_weight);
+        }
+    }
+
+    filtered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, random_float, rng};
+
+    // A 16 x 8 image, dark on the left half and bright on the right, with
+    // some noise on top.
+    fn noisy_edge() -> Vec<Color> {
+        rng::seed_thread(10);
+        (0..16 * 8)
+            .map(|index| {
+                let base = if index % 16 < 8 { 0.2 } else { 0.8 };
+                Color::from(base + 0.05 * (random_float() - 0.5))
+            })
+            .collect()
+    }
+
+    // Variance of the red channel over the dark half of the image.
+    fn spread_on_the_left(image: &[Color]) -> f64 {
+        let values: Vec<f64> = image
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| index % 16 < 8)
+            .map(|(_, c)| c.x())
+            .collect();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+    }
+
+    #[test]
+    fn noise_is_smoothed_and_edges_kept() {
+        let image = noisy_edge();
+        let filtered = bilateral_filter(&image, 16, 8, 1.0, 0.1);
+
+        assert!(spread_on_the_left(&filtered) < 0.5 * spread_on_the_left(&image));
+        for (index, color) in filtered.iter().enumerate() {
+            let base = if index % 16 < 8 { 0.2 } else { 0.8 };
+            assert!(
+                (color.x() - base).abs() < 0.03,
+                "pixel {}: {:?}",
+                index,
+                color
+            );
+        }
+    }
+
+    #[test]
+    fn narrow_filters_leave_the_image_as_it_is() {
+        let image = noisy_edge();
+        for filtered in [
+            bilateral_filter(&image, 16, 8, 1e-3, 0.1),
+            bilateral_filter(&image, 16, 8, 1.0, 1e-6),
+        ] {
+            for (a, b) in filtered.iter().zip(&image) {
+                assert_vec3_approx_eq!(*a, *b, 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Spatial sigma must be positive")]
+    fn zero_spatial_sigma_is_rejected() {
+        bilateral_filter(&noisy_edge(), 16, 8, 0.0, 0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Range sigma must be positive")]
+    fn zero_range_sigma_is_rejected() {
+        let normals = vec![Vec3::new(0.0, 0.0, 1.0); 16 * 8];
+        bilateral_filter_with_normals(&noisy_edge(), &normals, 16, 8, 1.0, 0.0);
+    }
+}
@@ -0,0 +1,3 @@
+// Filters applied to a finished image.
+
+pub mod bilateral;
@@ -1,9 +1,119 @@
-use crate::{hittable::Hittable, Color, Point3, Vec3};
+use std::{fmt, str::FromStr};
 
+use crate::{
+    background::Background,
+    hittable::{light_list::LightList, HitRecord, Hittable},
+    pdf::{cosine::CosinePdf, hittable::HittablePdf, mixture::MixturePdf, Pdf},
+    random_float,
+    stats::RENDER_STATS,
+    Color, Point3, Vec3,
+};
+
+// What `Ray::color` computes for a camera ray.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RenderMode {
+    // Full global illumination.
+    #[default]
+    PathTrace,
+    // Unoccluded fraction of the hemisphere above the first hit, looking
+    // `distance` far with `samples` rays. Ignores materials and lights, so
+    // it is a quick way to look at the geometry.
+    AmbientOcclusion {
+        distance: f64,
+        samples: u32,
+    },
+    // Path tracing with single wavelengths instead of RGB paths, see
+    // `color::spectral`.
+    #[cfg(feature = "spectral")]
+    Spectral,
+}
+
+#[derive(Debug)]
+pub struct ParseRenderModeError(String);
+
+impl fmt::Display for ParseRenderModeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown render mode '{}', expected path, ao[=DISTANCE[,SAMPLES]] or spectral (with the spectral feature)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseRenderModeError {}
+
+// Parses "path", or "ao" optionally followed by the distance and the number
+// of rays, e.g. "ao=2.5,8". Plain "ao" looks 1 unit far with one ray, which
+// averages out over the samples of a pixel like any other.
+impl FromStr for RenderMode {
+    type Err = ParseRenderModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseRenderModeError(s.to_string());
+        if s == "path" {
+            return Ok(RenderMode::PathTrace);
+        }
+        #[cfg(feature = "spectral")]
+        if s == "spectral" {
+            return Ok(RenderMode::Spectral);
+        }
+
+        let (mut distance, mut samples) = (1.0, 1);
+        let args = match s.strip_prefix("ao") {
+            Some("") => None,
+            Some(rest) => Some(rest.strip_prefix('=').ok_or_else(error)?),
+            None => return Err(error()),
+        };
+        if let Some(args) = args {
+            let (d, n) = match args.split_once(',') {
+                Some((d, n)) => (d, Some(n)),
+                None => (args, None),
+            };
+            distance = d.parse().map_err(|_| error())?;
+            if let Some(n) = n {
+                samples = n.parse().map_err(|_| error())?;
+            }
+        }
+        if distance <= 0.0 || samples == 0 {
+            return Err(error());
+        }
+
+        Ok(RenderMode::AmbientOcclusion { distance, samples })
+    }
+}
+
+// Fraction of `samples` cosine-distributed rays from `rec` that travel
+// `distance` without hitting anything, as a grey level. Cosine weighting
+// makes this the cosine-weighted visibility a diffuse surface would see.
+pub fn ambient_occlusion_color(
+    rec: &HitRecord,
+    world: &dyn Hittable,
+    samples: u32,
+    distance: f64,
+    time: f64,
+) -> Color {
+    let pdf = CosinePdf::new(rec.normal);
+    let unoccluded = (0..samples)
+        .filter(|_| {
+            let ray = Ray::new(rec.p, pdf.generate(), Some(time));
+            // Directions are unit vectors, so t is the distance.
+            world.hit(&ray, 0.001, distance).is_none()
+        })
+        .count();
+    let visibility = unoccluded as f64 / samples as f64;
+
+    Color::new(visibility, visibility, visibility)
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ray {
     pub origin: Point3,
     pub direction: Vec3,
     pub time: f64,
+    // The only one of red, green and blue the path carries on from here,
+    // once dispersive glass split white light into them.
+    pub channel: Option<usize>,
 }
 
 impl Ray {
@@ -12,6 +122,15 @@ impl Ray {
             origin,
             direction,
             time: if let Some(t) = time { t } else { 0.0 },
+            channel: None,
+        }
+    }
+
+    // `next` as the continuation of this ray's path, in its channel.
+    pub fn continued_by(&self, next: Ray) -> Ray {
+        Ray {
+            channel: next.channel.or(self.channel),
+            ..next
         }
     }
 
@@ -19,31 +138,177 @@ impl Ray {
         self.origin + self.direction * t
     }
 
-    pub fn color(&self, world: &dyn Hittable, depth: i32) -> Color {
-        if depth <= 0 {
-            return Color::new(0.0, 0.0, 0.0);
+    // Light arriving along the ray, or its ambient occlusion, depending on
+    // `mode`.
+    pub fn color(
+        &self,
+        mode: RenderMode,
+        world: &dyn Hittable,
+        lights: &LightList,
+        background: &dyn Background,
+        depth: i32,
+    ) -> Color {
+        match mode {
+            RenderMode::PathTrace => self.path_trace(world, lights, background, depth),
+            RenderMode::AmbientOcclusion { distance, samples } => {
+                self.ambient_occlusion(world, distance, samples)
+            }
+            #[cfg(feature = "spectral")]
+            RenderMode::Spectral => {
+                crate::color::spectral::spectral_color(self, world, lights, background, depth)
+            }
         }
+    }
+
+    // Escaping rays count as unoccluded, so the sky shows up white.
+    fn ambient_occlusion(&self, world: &dyn Hittable, distance: f64, samples: u32) -> Color {
+        let color = match world.hit(self, 0.001, f64::MAX) {
+            Some(hit) => ambient_occlusion_color(&hit, world, samples, distance, self.time),
+            None => Color::new(1.0, 1.0, 1.0),
+        };
+        RENDER_STATS.record_path(1 + samples as u64, 1, 0, 0);
+        color
+    }
+
+    // Rays that escape the scene pick up radiance from `background`. Scenes
+    // lit only by their lights should pass black.
+    //
+    // Paths are traced iteratively, tracking the product of attenuations
+    // along the way (the throughput). After MIN_BOUNCES bounces a path
+    // survives with a probability equal to its brightest throughput channel
+    // and is reweighted by it (Russian roulette), so dark paths end early
+    // without biasing the result. `depth` remains a hard cap on the length.
+    //
+    // At every diffuse bounce a shadow ray is also sent towards a random
+    // point on one of `lights` (next-event estimation). Light reached that
+    // way and light found by the scattered ray are weighted against each
+    // other with the balance heuristic, so each is counted once and small
+    // lights converge without fireflies. With one sample from each strategy
+    // the weights are their shares of a 50/50 mixture of the cosine lobe and
+    // the lights.
+    fn path_trace(
+        &self,
+        world: &dyn Hittable,
+        lights: &LightList,
+        background: &dyn Background,
+        depth: i32,
+    ) -> Color {
+        const MIN_BOUNCES: i32 = 3;
+
+        let mut ray = Ray::new(self.origin, self.direction, Some(self.time));
+        let mut radiance = Color::new(0.0, 0.0, 0.0);
+        let mut throughput = Color::new(1.0, 1.0, 1.0);
+        let (mut rays_cast, mut hits, mut bounces, mut shadow_rays) = (0, 0, 0, 0);
+        // Mixture of the strategies at the last bounce, if the light `ray`
+        // may hit was sampled directly as well.
+        let mut last_bounce: Option<MixturePdf<CosinePdf, HittablePdf>> = None;
+
+        for bounce in 0..depth {
+            rays_cast += 1;
+            let weight = match &last_bounce {
+                Some(mixture) => mixture.share_of_p0(ray.direction),
+                None => 1.0,
+            };
+            let Some(hit) = world.hit(&ray, 0.001, f64::MAX) else {
+                radiance += throughput
+                    * (background.sample(ray.direction)
+                        + lights.distant_radiance(ray.direction) * weight);
+                break;
+            };
+            hits += 1;
+
+            let emitted = hit.material.emitted(hit.u, hit.v, hit.p);
+            radiance += throughput * emitted * weight;
 
-        if let Some(hit) = world.hit(self, 0.001, f64::MAX) {
-            if let Some((scattered, attenuation)) = hit.material.scatter(&self, &hit) {
-                return attenuation * scattered.color(world, depth - 1);
+            let Some(scatter) = hit.material.scatter(&ray, &hit) else {
+                break;
+            };
+            bounces += 1;
+            let material_pdf = hit.material.scattering_pdf(&ray, &hit, &scatter.ray);
+
+            last_bounce = None;
+            if !scatter.is_specular && !lights.is_empty() && material_pdf > 0.0 {
+                let mixture = MixturePdf::new(
+                    CosinePdf::new(lobe_normal(&hit, &scatter.ray)),
+                    HittablePdf::new(lights, hit.p),
+                    0.5,
+                );
+                rays_cast += 1;
+                shadow_rays += 1;
+                radiance +=
+                    throughput * scatter.attenuation * sample_light(world, &ray, &hit, &mixture);
+                last_bounce = Some(mixture);
+            }
+
+            // Divide out the density the direction was drawn with.
+            let attenuation = match scatter.pdf {
+                None => scatter.attenuation,
+                Some(pdf) if pdf > 0.0 => scatter.attenuation * (material_pdf / pdf),
+                // A grazing direction, which carries no light.
+                Some(_) => break,
+            };
+            throughput = throughput * attenuation;
+
+            if bounce >= MIN_BOUNCES {
+                let survival = throughput
+                    .x()
+                    .max(throughput.y())
+                    .max(throughput.z())
+                    .min(1.0);
+                if random_float() >= survival {
+                    break;
+                }
+                throughput /= survival;
             }
 
-            return Color::new(0.0, 0.0, 0.0);
+            ray = ray.continued_by(scatter.ray);
         }
 
-        // unit_direction is a vector of length 1 that points in the direction
-        // of the ray. The x and y components are between -1 and 1. If we add 1
-        // to the y component, then the y component will be between 0 and 2. We
-        // multiply this by 0.5, so the y component will be between 0 and 1.
-        // This gives us a value that can be used as a lerp parameter.
-        // Which means we can use it to interpolate between the two colors.
-        let unit_direction = self.direction.unit_vector();
-        let t = 0.5 * (unit_direction.y() + 1.0);
-        let white = Color::new(1.0, 1.0, 1.0);
-        let blue = Color::new(0.5, 0.7, 1.0);
+        RENDER_STATS.record_path(rays_cast, hits, bounces, shadow_rays);
+        radiance
+    }
+}
 
-        // Linear interpolation between white and blue.
-        white * (1.0 - t) + blue * t
+// Normal of the cosine lobe that light samples at `hit` are weighted
+// against: on the side of the surface `scattered` leaves to, which is behind
+// it for materials that transmit light diffusely.
+pub(crate) fn lobe_normal(hit: &HitRecord, scattered: &Ray) -> Vec3 {
+    if scattered.direction.dot(hit.normal) < 0.0 {
+        -hit.normal
+    } else {
+        hit.normal
     }
 }
+
+// Light arriving at `hit` along a shadow ray towards a random light, divided
+// by the material's attenuation and weighted for combining it with the
+// scattered ray. The BRDF times the cosine is attenuation * scattering_pdf,
+// so the estimate brdf * cos * emitted / light_pdf * weight simplifies to the
+// expression below.
+pub(crate) fn sample_light(
+    world: &dyn Hittable,
+    ray_in: &Ray,
+    hit: &HitRecord,
+    mixture: &MixturePdf<CosinePdf, HittablePdf>,
+) -> Color {
+    let black = Color::new(0.0, 0.0, 0.0);
+
+    let direction = mixture.p1.generate();
+    let shadow_ray = Ray::new(hit.p, direction, Some(ray_in.time));
+    let light_pdf = mixture.p1.value(direction);
+    let scattering_pdf = hit.material.scattering_pdf(ray_in, hit, &shadow_ray);
+    if light_pdf <= 0.0 || scattering_pdf <= 0.0 {
+        return black;
+    }
+    let weight = 1.0 - mixture.share_of_p0(direction);
+
+    // Whatever the shadow ray hits first is what the scattered ray would
+    // have found in this direction, light or not.
+    let emitted = match world.hit(&shadow_ray, 0.001, f64::MAX) {
+        Some(light_hit) => light_hit
+            .material
+            .emitted(light_hit.u, light_hit.v, light_hit.p),
+        None => mixture.p1.objects.distant_radiance(direction),
+    };
+    emitted * (scattering_pdf / light_pdf * weight)
+}
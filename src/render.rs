@@ -0,0 +1,133 @@
+use rayon::prelude::*;
+
+use crate::{
+    background::Background,
+    camera::RayGenerator,
+    config::RenderConfig,
+    hittable::{light_list::LightList, Hittable},
+    ray::RenderMode,
+    rng,
+    sampler::{AnySampler, Sampler, SamplerKind},
+    Color,
+};
+
+// Edge length of the square tiles the image is rendered in. Each thread
+// works on one tile at a time, which keeps its rays close together.
+pub const TILE_SIZE: u32 = 64;
+
+// A rectangular block of the image, in pixels counted from the bottom left.
+#[derive(Debug, Clone, Copy)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Splits the image into tiles of at most tile_size x tile_size pixels, top
+// row first. Tiles on the right and top edges are cropped to fit.
+pub fn tiles(width: u32, height: u32, tile_size: u32) -> Vec<Tile> {
+    let mut tiles = Vec::new();
+    for y in (0..height).step_by(tile_size as usize).rev() {
+        for x in (0..width).step_by(tile_size as usize) {
+            tiles.push(Tile {
+                x,
+                y,
+                width: tile_size.min(width - x),
+                height: tile_size.min(height - y),
+            });
+        }
+    }
+
+    tiles
+}
+
+// Renders the tiles of a width x height image in parallel, except those
+// `skip` returns true for, given their index in `tiles`. `render_pixel`
+// computes pixel (i, j), counted from the bottom left, after its random
+// stream is seeded, so every pixel is the same no matter which thread
+// renders it or when. `on_tile` gets the index of each finished tile and
+// its pixels, rows top first. If it fails, the remaining tiles are skipped
+// and the error is returned.
+pub fn render_tiles<P, E>(
+    width: u32,
+    height: u32,
+    skip: impl Fn(usize) -> bool + Sync,
+    render_pixel: impl Fn(u32, u32) -> P + Sync,
+    on_tile: impl Fn(usize, Vec<P>) -> Result<(), E> + Sync,
+) -> Result<(), E>
+where
+    P: Send,
+    E: Send,
+{
+    tiles(width, height, TILE_SIZE)
+        .into_par_iter()
+        .enumerate()
+        .filter(|(index, _)| !skip(*index))
+        .try_for_each(|(index, tile)| {
+            let mut pixels = Vec::with_capacity((tile.width * tile.height) as usize);
+            for j in (tile.y..tile.y + tile.height).rev() {
+                for i in tile.x..tile.x + tile.width {
+                    rng::seed_stream((j * width + i) as u64);
+                    pixels.push(render_pixel(i, j));
+                }
+            }
+
+            on_tile(index, pixels)
+        })
+}
+
+// Path traces the image described by `config` on rayon's thread pool,
+// without a window or any output of its own. Diffuse surfaces sample
+// `lights` directly; pass an empty list for scenes lit by their background
+// only. `on_pixel` is called with the column, the row (counted from the
+// top) and the averaged linear color of every pixel as it finishes, from
+// whichever thread rendered it, so pixels arrive in no particular order.
+//
+// With a seed set through `rng::set_global_seed` the image is the same
+// each time.
+pub fn render_with_progress<F>(
+    config: &RenderConfig,
+    world: &dyn Hittable,
+    lights: &LightList,
+    camera: &dyn RayGenerator,
+    background: &dyn Background,
+    on_pixel: F,
+) where
+    F: Fn(u32, u32, Color) + Send + Sync,
+{
+    let RenderConfig {
+        width,
+        height,
+        samples,
+        max_depth,
+    } = *config;
+
+    let render_pixel = |i: u32, j: u32| {
+        let mut sampler = AnySampler::new(SamplerKind::default(), samples);
+        let color: Color = (0..samples)
+            .map(|_| {
+                let (du, dv) = sampler.next_2d();
+                let u = (i as f64 + du) / (width - 1) as f64;
+                let v = (j as f64 + dv) / (height - 1) as f64;
+                camera.get_ray(u, v).color(
+                    RenderMode::default(),
+                    world,
+                    lights,
+                    background,
+                    max_depth as i32,
+                )
+            })
+            .sum();
+
+        (i, height - 1 - j, color / samples as f64)
+    };
+    let on_tile = |_, pixels: Vec<(u32, u32, Color)>| {
+        for (x, y, color) in pixels {
+            on_pixel(x, y, color);
+        }
+        Ok::<(), ()>(())
+    };
+
+    let _ = render_tiles(width, height, |_| false, render_pixel, on_tile);
+}
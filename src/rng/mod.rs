@@ -0,0 +1,169 @@
+// Random numbers for rendering. Every thread owns a small, fast generator,
+// seeded from a global seed. Renders are reproducible when that seed is set
+// with `set_global_seed` and every unit of work (a pixel, a pass of a pixel,
+// ...) starts its own stream with `seed_stream`, since the threads pick up
+// work in no particular order.
+
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        OnceLock,
+    },
+};
+
+use rand::{Rng, SeedableRng};
+
+pub mod pcg;
+
+// SmallRng (Xoshiro256++ on 64 bit targets) draws floats about twice as
+// fast as Pcg32, which is in turn more than twice as fast as thread_rng
+// (see benches/rng.rs).
+#[cfg(not(feature = "pcg"))]
+pub type Generator = rand::rngs::SmallRng;
+#[cfg(feature = "pcg")]
+pub type Generator = pcg::Pcg32;
+
+static GLOBAL_SEED: AtomicU64 = AtomicU64::new(0);
+static SEEDED: AtomicBool = AtomicBool::new(false);
+// Used until a seed is set, so unseeded renders differ from run to run.
+static ENTROPY_SEED: OnceLock<u64> = OnceLock::new();
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static CONTEXT: RefCell<RngContext> = RefCell::new(RngContext::new(mix(
+        global_seed(),
+        NEXT_THREAD.fetch_add(1, Ordering::Relaxed),
+    )));
+}
+
+// The generator of one thread.
+pub struct RngContext {
+    rng: Generator,
+}
+
+impl RngContext {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Generator::seed_from_u64(seed),
+        }
+    }
+
+    pub fn rng(&mut self) -> &mut Generator {
+        &mut self.rng
+    }
+}
+
+// Seeds all generators, including the one of the calling thread.
+// Generators of other threads that already exist keep their state until
+// they are given a stream with `seed_stream`.
+pub fn set_global_seed(seed: u64) {
+    GLOBAL_SEED.store(seed, Ordering::Relaxed);
+    SEEDED.store(true, Ordering::Release);
+    CONTEXT.with(|context| *context.borrow_mut() = RngContext::new(mix(seed, u64::MAX)));
+}
+
+pub fn global_seed() -> u64 {
+    if SEEDED.load(Ordering::Acquire) {
+        GLOBAL_SEED.load(Ordering::Relaxed)
+    } else {
+        *ENTROPY_SEED.get_or_init(rand::random)
+    }
+}
+
+// Restarts the calling thread's generator at the stream derived from the
+// global seed and `stream`, so the numbers drawn next don't depend on what
+// the thread did before.
+pub fn seed_stream(stream: u64) {
+    CONTEXT.with(|context| *context.borrow_mut() = RngContext::new(mix(global_seed(), stream)));
+}
+
+// Restarts the calling thread's generator at `seed`, whatever the global
+// seed, so tests draw the same numbers on every run.
+#[cfg(test)]
+pub(crate) fn seed_thread(seed: u64) {
+    CONTEXT.with(|context| *context.borrow_mut() = RngContext::new(seed));
+}
+
+// Runs `f` with the calling thread's generator.
+pub fn with_rng<R>(f: impl FnOnce(&mut Generator) -> R) -> R {
+    CONTEXT.with(|context| f(context.borrow_mut().rng()))
+}
+
+// Random number in [0.0, 1.0).
+pub fn next_f64() -> f64 {
+    with_rng(|rng| rng.gen_range(0.0..1.0))
+}
+
+// Combines a seed and a stream number into a well distributed seed, so
+// neighbouring streams are unrelated (SplitMix64 finalizer).
+fn mix(seed: u64, stream: u64) -> u64 {
+    let mut z = seed ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::SmallRng;
+
+    use super::*;
+    use pcg::Pcg32;
+
+    const BUCKETS: usize = 100;
+    const DRAWS: usize = 100_000;
+    // Chi-squared with 99 degrees of freedom exceeds this once in a
+    // thousand times for uniform numbers.
+    const CRITICAL: f64 = 148.2;
+
+    // Pearson's statistic for `DRAWS` numbers falling into the buckets that
+    // `bucket` puts them in.
+    fn chi_squared(mut bucket: impl FnMut() -> usize) -> f64 {
+        let mut counts = [0usize; BUCKETS];
+        for _ in 0..DRAWS {
+            counts[bucket()] += 1;
+        }
+        let expected = DRAWS as f64 / BUCKETS as f64;
+        counts
+            .iter()
+            .map(|&count| (count as f64 - expected).powi(2) / expected)
+            .sum()
+    }
+
+    // Floats split into 100 slices of [0, 1), and consecutive pairs of them
+    // into a 10 x 10 grid, which also catches draws that depend on the one
+    // before.
+    fn assert_uniform(name: &str, rng: &mut impl Rng) {
+        let singles = chi_squared(|| (rng.gen_range(0.0..1.0) * BUCKETS as f64) as usize);
+        assert!(singles < CRITICAL, "{} floats: {}", name, singles);
+
+        let pairs = chi_squared(|| {
+            let x = (rng.gen_range(0.0..1.0) * 10.0) as usize;
+            let y = (rng.gen_range(0.0..1.0) * 10.0) as usize;
+            10 * y + x
+        });
+        assert!(pairs < CRITICAL, "{} pairs: {}", name, pairs);
+    }
+
+    #[test]
+    fn generators_draw_uniform_floats() {
+        for seed in [1, 2, 3] {
+            assert_uniform("SmallRng", &mut SmallRng::seed_from_u64(seed));
+            assert_uniform("Pcg32", &mut Pcg32::seed_from_u64(seed));
+        }
+    }
+
+    #[test]
+    fn streams_start_at_uniform_floats() {
+        // The first number of each of many streams, as pixels take them,
+        // with the streams seeded the way seed_stream does.
+        let mut stream = 0;
+        let firsts = chi_squared(|| {
+            stream += 1;
+            seed_thread(mix(7, stream));
+            (next_f64() * BUCKETS as f64) as usize
+        });
+        assert!(firsts < CRITICAL, "first draws of streams: {}", firsts);
+    }
+}
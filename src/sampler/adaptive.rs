@@ -0,0 +1,196 @@
+use crate::Color;
+
+// Running mean and variance of a pixel's samples, updated one sample at a
+// time with Welford's algorithm, which unlike summing squares doesn't lose
+// precision when the samples are much larger than their spread.
+#[derive(Debug, Clone, Copy)]
+pub struct PixelStats {
+    count: u32,
+    mean: Color,
+    // Sum of squared differences from the mean.
+    m2: Color,
+}
+
+impl Default for PixelStats {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            mean: Color::new(0.0, 0.0, 0.0),
+            m2: Color::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl PixelStats {
+    pub fn add(&mut self, sample: Color) {
+        self.count += 1;
+        let delta = sample - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (sample - self.mean);
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn mean(&self) -> Color {
+        self.mean
+    }
+
+    // Unbiased sample variance of each channel.
+    pub fn variance(&self) -> Color {
+        if self.count < 2 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        self.m2 / (self.count - 1) as f64
+    }
+
+    // Standard error of the mean, averaged over the channels: how far the
+    // pixel's value is likely to be from the converged one.
+    pub fn error(&self) -> f64 {
+        let variance = self.variance();
+        let average = (variance.x() + variance.y() + variance.z()) / 3.0;
+        (average / self.count.max(1) as f64).sqrt()
+    }
+}
+
+// Spends samples where the image is still noisy. Every pixel gets at least
+// `min_samples`; after that it takes batches of them until the standard
+// error of its mean falls below `threshold` times the square root of its
+// brightness, or it reaches `max_samples`. The square root roughly follows
+// how visible noise is after gamma encoding, so dark regions aren't held to
+// a much stricter standard than bright ones. Smooth regions converge early,
+// and their samples go to edges, caustics and soft shadows instead. A pixel
+// whose first batch happens to be all the same, like one a thin hard edge
+// barely covers, counts as converged too.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveSampler {
+    pub min_samples: u32,
+    pub max_samples: u32,
+    pub threshold: f64,
+}
+
+impl AdaptiveSampler {
+    // Below this brightness pixels are judged by their absolute error, so
+    // black ones converge too.
+    const DARK: f64 = 1e-3;
+
+    pub fn new(min_samples: u32, max_samples: u32, threshold: f64) -> Self {
+        Self {
+            min_samples: min_samples.max(2),
+            max_samples: max_samples.max(min_samples.max(2)),
+            threshold,
+        }
+    }
+
+    pub fn converged(&self, stats: &PixelStats) -> bool {
+        let mean = stats.mean();
+        let brightness = (mean.x() + mean.y() + mean.z()) / 3.0;
+        stats.error() <= self.threshold * brightness.max(Self::DARK).sqrt()
+    }
+
+    // Samples one pixel, calling `sample` until it converges.
+    pub fn sample_pixel(&self, mut sample: impl FnMut() -> Color) -> PixelStats {
+        let mut stats = PixelStats::default();
+        // Checking after every sample would stop on the first lucky streak.
+        while stats.count() < self.max_samples {
+            let batch = if stats.count() == 0 {
+                self.min_samples
+            } else {
+                self.min_samples.min(self.max_samples - stats.count())
+            };
+            for _ in 0..batch {
+                stats.add(sample());
+            }
+            if self.converged(&stats) {
+                break;
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{random_float, rng};
+
+    // A row of pixels whose exact values are known: flat ones that are 0.5
+    // wherever they're sampled, and ones crossed by a soft edge, a ramp
+    // from black to white over half of the pixel, starting `start` into it.
+    const FLAT: usize = 150;
+    const EDGES: usize = 50;
+    const RAMP: f64 = 0.5;
+
+    fn start(pixel: usize) -> Option<f64> {
+        (pixel >= FLAT).then(|| (pixel - FLAT) as f64 / EDGES as f64 * (1.0 - RAMP))
+    }
+
+    fn exact(pixel: usize) -> f64 {
+        match start(pixel) {
+            Some(start) => 1.0 - start - RAMP / 2.0,
+            None => 0.5,
+        }
+    }
+
+    fn sample(pixel: usize) -> Color {
+        let value = match start(pixel) {
+            Some(start) => ((random_float() - start) / RAMP).clamp(0.0, 1.0),
+            None => 0.5,
+        };
+        Color::from(value)
+    }
+
+    fn rmse(means: &[f64]) -> f64 {
+        let squared: f64 = means
+            .iter()
+            .enumerate()
+            .map(|(pixel, mean)| (mean - exact(pixel)).powi(2))
+            .sum();
+        (squared / means.len() as f64).sqrt()
+    }
+
+    #[test]
+    fn adaptive_sampling_beats_uniform_sampling_on_the_same_budget() {
+        rng::seed_thread(9);
+        let adaptive = AdaptiveSampler::new(16, 1024, 0.01);
+        let stats: Vec<PixelStats> = (0..FLAT + EDGES)
+            .map(|pixel| adaptive.sample_pixel(|| sample(pixel)))
+            .collect();
+        assert!(stats[..FLAT].iter().all(|s| s.count() == 16));
+
+        let budget: u32 = stats.iter().map(PixelStats::count).sum();
+        let per_pixel = budget / (FLAT + EDGES) as u32;
+        let uniform: Vec<f64> = (0..FLAT + EDGES)
+            .map(|pixel| {
+                let sum: f64 = (0..per_pixel).map(|_| sample(pixel).x()).sum();
+                sum / per_pixel as f64
+            })
+            .collect();
+        let adaptive: Vec<f64> = stats.iter().map(|s| s.mean().x()).collect();
+
+        let (adaptive, uniform) = (rmse(&adaptive), rmse(&uniform));
+        assert!(
+            adaptive < 0.75 * uniform,
+            "adaptive {} against uniform {}",
+            adaptive,
+            uniform
+        );
+    }
+
+    #[test]
+    fn pixel_stats_match_the_two_pass_formulas() {
+        let samples = [0.2, 1.5, 0.7, 3.1, 0.0, 2.2];
+        let mut stats = PixelStats::default();
+        for s in samples {
+            stats.add(Color::from(s));
+        }
+
+        let mean = samples.iter().sum::<f64>() / 6.0;
+        let variance = samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / 5.0;
+        assert!((stats.mean().x() - mean).abs() < 1e-12);
+        assert!((stats.variance().y() - variance).abs() < 1e-12);
+        assert!((stats.error() - (variance / 6.0).sqrt()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::random_float;
+
+use super::Sampler;
+
+// Low-discrepancy samples from the Halton sequence: the radical inverses of
+// 0, 1, 2, ... in base_x and base_y. Consecutive points fill the square far
+// more evenly than independent ones, for any number of samples. Every
+// sampler shifts its points by a random offset, wrapping around
+// (Cranley-Patterson rotation), so neighbouring pixels don't repeat the
+// same pattern.
+#[derive(Debug, Clone, Copy)]
+pub struct HaltonSampler {
+    pub base_x: u32,
+    pub base_y: u32,
+    pub index: u64,
+    offset: (f64, f64),
+}
+
+impl HaltonSampler {
+    // The usual first two primes, 2 and 3.
+    pub fn new() -> Self {
+        Self::with_bases(2, 3)
+    }
+
+    // The bases have to be coprime for the points to cover the square.
+    pub fn with_bases(base_x: u32, base_y: u32) -> Self {
+        assert!(
+            base_x >= 2 && base_y >= 2,
+            "Halton bases have to be at least 2"
+        );
+        Self {
+            base_x,
+            base_y,
+            index: 0,
+            offset: (random_float(), random_float()),
+        }
+    }
+}
+
+impl Default for HaltonSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn next_2d(&mut self) -> (f64, f64) {
+        let x = radical_inverse(self.index, self.base_x) + self.offset.0;
+        let y = radical_inverse(self.index, self.base_y) + self.offset.1;
+        self.index += 1;
+
+        (x.fract(), y.fract())
+    }
+}
+
+// Mirrors the digits of `index` in `base` around the radix point, e.g. 6 =
+// 110 in base 2 becomes 0.011 = 0.375.
+fn radical_inverse(mut index: u64, base: u32) -> f64 {
+    let base = base as u64;
+    let inv_base = 1.0 / base as f64;
+    let mut factor = inv_base;
+    let mut result = 0.0;
+    while index > 0 {
+        result += (index % base) as f64 * factor;
+        index /= base;
+        factor *= inv_base;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng, sampler::random::RandomSampler};
+
+    #[test]
+    fn radical_inverses_mirror_the_digits() {
+        assert_eq!(radical_inverse(0, 2), 0.0);
+        assert_eq!(radical_inverse(1, 2), 0.5);
+        assert_eq!(radical_inverse(6, 2), 0.375);
+        assert!((radical_inverse(5, 3) - (2.0 / 3.0 + 1.0 / 9.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn samples_lie_in_the_unit_square() {
+        rng::seed_thread(2);
+        let mut sampler = HaltonSampler::new();
+        for _ in 0..1000 {
+            let (x, y) = sampler.next_2d();
+            assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+        }
+        assert_eq!(sampler.index, 1000);
+    }
+
+    // Largest difference between the fraction of `points` inside a box
+    // [0, a) x [0, b) and its area a * b, over a grid of boxes.
+    fn star_discrepancy(points: &[(f64, f64)]) -> f64 {
+        let steps = 64;
+        let mut worst: f64 = 0.0;
+        for i in 1..=steps {
+            for j in 1..=steps {
+                let (a, b) = (i as f64 / steps as f64, j as f64 / steps as f64);
+                let inside = points.iter().filter(|(x, y)| *x < a && *y < b).count();
+                worst = worst.max((inside as f64 / points.len() as f64 - a * b).abs());
+            }
+        }
+
+        worst
+    }
+
+    #[test]
+    fn halton_points_are_more_even_than_random_ones() {
+        rng::seed_thread(3);
+        let mut halton = HaltonSampler::new();
+        let mut random = RandomSampler;
+        let halton: Vec<_> = (0..1024).map(|_| halton.next_2d()).collect();
+        let random: Vec<_> = (0..1024).map(|_| random.next_2d()).collect();
+
+        let (halton, random) = (star_discrepancy(&halton), star_discrepancy(&random));
+        assert!(
+            halton < random / 2.0,
+            "halton discrepancy {} against random {}",
+            halton,
+            random
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::{fmt, str::FromStr};
+
+use self::{halton::HaltonSampler, random::RandomSampler, stratified::StratifiedSampler};
+
+pub mod adaptive;
+pub mod halton;
+pub mod random;
+pub mod stratified;
+
+// Source of 2D sample positions in [0, 1)², e.g. sub-pixel offsets.
+pub trait Sampler {
+    fn next_2d(&mut self) -> (f64, f64);
+}
+
+// The samplers that can be picked for a render, e.g. on the command line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SamplerKind {
+    Random,
+    #[default]
+    Stratified,
+    Halton,
+}
+
+#[derive(Debug)]
+pub struct ParseSamplerKindError(String);
+
+impl fmt::Display for ParseSamplerKindError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown sampler '{}', expected random, stratified or halton",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseSamplerKindError {}
+
+impl FromStr for SamplerKind {
+    type Err = ParseSamplerKindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "random" => Ok(SamplerKind::Random),
+            "stratified" => Ok(SamplerKind::Stratified),
+            "halton" => Ok(SamplerKind::Halton),
+            _ => Err(ParseSamplerKindError(s.to_string())),
+        }
+    }
+}
+
+// A sampler of the kind chosen at run time. An enum rather than a boxed
+// trait object, so it can be cloned and stored per pixel.
+#[derive(Debug, Clone, Copy)]
+pub enum AnySampler {
+    Random(RandomSampler),
+    Stratified(StratifiedSampler),
+    Halton(HaltonSampler),
+}
+
+impl AnySampler {
+    // A sampler for taking `samples` samples of one pixel. The stratified
+    // grid is the largest square that fits into the sample count.
+    pub fn new(kind: SamplerKind, samples: u32) -> Self {
+        match kind {
+            SamplerKind::Random => AnySampler::Random(RandomSampler),
+            SamplerKind::Stratified => AnySampler::Stratified(StratifiedSampler::new(
+                ((samples as f64).sqrt() as u32).max(1),
+            )),
+            SamplerKind::Halton => AnySampler::Halton(HaltonSampler::new()),
+        }
+    }
+}
+
+impl Sampler for AnySampler {
+    fn next_2d(&mut self) -> (f64, f64) {
+        match self {
+            AnySampler::Random(sampler) => sampler.next_2d(),
+            AnySampler::Stratified(sampler) => sampler.next_2d(),
+            AnySampler::Halton(sampler) => sampler.next_2d(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use crate::random_float;
+
+use super::Sampler;
+
+// Independent uniform samples.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RandomSampler;
+
+impl Sampler for RandomSampler {
+    fn next_2d(&mut self) -> (f64, f64) {
+        (random_float(), random_float())
+    }
+}
@@ -0,0 +1,91 @@
+use crate::random_float;
+
+use super::Sampler;
+
+// Jittered sampling: [0, 1)² is split into a sqrt_spp x sqrt_spp grid and
+// each sample lands at a random spot inside the next cell. Every run of
+// sqrt_spp² samples covers the whole square evenly, which lowers variance
+// compared to independent samples. Starts over after a full grid.
+#[derive(Debug, Clone, Copy)]
+pub struct StratifiedSampler {
+    pub sqrt_spp: u32,
+    pub current: u32,
+}
+
+impl StratifiedSampler {
+    pub fn new(sqrt_spp: u32) -> Self {
+        assert!(sqrt_spp > 0, "StratifiedSampler needs at least one cell");
+        Self {
+            sqrt_spp,
+            current: 0,
+        }
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn next_2d(&mut self) -> (f64, f64) {
+        let i = self.current % self.sqrt_spp;
+        let j = (self.current / self.sqrt_spp) % self.sqrt_spp;
+        self.current = (self.current + 1) % (self.sqrt_spp * self.sqrt_spp);
+
+        let n = self.sqrt_spp as f64;
+        (
+            (i as f64 + random_float()) / n,
+            (j as f64 + random_float()) / n,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rng, sampler::random::RandomSampler};
+
+    #[test]
+    fn each_grid_puts_one_sample_in_every_cell() {
+        rng::seed_thread(1);
+        let mut sampler = StratifiedSampler::new(4);
+        for _ in 0..3 {
+            let mut cells = [[0; 4]; 4];
+            for _ in 0..16 {
+                let (x, y) = sampler.next_2d();
+                assert!((0.0..1.0).contains(&x) && (0.0..1.0).contains(&y));
+                cells[(y * 4.0) as usize][(x * 4.0) as usize] += 1;
+            }
+            assert_eq!(cells, [[1; 4]; 4]);
+        }
+    }
+
+    // Variance over many runs of the estimate of the area of a quarter disk,
+    // pi / 4, from `samples` samples each.
+    fn variance(mut sampler: impl Sampler, samples: u32) -> f64 {
+        let runs = 2000;
+        let estimates: Vec<f64> = (0..runs)
+            .map(|_| {
+                let inside = (0..samples)
+                    .filter(|_| {
+                        let (x, y) = sampler.next_2d();
+                        x * x + y * y < 1.0
+                    })
+                    .count();
+                inside as f64 / samples as f64
+            })
+            .collect();
+
+        let mean = estimates.iter().sum::<f64>() / runs as f64;
+        assert!((mean - std::f64::consts::FRAC_PI_4).abs() < 0.01, "{mean}");
+        estimates
+            .iter()
+            .map(|e| (e - mean) * (e - mean))
+            .sum::<f64>()
+            / runs as f64
+    }
+
+    #[test]
+    fn stratified_samples_have_lower_variance_than_random_ones() {
+        rng::seed_thread(2);
+        let random = variance(RandomSampler, 64);
+        let stratified = variance(StratifiedSampler::new(8), 64);
+        assert!(stratified < random / 4.0, "{stratified} vs {random}");
+    }
+}
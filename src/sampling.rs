@@ -0,0 +1,111 @@
+// Random points and directions drawn from the distributions the renderer
+// samples. Directions are unit vectors unless noted otherwise.
+
+use std::f64::consts::PI;
+
+use crate::{random_float, random_float_between, Vec3};
+
+// Uniform point inside the unit sphere (not normalized).
+pub fn in_unit_sphere() -> Vec3 {
+    loop {
+        let p = Vec3::random_between(-1.0, 1.0);
+        if p.length_squared() < 1.0 {
+            return p;
+        }
+    }
+}
+
+// Uniform direction over the whole sphere.
+pub fn uniform_sphere() -> Vec3 {
+    in_unit_sphere().unit_vector()
+}
+
+// Uniform point inside the unit sphere, on the same side as `normal`.
+pub fn uniform_hemisphere(normal: Vec3) -> Vec3 {
+    let in_unit_sphere = in_unit_sphere();
+    if in_unit_sphere.dot(normal) > 0.0 {
+        // In the same hemisphere as the normal.
+        in_unit_sphere
+    } else {
+        -in_unit_sphere
+    }
+}
+
+// Cosine distributed direction around +z: a uniform point on the unit disk
+// projected up onto the hemisphere.
+pub fn cosine_hemisphere() -> Vec3 {
+    let r1 = random_float();
+    let r2 = random_float();
+    let phi = 2.0 * PI * r1;
+    let r = r2.sqrt();
+
+    Vec3::new(phi.cos() * r, phi.sin() * r, (1.0 - r2).sqrt())
+}
+
+// Uniform direction within the cone around +z whose half-angle has cosine
+// `cos_theta_max`, e.g. the directions towards a sphere.
+pub fn uniform_cone(cos_theta_max: f64) -> Vec3 {
+    let r1 = random_float();
+    let r2 = random_float();
+    let z = 1.0 + r2 * (cos_theta_max - 1.0);
+    let phi = 2.0 * PI * r1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+
+    Vec3::new(phi.cos() * r, phi.sin() * r, z)
+}
+
+// Direction around +z distributed by the Henyey-Greenstein phase function
+// with asymmetry `g`: mostly close to +z for g near 1, mostly away from it
+// near -1 and uniform for 0.
+pub fn henyey_greenstein(g: f64) -> Vec3 {
+    let r1 = random_float();
+    let r2 = random_float();
+    let z = if g.abs() < 1e-3 {
+        1.0 - 2.0 * r2
+    } else {
+        let s = (1.0 - g * g) / (1.0 - g + 2.0 * g * r2);
+        (1.0 + g * g - s * s) / (2.0 * g)
+    };
+    let phi = 2.0 * PI * r1;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+
+    Vec3::new(phi.cos() * r, phi.sin() * r, z)
+}
+
+// Uniform point inside the unit disk of the xy plane.
+pub fn uniform_disk() -> Vec3 {
+    loop {
+        let p = Vec3::new(
+            random_float_between(-1.0, 1.0),
+            random_float_between(-1.0, 1.0),
+            0.0,
+        );
+        if p.length_squared() < 1.0 {
+            return p;
+        }
+    }
+}
+
+// Uniform point inside a regular polygon with the given number of sides,
+// inscribed in the unit circle of the xy plane. The polygon is a fan of
+// identical triangles around the origin, so picking one at random and then
+// a uniform point within it covers the whole area evenly.
+pub fn uniform_polygon(sides: u32, rotation_degrees: f64) -> Vec3 {
+    assert!(sides >= 3, "a polygon needs at least 3 sides");
+
+    let step = 2.0 * PI / sides as f64;
+    let k = ((random_float() * sides as f64) as u32).min(sides - 1);
+    let angle = rotation_degrees.to_radians() + step * k as f64;
+    let a = Vec3::new(angle.cos(), angle.sin(), 0.0);
+    let b = Vec3::new((angle + step).cos(), (angle + step).sin(), 0.0);
+
+    // Fold points of the unit square that fall outside the triangle back
+    // into it.
+    let (mut r1, mut r2) = (random_float(), random_float());
+    if r1 + r2 > 1.0 {
+        r1 = 1.0 - r1;
+        r2 = 1.0 - r2;
+    }
+
+    a * r1 + b * r2
+}
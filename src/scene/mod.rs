@@ -0,0 +1,518 @@
+use std::{error::Error, fmt, fs, io, path::Path, path::PathBuf, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    background::def::BackgroundDef,
+    camera::Camera,
+    config::RenderConfig,
+    hittable::{
+        aabox::AaBox,
+        cone::Cone,
+        cylinder::Cylinder,
+        disk::Disk,
+        light_list::LightList,
+        mesh::{MeshError, TriangleMesh},
+        moving_sphere::MovingSphere,
+        plane::Plane,
+        rect::{XyRect, XzRect, YzRect},
+        sphere::Sphere,
+        torus::Torus,
+        triangle::Triangle,
+        Hittable, HittableList,
+    },
+    material::def::MaterialDef,
+    Point3, Vec3,
+};
+
+pub mod toml_loader;
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(io::Error),
+    Parse(String),
+    Mesh { path: PathBuf, error: MeshError },
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "unable to read scene: {}", err),
+            SceneError::Parse(message) => write!(f, "invalid scene: {}", message),
+            SceneError::Mesh { path, error } => {
+                write!(f, "unable to load mesh {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl Error for SceneError {}
+
+impl From<io::Error> for SceneError {
+    fn from(err: io::Error) -> Self {
+        SceneError::Io(err)
+    }
+}
+
+// What loading a scene file gives.
+pub struct LoadedScene {
+    pub world: HittableList,
+    // The emitting spheres, rectangles and disks of `world`, shared with it,
+    // and the sun of a Rayleigh sky, for sampling lights directly. Emitting
+    // objects of other shapes are only found by paths that happen to hit
+    // them.
+    pub lights: LightList,
+    pub camera: Camera,
+    pub config: RenderConfig,
+    pub background: BackgroundDef,
+}
+
+// A complete scene as stored in a scene file: where to look from, what to
+// render and how.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneDescription {
+    pub camera: CameraDescription,
+    pub render: RenderConfig,
+    // What rays leaving the scene see, the Rayleigh sky if left out.
+    #[serde(default)]
+    pub background: BackgroundDef,
+    pub objects: Vec<ObjectDescription>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraDescription {
+    pub lookfrom: Point3,
+    pub lookat: Point3,
+    // Defaults to +y.
+    pub vup: Option<Vec3>,
+    // Vertical field of view in degrees.
+    pub vfov: f64,
+    // Lens diameter; defaults to 0, a pinhole with everything in focus.
+    pub aperture: Option<f64>,
+    // Defaults to the distance between lookfrom and lookat.
+    pub focus_dist: Option<f64>,
+    // Time interval [open, close] the shutter is open, for motion blur.
+    pub shutter: Option<(f64, f64)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ObjectDescription {
+    Sphere {
+        center: Point3,
+        radius: f64,
+        material: MaterialDef,
+    },
+    MovingSphere {
+        center0: Point3,
+        center1: Point3,
+        time0: f64,
+        time1: f64,
+        radius: f64,
+        material: MaterialDef,
+    },
+    Plane {
+        point: Point3,
+        normal: Vec3,
+        material: MaterialDef,
+    },
+    Triangle {
+        v0: Point3,
+        v1: Point3,
+        v2: Point3,
+        material: MaterialDef,
+    },
+    Box {
+        p0: Point3,
+        p1: Point3,
+        material: MaterialDef,
+    },
+    XyRect {
+        x0: f64,
+        x1: f64,
+        y0: f64,
+        y1: f64,
+        k: f64,
+        material: MaterialDef,
+    },
+    XzRect {
+        x0: f64,
+        x1: f64,
+        z0: f64,
+        z1: f64,
+        k: f64,
+        material: MaterialDef,
+    },
+    YzRect {
+        y0: f64,
+        y1: f64,
+        z0: f64,
+        z1: f64,
+        k: f64,
+        material: MaterialDef,
+    },
+    Disk {
+        center: Point3,
+        normal: Vec3,
+        radius: f64,
+        material: MaterialDef,
+    },
+    // Caps are included unless `capped` is false.
+    Cylinder {
+        center: Point3,
+        axis: Vec3,
+        radius: f64,
+        height: f64,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: MaterialDef,
+    },
+    // Tip at `apex`, opening along `axis`. The base is capped unless
+    // `capped` is false.
+    Cone {
+        apex: Point3,
+        axis: Vec3,
+        half_angle_degrees: f64,
+        height: f64,
+        #[serde(default = "default_capped")]
+        capped: bool,
+        material: MaterialDef,
+    },
+    // Ring around the y axis through `center`.
+    Torus {
+        center: Point3,
+        major_radius: f64,
+        minor_radius: f64,
+        material: MaterialDef,
+    },
+    // A Wavefront OBJ file, relative to the working directory.
+    Mesh {
+        path: PathBuf,
+        material: MaterialDef,
+    },
+}
+
+fn default_capped() -> bool {
+    true
+}
+
+impl SceneDescription {
+    pub fn build(&self) -> Result<LoadedScene, SceneError> {
+        let mut world = HittableList::default();
+        let mut lights = LightList::default();
+        for object in self.objects.iter() {
+            add_object(&mut world, &mut lights, object)?;
+        }
+        if let Some(sun) = self.background.sun() {
+            lights.add(Arc::new(sun));
+        }
+
+        Ok(LoadedScene {
+            world,
+            lights,
+            camera: self.camera.build(self.render.aspect_ratio()),
+            config: self.render,
+            background: self.background.clone(),
+        })
+    }
+}
+
+impl CameraDescription {
+    pub fn build(&self, aspect_ratio: f64) -> Camera {
+        Camera::new(
+            self.lookfrom,
+            self.lookat,
+            self.vup.unwrap_or(Vec3::new(0.0, 1.0, 0.0)),
+            self.vfov,
+            aspect_ratio,
+            self.aperture.unwrap_or(0.0),
+            self.focus_dist
+                .unwrap_or_else(|| self.lookfrom.distance_to(self.lookat)),
+            self.shutter,
+        )
+    }
+}
+
+fn add_object(
+    world: &mut HittableList,
+    lights: &mut LightList,
+    object: &ObjectDescription,
+) -> Result<(), SceneError> {
+    match object {
+        ObjectDescription::Sphere {
+            center,
+            radius,
+            material,
+        } => add_sampled(
+            world,
+            lights,
+            Sphere::new(*center, *radius, material.build()),
+            material,
+        ),
+        ObjectDescription::MovingSphere {
+            center0,
+            center1,
+            time0,
+            time1,
+            radius,
+            material,
+        } => world.add(MovingSphere::new(
+            *center0,
+            *center1,
+            *time0,
+            *time1,
+            *radius,
+            material.build(),
+        )),
+        ObjectDescription::Plane {
+            point,
+            normal,
+            material,
+        } => world.add(Plane::new(*point, *normal, material.build())),
+        ObjectDescription::Triangle {
+            v0,
+            v1,
+            v2,
+            material,
+        } => world.add(Triangle::new(*v0, *v1, *v2, material.build())),
+        ObjectDescription::Box { p0, p1, material } => {
+            world.add(AaBox::new(*p0, *p1, material.build()))
+        }
+        ObjectDescription::XyRect {
+            x0,
+            x1,
+            y0,
+            y1,
+            k,
+            material,
+        } => add_sampled(
+            world,
+            lights,
+            XyRect::new(*x0, *x1, *y0, *y1, *k, material.build()),
+            material,
+        ),
+        ObjectDescription::XzRect {
+            x0,
+            x1,
+            z0,
+            z1,
+            k,
+            material,
+        } => add_sampled(
+            world,
+            lights,
+            XzRect::new(*x0, *x1, *z0, *z1, *k, material.build()),
+            material,
+        ),
+        ObjectDescription::YzRect {
+            y0,
+            y1,
+            z0,
+            z1,
+            k,
+            material,
+        } => add_sampled(
+            world,
+            lights,
+            YzRect::new(*y0, *y1, *z0, *z1, *k, material.build()),
+            material,
+        ),
+        ObjectDescription::Disk {
+            center,
+            normal,
+            radius,
+            material,
+        } => add_sampled(
+            world,
+            lights,
+            Disk::new(*center, *normal, *radius, material.build()),
+            material,
+        ),
+        ObjectDescription::Cylinder {
+            center,
+            axis,
+            radius,
+            height,
+            capped,
+            material,
+        } => world.add(Cylinder::new(
+            *center,
+            *axis,
+            *radius,
+            *height,
+            *capped,
+            material.build(),
+        )),
+        ObjectDescription::Cone {
+            apex,
+            axis,
+            half_angle_degrees,
+            height,
+            capped,
+            material,
+        } => world.add(Cone::new(
+            *apex,
+            *axis,
+            half_angle_degrees.to_radians(),
+            *height,
+            *capped,
+            material.build(),
+        )),
+        ObjectDescription::Torus {
+            center,
+            major_radius,
+            minor_radius,
+            material,
+        } => world.add(Torus::new(
+            *center,
+            *major_radius,
+            *minor_radius,
+            material.build(),
+        )),
+        ObjectDescription::Mesh { path, material } => {
+            let mesh = TriangleMesh::from_obj(path, material.build()).map_err(|error| {
+                SceneError::Mesh {
+                    path: path.clone(),
+                    error,
+                }
+            })?;
+            world.add(mesh);
+        }
+    }
+
+    Ok(())
+}
+
+// Adds `object` to the world, and to `lights` too if its material emits
+// light. Only for shapes that can sample directions towards themselves.
+fn add_sampled<H: Hittable + 'static>(
+    world: &mut HittableList,
+    lights: &mut LightList,
+    object: H,
+    material: &MaterialDef,
+) {
+    if material.emits() {
+        let object = Arc::new(object);
+        lights.add(object.clone());
+        world.add(object);
+    } else {
+        world.add(object);
+    }
+}
+
+// Loads a JSON scene file. See `toml_loader` for the TOML equivalent.
+pub fn load_scene(path: &Path) -> Result<LoadedScene, SceneError> {
+    let source = fs::read_to_string(path)?;
+    let description: SceneDescription =
+        serde_json::from_str(&source).map_err(|err| SceneError::Parse(err.to_string()))?;
+
+    description.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, Color};
+
+    const GREY: &str =
+        r#"{ "type": "lambertian", "albedo": { "type": "solid", "color": [0.5, 0.5, 0.5] } }"#;
+
+    // A scene of `objects`, in front of `background` if given.
+    fn scene(background: Option<&str>, objects: &str) -> LoadedScene {
+        let background = background
+            .map(|background| format!(r#""background": {},"#, background))
+            .unwrap_or_default();
+        let source = format!(
+            r#"{{
+                "camera": {{ "lookfrom": [0, 1, -5], "lookat": [0, 1, 0], "vfov": 40 }},
+                "render": {{ "width": 32, "height": 24, "samples": 4, "max_depth": 8 }},
+                {}
+                "objects": [{}]
+            }}"#,
+            background, objects
+        );
+        let description: SceneDescription = serde_json::from_str(&source).unwrap();
+        description.build().unwrap()
+    }
+
+    #[test]
+    fn emitting_spheres_rects_and_disks_are_lights() {
+        let light =
+            r#"{ "type": "diffuse_light", "emit": { "type": "solid", "color": [4, 4, 4] } }"#;
+        let loaded = scene(
+            Some(r#"{ "type": "constant", "color": [0, 0, 0] }"#),
+            &format!(
+                r#"{{ "type": "sphere", "center": [0, 3, 0], "radius": 0.5, "material": {light} }},
+                   {{ "type": "xz_rect", "x0": -1, "x1": 1, "z0": -1, "z1": 1, "k": 4, "material": {light} }},
+                   {{ "type": "disk", "center": [2, 3, 0], "normal": [0, -1, 0], "radius": 0.5, "material": {light} }},
+                   {{ "type": "box", "p0": [-3, 0, -1], "p1": [-2, 1, 0], "material": {light} }},
+                   {{ "type": "sphere", "center": [0, 1, 0], "radius": 1, "material": {GREY} }}"#
+            ),
+        );
+
+        assert_eq!(loaded.world.objects.len(), 5);
+        // Boxes can't be sampled, so they are only in the world.
+        assert_eq!(loaded.lights.lights.len(), 3);
+    }
+
+    #[test]
+    fn the_default_sky_brings_its_sun() {
+        let object = format!(
+            r#"{{ "type": "sphere", "center": [0, 1, 0], "radius": 1, "material": {GREY} }}"#
+        );
+        assert_eq!(scene(None, &object).lights.lights.len(), 1);
+
+        let sunless = r#"{ "type": "rayleigh", "sun_direction": [0, 1, 0], "sun_intensity": 20, "sun_irradiance": 0 }"#;
+        assert!(scene(Some(sunless), &object).lights.is_empty());
+        let gradient = r#"{ "type": "gradient", "top": [0.5, 0.7, 1], "bottom": [1, 1, 1] }"#;
+        assert!(scene(Some(gradient), &object).lights.is_empty());
+    }
+
+    #[test]
+    fn constant_backgrounds_light_scenes_evenly() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenes/ambient.json");
+        let loaded = load_scene(&path).unwrap();
+
+        assert!(matches!(
+            loaded.background,
+            BackgroundDef::Constant { color } if color.approx_eq(Color::from(0.8), 1e-12)
+        ));
+        // Ambient light has no sun to sample.
+        assert!(loaded.lights.is_empty());
+        let background = loaded.background.build().unwrap();
+        for direction in [
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(1.0, -1.0, 0.5),
+            Vec3::new(0.0, 0.0, -1.0),
+        ] {
+            assert_vec3_approx_eq!(background.sample(direction), Color::from(0.8), 1e-12);
+        }
+    }
+
+    #[test]
+    fn hdr_backgrounds_are_loaded_from_their_path() {
+        // A 4 x 2 image of a single color, stored flat.
+        let path = std::env::temp_dir().join("tracy-environment.hdr");
+        let mut data = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 4\n".to_vec();
+        data.extend([128, 64, 32, 129].repeat(8));
+        fs::write(&path, data).unwrap();
+
+        let object = format!(
+            r#"{{ "type": "sphere", "center": [0, 1, 0], "radius": 1, "material": {GREY} }}"#
+        );
+        let hdr = format!(r#"{{ "type": "hdr", "path": {:?} }}"#, path);
+        let loaded = scene(Some(&hdr), &object);
+        assert!(loaded.lights.is_empty());
+        let background = loaded.background.build().unwrap();
+        for direction in [Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, -1.0, 0.5)] {
+            assert_vec3_approx_eq!(
+                background.sample(direction),
+                Color::new(1.0, 0.5, 0.25),
+                1e-12
+            );
+        }
+
+        let missing = r#"{ "type": "hdr", "path": "no/such/environment.hdr" }"#;
+        assert!(scene(Some(missing), &object).background.build().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+use std::{fs, path::Path};
+
+use super::{LoadedScene, SceneDescription, SceneError};
+
+// Loads a TOML scene file. It describes the same SceneDescription as the
+// JSON files do, with objects given as an array of tables:
+//
+//     [[objects]]
+//     type = "sphere"
+//     center = [0.0, 0.0, -1.0]
+//     radius = 0.5
+//     material = { type = "lambertian", albedo = { type = "solid", color = [0.1, 0.2, 0.5] } }
+pub fn load_toml(path: &Path) -> Result<LoadedScene, SceneError> {
+    let source = fs::read_to_string(path)?;
+    let description: SceneDescription =
+        toml::from_str(&source).map_err(|err| SceneError::Parse(err.to_string()))?;
+
+    description.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        config::RenderConfig,
+        material::def::MaterialDef,
+        random_float_between, rng,
+        scene::{CameraDescription, ObjectDescription},
+        texture::def::TextureDef,
+        Color, Vec3,
+    };
+
+    // A scene of random spheres and boxes seen by a randomly placed camera.
+    fn random_description() -> SceneDescription {
+        let solid = || TextureDef::Solid {
+            color: Color::random(),
+        };
+        let objects = (0..8)
+            .map(|i| match i % 3 {
+                0 => ObjectDescription::Sphere {
+                    center: Vec3::random_between(-100.0, 100.0),
+                    radius: random_float_between(1e-3, 10.0),
+                    material: MaterialDef::Lambertian { albedo: solid() },
+                },
+                1 => ObjectDescription::Sphere {
+                    center: Vec3::random_between(-100.0, 100.0),
+                    radius: random_float_between(1e-3, 10.0),
+                    material: MaterialDef::Metal {
+                        albedo: solid(),
+                        fuzz: random_float_between(0.0, 1.0),
+                    },
+                },
+                _ => ObjectDescription::Box {
+                    p0: Vec3::random_between(-100.0, 0.0),
+                    p1: Vec3::random_between(0.0, 100.0),
+                    material: MaterialDef::Dielectric {
+                        index_of_refraction: random_float_between(1.0, 2.5),
+                        absorption: Color::random(),
+                        cauchy_b: random_float_between(0.0, 0.01),
+                    },
+                },
+            })
+            .collect();
+
+        SceneDescription {
+            camera: CameraDescription {
+                lookfrom: Vec3::random_between(-10.0, 10.0),
+                lookat: Vec3::random_between(-10.0, 10.0),
+                vup: Some(Vec3::random_between(-1.0, 1.0)),
+                vfov: random_float_between(10.0, 90.0),
+                aperture: Some(random_float_between(0.0, 1.0)),
+                focus_dist: None,
+                shutter: Some((0.0, random_float_between(0.0, 1.0))),
+            },
+            render: RenderConfig::default(),
+            background: Default::default(),
+            objects,
+        }
+    }
+
+    #[test]
+    fn round_tripping_through_toml_keeps_every_number() {
+        rng::seed_thread(5);
+        for _ in 0..20 {
+            let description = random_description();
+            let source = toml::to_string(&description).unwrap();
+            let parsed: SceneDescription = toml::from_str(&source).unwrap();
+
+            // serde_json keeps f64s exactly, so equal values mean no number
+            // lost precision on the way.
+            assert_eq!(
+                serde_json::to_value(&parsed).unwrap(),
+                serde_json::to_value(&description).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn the_example_scene_loads() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenes/test_scene.toml");
+        let loaded = load_toml(&path).unwrap();
+
+        assert!(!loaded.world.objects.is_empty());
+        loaded.config.validate().unwrap();
+    }
+}
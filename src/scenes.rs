@@ -0,0 +1,1098 @@
+// Scenes built in code.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use tracy::{
+    background::rayleigh::RayleighSky,
+    camera::Camera,
+    hittable::{
+        aabox::AaBox,
+        heightmap::Heightmap,
+        light_list::LightList,
+        medium::{god_ray::GodRayMedium, ConstantMedium},
+        moving_sphere::MovingSphere,
+        plane::Plane,
+        rect::{XyRect, XzRect, YzRect},
+        sdf::{Mandelbulb, SdfMarcher, SdfSmoothUnion, SdfSphere},
+        sphere::Sphere,
+        sun::SunLight,
+        transform::{RotateY, Translate},
+        triangle::Triangle,
+        HittableList,
+    },
+    material::{
+        dielectric::Dielectric,
+        diffuse_light::DiffuseLight,
+        ggx::GGX,
+        isotropic::Isotropic,
+        lambertian::Lambertian,
+        metal::Metal,
+        normal_map::{encode_normal, NormalMappedMaterial},
+        thin_film::ThinFilm,
+        velvet::Velvet,
+    },
+    random_float, random_float_between,
+    texture::{
+        checker::CheckerTexture, image::ImageTexture, marble::MarbleTexture,
+        orbit_trap::OrbitTrapTexture, texture2d::Texture2D, wood::WoodTexture,
+    },
+    Color, Point3, Vec3,
+};
+
+pub fn sebi_scene() -> HittableList {
+    let mut world = HittableList::default();
+    let m1 = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        m1,
+    ));
+
+    let sphere_radius = 0.3;
+    let spacing = 0.7;
+    let primary_mat = Metal::new(Color::new(0.8, 0.2, 0.2), 0.1);
+    let secondary_mat = Dielectric::new(1.5);
+    
+    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0 + spacing * 1.3, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0, sphere_radius + spacing * 0.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 0.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 0.7, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(1.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2 + spacing, sphere_radius + spacing * 1.5, 0.0), sphere_radius, secondary_mat));
+    world.add(Sphere::new(Point3::new(7.8, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2 + spacing * 1.5, sphere_radius + spacing * 2.5, 0.0), sphere_radius, secondary_mat));
+    world.add(Sphere::new(Point3::new(7.8 + spacing, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2 + spacing * 0.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, secondary_mat));
+    world.add(Sphere::new(Point3::new(10.6 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2, sphere_radius + spacing * 2.5, 0.0), sphere_radius, secondary_mat));
+    world.add(Sphere::new(Point3::new(6.5, 0.7, -4.5), 0.7, Metal::new(Color::new(0.2, 0.8, 0.3), 0.1)));
+    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(1.5 + spacing * 0.5, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(0.5, 1.0, -4.0), 1.0, Dielectric::new(1.5)));
+    world.add(Sphere::new(Point3::new(10.6, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0 + spacing * 0.7, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(2.0, 0.55, -8.0), 0.55, Metal::new(Color::new(0.95, 0.6, 0.2), 0.15)));
+    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(8.5, 0.8, -3.5), 0.8, Metal::new(Color::new(0.95, 0.85, 0.3), 0.0)));
+    world.add(Sphere::new(Point3::new(1.5 + spacing, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.1, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0, 0.6, -7.0), 0.6, Lambertian::new(Color::new(0.8, 0.3, 0.7))));
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 1.4, sphere_radius + spacing * 0.5, 0.0), sphere_radius, primary_mat.clone()));
+    
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.8, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.8, sphere_radius + spacing * 0.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 1.5, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0, 0.4, -6.5), 0.4, Lambertian::new(Color::new(0.9, 0.4, 0.8))));
+    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 2.8, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(1.5 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 0.5, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2 + spacing * 1.5, sphere_radius + spacing * 2.0, 0.0), sphere_radius, secondary_mat));
+    world.add(Sphere::new(Point3::new(-7.0, 0.6, -4.0), 0.6, Lambertian::new(Color::new(0.2, 0.4, 0.8))));
+    world.add(Sphere::new(Point3::new(-2.0, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(3.0, 0.5, -6.0), 0.5, Metal::new(Color::new(0.4, 0.9, 0.4), 0.0)));
+    world.add(Sphere::new(Point3::new(-6.0 + spacing * 1.4, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-2.0 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2 + spacing, sphere_radius + spacing * 3.0, 0.0), sphere_radius, secondary_mat));
+    world.add(Sphere::new(Point3::new(-2.0 + spacing, sphere_radius + spacing * 1.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(13.2 + spacing * 0.75, sphere_radius + spacing * 0.3, 0.0), sphere_radius * 0.8, secondary_mat));
+    world.add(Sphere::new(Point3::new(-3.5, 0.7, -7.5), 0.7, Metal::new(Color::new(0.9, 0.5, 0.1), 0.2)));
+    world.add(Sphere::new(Point3::new(10.6 + spacing, sphere_radius, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-9.0, 0.5, -5.0), 0.5, Dielectric::new(1.5)));
+    world.add(Sphere::new(Point3::new(5.0 + spacing * 2.0, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-4.0, 0.8, -5.0), 0.8, Lambertian::new(Color::new(0.3, 0.6, 0.9))));
+    world.add(Sphere::new(Point3::new(1.5 + spacing * 1.5, sphere_radius + spacing * 2.5, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-1.0, 0.65, -5.5), 0.65, Metal::new(Color::new(0.9, 0.8, 0.2), 0.05)));
+    world.add(Sphere::new(Point3::new(1.5 + spacing, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(-2.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(1.5 + spacing, sphere_radius + spacing * 2.0, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(5.0, sphere_radius + spacing, 0.0), sphere_radius, primary_mat.clone()));
+    world.add(Sphere::new(Point3::new(7.8 + spacing * 1.5, sphere_radius + spacing * 3.0, 0.0), sphere_radius, primary_mat.clone()));
+    world
+}
+
+#[allow(dead_code)]
+pub fn test_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let material_ground = Lambertian::new(Color::new(0.8, 0.8, 0.0));
+    let material_center = Lambertian::new(Color::new(0.1, 0.2, 0.5));
+    let material_left = Dielectric::new(1.5);
+    let material_right = Metal::new(Color::new(0.8, 0.6, 0.2), 1.0);
+
+    world.add(Sphere::new(
+        Point3::new(0.0, -100.5, -1.0),
+        100.0,
+        material_ground,
+    ));
+    world.add(Sphere::new(
+        Point3::new(0.0, 0.0, -1.0),
+        0.5,
+        material_center,
+    ));
+    world.add(Sphere::new(
+        Point3::new(-1.0, 0.0, -1.0),
+        0.5,
+        material_left,
+    ));
+    world.add(Sphere::new(
+        Point3::new(-1.0, 0.0, -1.0),
+        -0.45,
+        material_left,
+    ));
+    world.add(Sphere::new(
+        Point3::new(1.0, 0.0, -1.0),
+        0.5,
+        material_right,
+    ));
+
+    world
+}
+
+// Empty Cornell box built from thin boxes: red and green side walls, white
+// floor, ceiling and back wall, and a light panel just below the ceiling.
+// The front is left open. Look at it from (278, 278, -800) towards
+// (278, 278, 0) and render with a black background.
+pub fn cornell_room() -> HittableList {
+    let mut world = HittableList::default();
+
+    let red = Lambertian::new(Color::new(0.65, 0.05, 0.05));
+    let white = Lambertian::new(Color::from(0.73));
+    let green = Lambertian::new(Color::new(0.12, 0.45, 0.15));
+    let light = DiffuseLight::new(Color::from(15.0));
+
+    let size = 555.0;
+    let wall = 1.0;
+
+    // Left, right, floor, ceiling and back wall.
+    world.add(AaBox::new(
+        Point3::new(size, 0.0, 0.0),
+        Point3::new(size + wall, size, size),
+        green.clone(),
+    ));
+    world.add(AaBox::new(
+        Point3::new(-wall, 0.0, 0.0),
+        Point3::new(0.0, size, size),
+        red.clone(),
+    ));
+    world.add(AaBox::new(
+        Point3::new(0.0, -wall, 0.0),
+        Point3::new(size, 0.0, size),
+        white.clone(),
+    ));
+    world.add(AaBox::new(
+        Point3::new(0.0, size, 0.0),
+        Point3::new(size, size + wall, size),
+        white.clone(),
+    ));
+    world.add(AaBox::new(
+        Point3::new(213.0, size - wall, 227.0),
+        Point3::new(343.0, size, 332.0),
+        light,
+    ));
+    world.add(AaBox::new(
+        Point3::new(0.0, 0.0, size),
+        Point3::new(size, size, size + wall),
+        white,
+    ));
+
+    world
+}
+
+// The light panel of cornell_room as a rectangle, for sampling it directly.
+// The panel's other faces are too thin to matter.
+pub fn cornell_lights() -> LightList {
+    let mut lights = LightList::default();
+    let light = DiffuseLight::new(Color::from(15.0));
+    lights.add(Arc::new(XzRect::new(213.0, 343.0, 227.0, 332.0, 554.0, light)));
+
+    lights
+}
+
+// The Cornell box with its tall and short box.
+pub fn cornell_box_scene() -> HittableList {
+    let mut world = cornell_room();
+
+    let white = Lambertian::new(Color::from(0.73));
+    let short_box = AaBox::new(Point3::from(0.0), Point3::from(165.0), white.clone());
+    world.add(Translate::new(
+        RotateY::new(short_box, -18.0),
+        Vec3::new(130.0, 0.0, 65.0),
+    ));
+    let tall_box = AaBox::new(Point3::from(0.0), Point3::new(165.0, 330.0, 165.0), white);
+    world.add(Translate::new(
+        RotateY::new(tall_box, 15.0),
+        Vec3::new(265.0, 0.0, 295.0),
+    ));
+
+    world
+}
+
+// The standard Cornell box preset, seen through its open front with a
+// 40 degree field of view. Render it with a black background and
+// cornell_lights.
+pub fn cornell_box(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(278.0, 278.0, -800.0),
+        Point3::new(278.0, 278.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        Some((0.0, 1.0)),
+    );
+
+    (cornell_box_scene(), camera)
+}
+
+// The Cornell box with its two boxes replaced by volumes of dark and light
+// smoke.
+pub fn cornell_smoke_scene() -> HittableList {
+    let mut world = cornell_room();
+
+    let white = Lambertian::new(Color::from(0.73));
+    world.add(ConstantMedium::new(
+        AaBox::new(
+            Point3::new(130.0, 0.0, 65.0),
+            Point3::new(295.0, 165.0, 230.0),
+            white.clone(),
+        ),
+        0.01,
+        Arc::new(Isotropic::new(Color::from(1.0))),
+    ));
+    world.add(ConstantMedium::new(
+        AaBox::new(
+            Point3::new(265.0, 0.0, 295.0),
+            Point3::new(430.0, 330.0, 460.0),
+            white,
+        ),
+        0.01,
+        Arc::new(Isotropic::new(Color::from(0.0))),
+    ));
+
+    world
+}
+
+// The smoky Cornell box, seen like the cornell_box preset.
+pub fn cornell_smoke(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(278.0, 278.0, -800.0),
+        Point3::new(278.0, 278.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (cornell_smoke_scene(), camera)
+}
+
+// A globe textured with the equirectangular Earth map in
+// assets/earthmap.png, a simplified map of the continents drawn for this
+// scene. The map's prime meridian faces +X and north +Y.
+pub fn earth_scene() -> Result<HittableList, String> {
+    let mut world = HittableList::default();
+
+    let path = Path::new("assets/earthmap.png");
+    let earth_texture = ImageTexture::new(path)
+        .map_err(|err| format!("Unable to load {}: {}", path.display(), err))?;
+    world.add(Sphere::new(
+        Point3::from(0.0),
+        2.0,
+        Lambertian::from_texture(Arc::new(earth_texture)),
+    ));
+
+    Ok(world)
+}
+
+// The globe against a black background, lit by a small, bright sun off to
+// the upper right. The camera looks at the Americas.
+pub fn earth(aspect_ratio: f64) -> Result<(HittableList, Camera), String> {
+    let mut world = earth_scene()?;
+    world.add(earth_sun());
+
+    let camera = Camera::new(
+        Point3::new(0.0, 0.0, 12.0),
+        Point3::from(0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        20.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        Some((0.0, 1.0)),
+    );
+
+    Ok((world, camera))
+}
+
+// The sun of the earth preset, for sampling it directly.
+pub fn earth_lights() -> LightList {
+    let mut lights = LightList::default();
+    lights.add(Arc::new(earth_sun()));
+
+    lights
+}
+
+fn earth_sun() -> Sphere<DiffuseLight> {
+    Sphere::new(
+        Point3::new(30.0, 15.0, 30.0),
+        3.0,
+        DiffuseLight::new(Color::new(120.0, 115.0, 105.0)),
+    )
+}
+
+// A white room built from rectangles, lit only by a glowing XzRect in the
+// ceiling. Look at it from (5, 5, -12) towards (5, 4, 5) and render with a
+// black background.
+pub fn rect_light_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let white = Lambertian::new(Color::from(0.73));
+    let light = DiffuseLight::new(Color::from(7.0));
+
+    world.add(YzRect::new(0.0, 10.0, 0.0, 10.0, 0.0, white.clone()));
+    world.add(YzRect::new(0.0, 10.0, 0.0, 10.0, 10.0, white.clone()));
+    world.add(XzRect::new(0.0, 10.0, 0.0, 10.0, 0.0, white.clone()));
+    world.add(XzRect::new(0.0, 10.0, 0.0, 10.0, 10.0, white.clone()));
+    world.add(XyRect::new(0.0, 10.0, 0.0, 10.0, 10.0, white.clone()));
+    world.add(XzRect::new(3.5, 6.5, 3.5, 6.5, 9.99, light));
+
+    world.add(Sphere::new(Point3::new(5.0, 2.0, 5.0), 2.0, white));
+
+    world
+}
+
+// The ceiling light of rect_light_scene, for sampling it directly.
+pub fn rect_light_lights() -> LightList {
+    let mut lights = LightList::default();
+    let light = DiffuseLight::new(Color::from(7.0));
+    lights.add(Arc::new(XzRect::new(3.5, 6.5, 3.5, 6.5, 9.99, light)));
+
+    lights
+}
+
+// The lit room from in front of its open side.
+pub fn rect_light(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(5.0, 5.0, -12.0),
+        Point3::new(5.0, 4.0, 5.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (rect_light_scene(), camera)
+}
+
+// A foggy room under a slatted roof, with a light above the roof. The fog
+// scatters the light coming through the gaps into shafts reaching down to
+// the floor. Look at it from (5, 3, -14) towards (5, 5, 5) and render with
+// a black background and god_rays_lights.
+pub fn god_rays_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let white = Lambertian::new(Color::from(0.73));
+    world.add(XzRect::new(0.0, 10.0, 0.0, 10.0, 0.0, white.clone()));
+    world.add(YzRect::new(0.0, 10.0, 0.0, 10.0, 0.0, white.clone()));
+    world.add(YzRect::new(0.0, 10.0, 0.0, 10.0, 10.0, white.clone()));
+    world.add(XyRect::new(0.0, 10.0, 0.0, 10.0, 10.0, white.clone()));
+
+    // Slats 0.8 wide with gaps of 0.4 between them.
+    for i in 0..9 {
+        let x = 0.1 + 1.2 * i as f64;
+        world.add(AaBox::new(
+            Point3::new(x, 10.0, 0.0),
+            Point3::new(x + 0.8, 10.2, 10.0),
+            white.clone(),
+        ));
+    }
+    world.add(XzRect::new(4.0, 6.0, 4.0, 6.0, 16.0, god_rays_light()));
+
+    let fog = GodRayMedium::new(Vec3::new(0.0, 1.0, 0.0), 1.0, 0.01, 0.04);
+    world.add(fog.fill(AaBox::new(
+        Point3::new(0.01, 0.01, 0.01),
+        Point3::new(9.99, 9.99, 9.99),
+        white,
+    )));
+
+    world
+}
+
+fn god_rays_light() -> DiffuseLight {
+    DiffuseLight::new(Color::from(60.0))
+}
+
+pub fn god_rays_lights() -> LightList {
+    let mut lights = LightList::default();
+    lights.add(Arc::new(XzRect::new(
+        4.0,
+        6.0,
+        4.0,
+        6.0,
+        16.0,
+        god_rays_light(),
+    )));
+
+    lights
+}
+
+// The god rays room seen from outside its open front, looking up at the
+// shafts.
+pub fn god_rays(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(5.0, 3.0, -14.0),
+        Point3::new(5.0, 5.0, 5.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (god_rays_scene(), camera)
+}
+
+// A flat square, lit from the side, that looks corrugated: its normal map
+// tilts the normals back and forth along x with a sine wave, and the two
+// halves of each wave face towards and away from the light. Look at it from
+// (0, 3, 4) towards (0, 0, 0) and render with a black background and
+// normal_map_lights.
+pub fn normal_map_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    // Eight waves across the map, tilting the normal by up to 45 degrees.
+    let size = 256;
+    let mut map = Texture2D::new(size, size);
+    for y in 0..size {
+        for x in 0..size {
+            let phase = 8.0 * 2.0 * std::f64::consts::PI * x as f64 / size as f64;
+            map.set(x, y, encode_normal(Vec3::new(phase.cos(), 0.0, 1.0)));
+        }
+    }
+    world.add(XzRect::new(
+        -2.0,
+        2.0,
+        -2.0,
+        2.0,
+        0.0,
+        NormalMappedMaterial::new(Lambertian::new(Color::from(0.8)), Arc::new(map)),
+    ));
+    world.add(YzRect::new(0.5, 1.5, -1.0, 1.0, -4.0, normal_map_light()));
+
+    world
+}
+
+fn normal_map_light() -> DiffuseLight {
+    DiffuseLight::new(Color::from(15.0))
+}
+
+pub fn normal_map_lights() -> LightList {
+    let mut lights = LightList::default();
+    lights.add(Arc::new(YzRect::new(
+        0.5,
+        1.5,
+        -1.0,
+        1.0,
+        -4.0,
+        normal_map_light(),
+    )));
+
+    lights
+}
+
+// The corrugated square seen from above its far edge.
+pub fn normal_map(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 3.0, 4.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (normal_map_scene(), camera)
+}
+
+// Two gold GGX spheres side by side: nearly mirror-like on the left,
+// brushed on the right.
+pub fn ggx_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let checker = CheckerTexture::from_colors(Color::from(0.2), Color::from(0.9), 2.0);
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::from_texture(Arc::new(checker)),
+    ));
+
+    let gold = Color::new(1.0, 0.71, 0.29);
+    let white = Color::from(1.0);
+    world.add(Sphere::new(
+        Point3::new(-1.1, 1.0, 0.0),
+        1.0,
+        GGX::new(white, 0.05, gold),
+    ));
+    world.add(Sphere::new(
+        Point3::new(1.1, 1.0, 0.0),
+        1.0,
+        GGX::new(white, 0.5, gold),
+    ));
+
+    world
+}
+
+// The two spheres from the front, reflecting the sky and the floor.
+pub fn ggx(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 1.5, 6.0),
+        Point3::new(0.0, 0.9, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        35.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (ggx_scene(), camera)
+}
+
+// Two strips of cloth of the same red side by side on a grey floor:
+// velvet on the left, Lambertian on the right. Look at them from low
+// above the floor, e.g. from (0, 0.6, -6) towards (0, 0, 0), to see the
+// velvet brighten towards the far, grazing end.
+pub fn velvet_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        Lambertian::new(Color::from(0.5)),
+    ));
+
+    let red = Color::new(0.6, 0.05, 0.08);
+    world.add(XzRect::new(-2.1, -0.1, -2.0, 4.0, 0.01, Velvet::new(red, 0.8)));
+    world.add(XzRect::new(0.1, 2.1, -2.0, 4.0, 0.01, Lambertian::new(red)));
+
+    world
+}
+
+// The two strips seen from low above the floor.
+pub fn velvet(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 0.6, -6.0),
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0,
+        aspect_ratio,
+        0.0,
+        6.0,
+        None,
+    );
+
+    (velvet_scene(), camera)
+}
+
+// A soap bubble: a 400 nm water film with air on both sides, floating in
+// front of a dark wall so the reflected colors stand out. The bands shift
+// from the middle of the bubble towards its rim as the angle changes.
+pub fn thin_film_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let ground = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground));
+    world.add(XyRect::new(
+        -10.0,
+        10.0,
+        0.0,
+        10.0,
+        3.0,
+        Lambertian::new(Color::from(0.05)),
+    ));
+    world.add(Sphere::new(
+        Point3::new(0.0, 1.2, 0.0),
+        1.0,
+        ThinFilm::new(1.0, 1.33, 400.0),
+    ));
+
+    world
+}
+
+// The bubble from in front, with the wall behind it.
+pub fn thin_film(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 1.5, -5.0),
+        Point3::new(0.0, 1.2, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        35.0,
+        aspect_ratio,
+        0.0,
+        5.0,
+        None,
+    );
+
+    (thin_film_scene(), camera)
+}
+
+// A glass prism in the dark, with a thin white light standing off to the
+// side behind it. Looking through the prism from (0, 1, 8) towards
+// (0, 1, 0) with a 20° field of view, the light shows up spread into a
+// rainbow. The prism's dispersion is about three times that of real glass,
+// so the spectrum is wide enough to see. Render it with a black background.
+pub fn prism_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let glass = Dielectric::with_dispersion(1.5, 12000.0);
+    // Equilateral cross-section, with the face towards the camera turned
+    // to the angle of minimum deviation.
+    let corners: Vec<Point3> = (0..3)
+        .map(|k| {
+            let angle = (48.6 - 60.0 + 120.0 * k as f64).to_radians();
+            Point3::new(angle.sin(), 0.0, angle.cos())
+        })
+        .collect();
+    let up = Vec3::new(0.0, 2.0, 0.0);
+    let center = up / 2.0;
+
+    let mut add_face = |a: Point3, b: Point3, c: Point3| {
+        // Wind every triangle to face out of the prism.
+        if (b - a).cross(c - a).dot(a - center) < 0.0 {
+            world.add(Triangle::new(a, c, b, glass));
+        } else {
+            world.add(Triangle::new(a, b, c, glass));
+        }
+    };
+    for k in 0..3 {
+        let (p, q) = (corners[k], corners[(k + 1) % 3]);
+        add_face(p, q, q + up);
+        add_face(p, q + up, p + up);
+    }
+    add_face(corners[0], corners[1], corners[2]);
+    add_face(corners[0] + up, corners[1] + up, corners[2] + up);
+
+    world.add(AaBox::new(
+        Point3::new(-4.42, 0.0, -5.22),
+        Point3::new(-4.32, 2.0, -5.12),
+        DiffuseLight::new(Color::from(20.0)),
+    ));
+
+    world
+}
+
+// The prism seen from the front, with the light's rainbow in the glass.
+pub fn prism(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 1.0, 8.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        20.0,
+        aspect_ratio,
+        0.0,
+        8.0,
+        None,
+    );
+
+    (prism_scene(), camera)
+}
+
+// An 8 x 8 pool with a tiled floor under rippled water, lit by a small
+// light high above it. The water focuses the light into a network of
+// bright lines on the floor, a caustic path tracing takes ages to resolve
+// and photon mapping (--photons) shows right away. Render it with a black
+// background and pool_lights.
+pub fn pool_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    // Tiles one unit wide. The floor is at a height where the sine of the
+    // checker texture along y isn't 0.
+    let tiles = CheckerTexture::from_colors(
+        Color::new(0.2, 0.45, 0.7),
+        Color::from(0.85),
+        std::f64::consts::PI,
+    );
+    world.add(XzRect::new(
+        0.0,
+        8.0,
+        0.0,
+        8.0,
+        -1.5,
+        Lambertian::from_texture(Arc::new(tiles)),
+    ));
+    let wall = Lambertian::new(Color::from(0.85));
+    world.add(YzRect::new(-1.5, 0.0, 0.0, 8.0, 0.0, wall.clone()));
+    world.add(YzRect::new(-1.5, 0.0, 0.0, 8.0, 8.0, wall.clone()));
+    world.add(XyRect::new(0.0, 8.0, -1.5, 0.0, 0.0, wall.clone()));
+    world.add(XyRect::new(0.0, 8.0, -1.5, 0.0, 8.0, wall));
+
+    // A few overlapping ripples, curved enough to focus the light about as
+    // deep as the pool, and sampled finely enough that the facets of the
+    // surface don't show in the caustic.
+    let samples = 257;
+    let spacing = 8.0 / (samples - 1) as f64;
+    let heights = (0..samples * samples)
+        .map(|index| {
+            let x = (index % samples) as f64 * spacing;
+            let z = (index / samples) as f64 * spacing;
+            0.03 * f64::sin(6.0 * x + 2.0 * z)
+                + 0.025 * f64::sin(-3.0 * x + 6.5 * z + 1.0)
+                + 0.02 * f64::sin(5.0 * x - 4.5 * z + 2.0)
+        })
+        .collect();
+    let water = Heightmap::new(
+        samples,
+        samples,
+        heights,
+        spacing,
+        1.0,
+        Dielectric::new(1.33),
+    );
+    world.add(Translate::new(water, Vec3::new(0.0, -0.3, 0.0)));
+
+    world.add(XzRect::new(3.5, 4.5, 3.5, 4.5, 10.0, pool_light()));
+
+    world
+}
+
+fn pool_light() -> DiffuseLight {
+    DiffuseLight::new(Color::from(80.0))
+}
+
+pub fn pool_lights() -> LightList {
+    let mut lights = LightList::default();
+    lights.add(Arc::new(XzRect::new(3.5, 4.5, 3.5, 4.5, 10.0, pool_light())));
+
+    lights
+}
+
+// The pool seen from above one of its sides.
+pub fn pool(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(4.0, 6.0, -4.0),
+        Point3::new(4.0, -1.5, 4.5),
+        Vec3::new(0.0, 1.0, 0.0),
+        45.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (pool_scene(), camera)
+}
+
+// Three spheres, diffuse, glass and metal, on a grey ground in the
+// afternoon sun. Render it with sun_sky and sun_lights.
+pub fn sun_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    world.add(Plane::new(
+        Point3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        Lambertian::new(Color::from(0.5)),
+    ));
+    world.add(Sphere::new(
+        Point3::new(-2.2, 1.0, 0.0),
+        1.0,
+        Lambertian::new(Color::new(0.7, 0.2, 0.1)),
+    ));
+    world.add(Sphere::new(
+        Point3::new(0.0, 1.0, 0.0),
+        1.0,
+        Dielectric::new(1.5),
+    ));
+    world.add(Sphere::new(
+        Point3::new(2.2, 1.0, 0.0),
+        1.0,
+        Metal::new(Color::new(0.8, 0.8, 0.9), 0.05),
+    ));
+
+    world
+}
+
+pub fn sun_sky() -> RayleighSky {
+    RayleighSky::from_angles(35.0, 120.0, 20.0)
+}
+
+// The sun of sun_sky as seen from the ground, `angular_radius_radians`
+// across: about 0.0047 for the real sun's sharp shadows, 0.1 or more for
+// soft ones.
+pub fn sun_lights(angular_radius_radians: f64) -> LightList {
+    let mut lights = LightList::default();
+    lights.add(Arc::new(SunLight::new(
+        sun_sky().sun_direction,
+        angular_radius_radians,
+        Color::new(3.0, 2.8, 2.5),
+    )));
+
+    lights
+}
+
+// The spheres of sun_scene from a little above the ground.
+pub fn sun(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 2.5, 9.0),
+        Point3::new(0.0, 0.8, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        35.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (sun_scene(), camera)
+}
+
+// Four spheres melted into one blob, ray marched as a distance field. Look
+// at it from (0, 2, 6) towards (0, 1, 0).
+pub fn sdf_blob_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let ground = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground));
+
+    let sphere = |x, y, z, radius| SdfSphere::new(Point3::new(x, y, z), radius);
+    let blob = SdfSmoothUnion::new(
+        SdfSmoothUnion::new(sphere(-0.7, 0.8, 0.0, 0.6), sphere(0.7, 0.8, 0.0, 0.6), 0.5),
+        SdfSmoothUnion::new(sphere(0.0, 1.5, 0.2, 0.5), sphere(0.0, 0.9, 0.8, 0.4), 0.5),
+        0.5,
+    );
+    world.add(SdfMarcher::new(
+        blob,
+        1.0,
+        Lambertian::new(Color::new(0.8, 0.3, 0.5)),
+    ));
+
+    world
+}
+
+// The blob from the front and a little above.
+pub fn sdf_blob(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 2.0, 6.0),
+        Point3::new(0.0, 1.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0,
+        aspect_ratio,
+        0.0,
+        6.0,
+        None,
+    );
+
+    (sdf_blob_scene(), camera)
+}
+
+// A power 8 Mandelbulb raised above a grey floor, colored by its orbit trap.
+// Look at it from (0, 1.6, 3.2) towards (0, 1.1, 0).
+pub fn mandelbulb_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let ground = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground));
+
+    let colors = OrbitTrapTexture::new(Color::new(0.9, 0.3, 0.1), Color::new(0.95, 0.85, 0.6));
+    let bulb = SdfMarcher::new(
+        Mandelbulb::new(8.0, 8, 2.0),
+        1.0,
+        Lambertian::from_texture(Arc::new(colors)),
+    )
+    .with_epsilon(2e-3);
+    world.add(Translate::new(bulb, Vec3::new(0.0, 1.15, 0.0)));
+
+    world
+}
+
+// The Mandelbulb from the front and a little above.
+pub fn mandelbulb(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 1.6, 3.2),
+        Point3::new(0.0, 1.1, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        45.0,
+        aspect_ratio,
+        0.0,
+        3.2,
+        None,
+    );
+
+    (mandelbulb_scene(), camera)
+}
+
+// A marble sphere next to a block of wood on a grey floor, lit by the sky.
+// The block's rings are centered on it, as it's built around the origin
+// and moved into place. Look at them from (0, 2.5, 6) towards (0, 0.7, 0).
+pub fn marble_wood_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let ground = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(Point3::new(0.0, -1000.0, 0.0), 1000.0, ground));
+
+    let marble = MarbleTexture::new(
+        12.0,
+        7,
+        Color::new(0.25, 0.22, 0.2),
+        Color::new(0.9, 0.88, 0.85),
+    );
+    world.add(Sphere::new(
+        Point3::new(-1.2, 0.8, 0.0),
+        0.8,
+        Lambertian::from_texture(Arc::new(marble)),
+    ));
+
+    let wood = WoodTexture::new(
+        2.0,
+        12.0,
+        4,
+        Color::new(0.35, 0.18, 0.07),
+        Color::new(0.75, 0.5, 0.28),
+    );
+    let block = AaBox::new(
+        Point3::new(-0.6, 0.0, -0.6),
+        Point3::new(0.6, 1.2, 0.6),
+        Lambertian::from_texture(Arc::new(wood)),
+    );
+    world.add(Translate::new(
+        RotateY::new(block, 30.0),
+        Vec3::new(1.2, 0.0, 0.0),
+    ));
+
+    world
+}
+
+// The marble sphere and the block of wood, seen from the front.
+pub fn marble_wood(aspect_ratio: f64) -> (HittableList, Camera) {
+    let camera = Camera::new(
+        Point3::new(0.0, 2.5, 6.0),
+        Point3::new(0.0, 0.7, 0.0),
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0,
+        aspect_ratio,
+        0.0,
+        10.0,
+        None,
+    );
+
+    (marble_wood_scene(), camera)
+}
+
+#[allow(dead_code)]
+pub fn random_scene() -> HittableList {
+    let mut world = HittableList::default();
+
+    let ground_material = Lambertian::new(Color::from(0.5));
+    world.add(Sphere::new(
+        Point3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground_material,
+    ));
+
+    for a in -11..11 {
+        let a = f64::from(a);
+
+        for b in -11..11 {
+            let b = f64::from(b);
+
+            let choose_mat = random_float();
+            let center = Point3::new(a + 0.9 * random_float(), 0.2, b + 0.9 * random_float());
+
+            if center.distance_to(Point3::new(4.0, 0.2, 0.0)) > 0.9 {
+                if choose_mat < 0.8 {
+                    // diffuse
+                    let albedo = Color::random() * Color::random();
+                    let material = Lambertian::new(albedo);
+                    let center2 = center + Point3::new(0.0, random_float_between(0.0, 0.5), 0.0);
+                    world.add(MovingSphere::new(center, center2, 0.0, 1.0, 0.2, material));
+                } else if choose_mat < 0.95 {
+                    // metal
+                    let albedo = Color::random_between(0.5, 1.0);
+                    let fuzz = random_float_between(0.0, 0.5);
+                    let material = Metal::new(albedo, fuzz);
+                    world.add(Sphere::new(center, 0.2, material));
+                } else {
+                    // glass
+                    let material = Dielectric::new(1.5);
+                    world.add(Sphere::new(center, 0.2, material));
+                }
+            }
+        }
+    }
+
+    let material1 = Dielectric::new(1.5);
+    world.add(Sphere::new(Point3::new(0.0, 1.0, 0.0), 1.0, material1));
+
+    let material2 = Lambertian::new(Color::new(0.4, 0.2, 0.1));
+    world.add(Sphere::new(Point3::new(-4.0, 1.0, 0.0), 1.0, material2));
+
+    let material3 = Metal::new(Color::new(0.7, 0.6, 0.5), 0.0);
+    world.add(Sphere::new(Point3::new(4.0, 1.0, 0.0), 1.0, material3));
+
+    world
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracy::{hittable::Hittable, ray::Ray};
+
+    // The color of the globe at a latitude and longitude in degrees, seen
+    // from straight above the spot.
+    fn earth_color(earth: &HittableList, latitude: f64, longitude: f64) -> Color {
+        let (lat, lon) = (latitude.to_radians(), longitude.to_radians());
+        let up = Vec3::new(lat.cos() * lon.cos(), lat.sin(), -lat.cos() * lon.sin());
+        let ray = Ray::new(up * 10.0, -up, None);
+        let hit = earth.hit(&ray, 0.001, f64::MAX).unwrap();
+        hit.material.scatter(&ray, &hit).unwrap().attenuation
+    }
+
+    fn is_land(color: Color) -> bool {
+        color.y() > color.z()
+    }
+
+    #[test]
+    fn the_earth_map_lines_up_with_latitude_and_longitude() {
+        let earth = earth_scene().unwrap();
+
+        // Central Africa, the Amazon, Siberia and the Australian outback.
+        for (latitude, longitude) in [(0.0, 20.0), (-5.0, -60.0), (60.0, 100.0), (-25.0, 130.0)] {
+            let color = earth_color(&earth, latitude, longitude);
+            assert!(is_land(color), "{} {}: {:?}", latitude, longitude, color);
+        }
+        // The middle of the Atlantic, the Pacific and the Indian Ocean.
+        for (latitude, longitude) in [(0.0, -30.0), (0.0, -140.0), (-20.0, 80.0)] {
+            let color = earth_color(&earth, latitude, longitude);
+            assert!(!is_land(color), "{} {}: {:?}", latitude, longitude, color);
+        }
+    }
+}
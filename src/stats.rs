@@ -0,0 +1,87 @@
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+// Counters describing the work done by a render, shared by all render
+// threads. `Ray::color` adds up its counts locally and publishes them once
+// per path, so the atomics aren't contended on every bounce.
+pub struct RenderStats {
+    // Primary rays, i.e. traced paths.
+    pub paths: AtomicU64,
+    // Every ray tested against the scene, primary or not.
+    pub rays_cast: AtomicU64,
+    pub hits: AtomicU64,
+    // Scattering events along all paths.
+    pub bounces: AtomicU64,
+    // Rays testing the visibility of a light.
+    pub shadow_rays: AtomicU64,
+}
+
+pub static RENDER_STATS: RenderStats = RenderStats::new();
+
+impl RenderStats {
+    pub const fn new() -> Self {
+        Self {
+            paths: AtomicU64::new(0),
+            rays_cast: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            bounces: AtomicU64::new(0),
+            shadow_rays: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record_path(&self, rays_cast: u64, hits: u64, bounces: u64, shadow_rays: u64) {
+        self.paths.fetch_add(1, Ordering::Relaxed);
+        self.rays_cast.fetch_add(rays_cast, Ordering::Relaxed);
+        self.hits.fetch_add(hits, Ordering::Relaxed);
+        self.bounces.fetch_add(bounces, Ordering::Relaxed);
+        self.shadow_rays.fetch_add(shadow_rays, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        for counter in [
+            &self.paths,
+            &self.rays_cast,
+            &self.hits,
+            &self.bounces,
+            &self.shadow_rays,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    // One line summary, e.g. for printing once a render taking `elapsed`
+    // finished.
+    pub fn summary(&self, elapsed: Duration) -> String {
+        let paths = self.paths.load(Ordering::Relaxed);
+        let rays_cast = self.rays_cast.load(Ordering::Relaxed);
+        let hits = self.hits.load(Ordering::Relaxed);
+        let bounces = self.bounces.load(Ordering::Relaxed);
+        let shadow_rays = self.shadow_rays.load(Ordering::Relaxed);
+
+        let average_depth = if paths > 0 {
+            bounces as f64 / paths as f64
+        } else {
+            0.0
+        };
+        let mpaths_per_second = paths as f64 / elapsed.as_secs_f64().max(f64::EPSILON) / 1e6;
+
+        let mut summary = format!(
+            "Rays: {} | Hits: {} | Avg depth: {:.2} | MPaths/s: {:.3}",
+            rays_cast, hits, average_depth, mpaths_per_second
+        );
+        if shadow_rays > 0 {
+            let _ = write!(summary, " | Shadow rays: {}", shadow_rays);
+        }
+
+        summary
+    }
+}
+
+impl Default for RenderStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, solid_color::SolidColor, Texture};
+
+pub struct CheckerTexture {
+    pub odd: Arc<dyn Texture>,
+    pub even: Arc<dyn Texture>,
+    // Number of tiles per 2π world units along each axis.
+    pub scale: f64,
+}
+
+impl CheckerTexture {
+    pub fn new(odd: Arc<dyn Texture>, even: Arc<dyn Texture>, scale: f64) -> Self {
+        Self { odd, even, scale }
+    }
+
+    pub fn from_colors(odd: Color, even: Color, scale: f64) -> Self {
+        Self::new(
+            Arc::new(SolidColor::new(odd)),
+            Arc::new(SolidColor::new(even)),
+            scale,
+        )
+    }
+}
+
+impl Texture for CheckerTexture {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color {
+        // The product of the sines flips sign every time p crosses a tile
+        // boundary along any axis, giving a 3D checkerboard.
+        let sines = f64::sin(self.scale * p.x())
+            * f64::sin(self.scale * p.y())
+            * f64::sin(self.scale * p.z());
+        if sines < 0.0 {
+            self.odd.value(u, v, p)
+        } else {
+            self.even.value(u, v, p)
+        }
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::Checker {
+            odd: Box::new(self.odd.definition()?),
+            even: Box::new(self.even.definition()?),
+            scale: self.scale,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    #[test]
+    fn adjacent_tiles_alternate_colors() {
+        let (odd, even) = (Color::new(0.2, 0.3, 0.1), Color::from(0.9));
+        // Tiles one unit wide, with corners on integer coordinates.
+        let checker = CheckerTexture::from_colors(odd, even, PI);
+        let color = |x: f64, y: f64, z: f64| checker.value(0.0, 0.0, Point3::new(x, y, z));
+
+        assert_eq!(color(0.5, 0.5, 0.5), even);
+        assert_eq!(color(0.9, 0.1, 0.5), even);
+        assert_eq!(color(1.5, 0.5, 0.5), odd);
+        assert_eq!(color(0.5, -0.5, 0.5), odd);
+        assert_eq!(color(0.5, 0.5, 1.5), odd);
+        assert_eq!(color(1.5, 1.5, 0.5), even);
+        assert_eq!(color(-0.5, -0.5, -0.5), odd);
+    }
+
+    #[test]
+    fn tiles_can_hold_other_textures() {
+        let stripes = CheckerTexture::from_colors(Color::from(0.0), Color::from(1.0), 4.0 * PI);
+        let checker = CheckerTexture::new(
+            Arc::new(stripes),
+            Arc::new(SolidColor::new(Color::from(0.5))),
+            PI,
+        );
+
+        // The odd tile is checkered again, four times finer.
+        let p = Point3::new(1.125, 0.125, 0.125);
+        assert_eq!(checker.value(0.0, 0.0, p), Color::from(1.0));
+        let p = Point3::new(1.375, 0.125, 0.125);
+        assert_eq!(checker.value(0.0, 0.0, p), Color::from(0.0));
+        let p = Point3::new(0.375, 0.125, 0.125);
+        assert_eq!(checker.value(0.0, 0.0, p), Color::from(0.5));
+    }
+}
@@ -0,0 +1,153 @@
+use std::sync::Arc;
+
+use crate::Color;
+
+use super::{
+    checker::CheckerTexture,
+    gradient::{GradientDir, GradientTexture},
+    marble::MarbleTexture,
+    noise::NoiseTexture,
+    orbit_trap::OrbitTrapTexture,
+    solid_color::SolidColor,
+    transform::UvTransform,
+    wood::WoodTexture,
+    Texture,
+};
+
+// Plain description of a built-in texture, which unlike `Arc<dyn Texture>`
+// can be inspected, cloned and (with the serde feature) serialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename_all = "snake_case")
+)]
+pub enum TextureDef {
+    Solid {
+        color: Color,
+    },
+    Checker {
+        odd: Box<TextureDef>,
+        even: Box<TextureDef>,
+        scale: f64,
+    },
+    // The noise pattern itself is random and isn't part of the description.
+    Noise {
+        scale: f64,
+    },
+    OrbitTrap {
+        inner: Color,
+        outer: Color,
+    },
+    // Like noise, marble and wood describe only their parameters.
+    Marble {
+        scale: f64,
+        turbulence_depth: u32,
+        vein_color: Color,
+        base_color: Color,
+    },
+    Wood {
+        scale: f64,
+        ring_frequency: f64,
+        grain_depth: u32,
+        dark: Color,
+        light: Color,
+    },
+    Gradient {
+        a: Box<TextureDef>,
+        b: Box<TextureDef>,
+        direction: GradientDir,
+    },
+    UvTransform {
+        inner: Box<TextureDef>,
+        scale_u: f64,
+        scale_v: f64,
+        offset_u: f64,
+        offset_v: f64,
+        rotation_radians: f64,
+    },
+}
+
+impl TextureDef {
+    pub fn build(&self) -> Arc<dyn Texture> {
+        match self {
+            TextureDef::Solid { color } => Arc::new(SolidColor::new(*color)),
+            TextureDef::Checker { odd, even, scale } => {
+                Arc::new(CheckerTexture::new(odd.build(), even.build(), *scale))
+            }
+            TextureDef::Noise { scale } => Arc::new(NoiseTexture::new(*scale)),
+            TextureDef::OrbitTrap { inner, outer } => {
+                Arc::new(OrbitTrapTexture::new(*inner, *outer))
+            }
+            TextureDef::Marble {
+                scale,
+                turbulence_depth,
+                vein_color,
+                base_color,
+            } => Arc::new(MarbleTexture::new(
+                *scale,
+                *turbulence_depth,
+                *vein_color,
+                *base_color,
+            )),
+            TextureDef::Wood {
+                scale,
+                ring_frequency,
+                grain_depth,
+                dark,
+                light,
+            } => Arc::new(WoodTexture::new(
+                *scale,
+                *ring_frequency,
+                *grain_depth,
+                *dark,
+                *light,
+            )),
+            TextureDef::Gradient { a, b, direction } => {
+                Arc::new(GradientTexture::new(a.build(), b.build(), *direction))
+            }
+            TextureDef::UvTransform {
+                inner,
+                scale_u,
+                scale_v,
+                offset_u,
+                offset_v,
+                rotation_radians,
+            } => Arc::new(
+                UvTransform::new(inner.build())
+                    .with_scale(*scale_u, *scale_v)
+                    .with_offset(*offset_u, *offset_v)
+                    .with_rotation(*rotation_radians),
+            ),
+        }
+    }
+}
+
+impl From<Color> for TextureDef {
+    fn from(color: Color) -> Self {
+        TextureDef::Solid { color }
+    }
+}
+
+// Serializes `Arc<dyn Texture>` fields through their `TextureDef`, for use
+// with `#[serde(with = ...)]`. Textures without a definition, such as
+// images, fail to serialize.
+#[cfg(feature = "serde")]
+pub mod serde_texture {
+    use std::sync::Arc;
+
+    use serde::{ser::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Texture, TextureDef};
+
+    pub fn serialize<S: Serializer>(texture: &Arc<dyn Texture>, s: S) -> Result<S::Ok, S::Error> {
+        texture
+            .definition()
+            .ok_or_else(|| S::Error::custom("texture can't be serialized"))?
+            .serialize(s)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Arc<dyn Texture>, D::Error> {
+        Ok(TextureDef::deserialize(d)?.build())
+    }
+}
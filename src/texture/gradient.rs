@@ -0,0 +1,123 @@
+use std::{f64::consts::PI, sync::Arc};
+
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, Texture};
+
+// How a `GradientTexture` goes from `a` to `b` over the UV square.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum GradientDir {
+    // From u = 0 to u = 1.
+    U,
+    // From v = 0 to v = 1.
+    V,
+    // From the center (0.5, 0.5) out to the middle of the edges, and `b`
+    // beyond.
+    Radial,
+    // Once around the center counterclockwise, starting and ending at +u.
+    Angle,
+}
+
+impl GradientDir {
+    // How far along the gradient (u, v) is, from 0 to 1.
+    pub fn blend(self, u: f64, v: f64) -> f64 {
+        let (du, dv) = (u - 0.5, v - 0.5);
+        let t = match self {
+            GradientDir::U => u,
+            GradientDir::V => v,
+            GradientDir::Radial => 2.0 * du.hypot(dv),
+            GradientDir::Angle => dv.atan2(du).rem_euclid(2.0 * PI) / (2.0 * PI),
+        };
+        t.clamp(0.0, 1.0)
+    }
+}
+
+// Blends linearly between two textures, e.g. for skies fading towards the
+// horizon, fog thickening near the ground or one material giving way to
+// another. Both ends are textures themselves, so it can fade between
+// patterns too; at the ends of the gradient it is exactly `a` or `b`.
+#[derive(Debug, Clone)]
+pub struct GradientTexture {
+    pub a: Arc<dyn Texture>,
+    pub b: Arc<dyn Texture>,
+    pub direction: GradientDir,
+}
+
+impl GradientTexture {
+    pub fn new(a: Arc<dyn Texture>, b: Arc<dyn Texture>, direction: GradientDir) -> Self {
+        Self { a, b, direction }
+    }
+}
+
+impl Texture for GradientTexture {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color {
+        let t = self.direction.blend(u, v);
+        Color::lerp(self.a.value(u, v, p), self.b.value(u, v, p), t)
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::Gradient {
+            a: Box::new(self.a.definition()?),
+            b: Box::new(self.b.definition()?),
+            direction: self.direction,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{assert_vec3_approx_eq, texture::solid_color::SolidColor};
+
+    fn gradient(direction: GradientDir) -> GradientTexture {
+        GradientTexture::new(
+            Arc::new(SolidColor::new(Color::new(0.1, 0.7, 0.3))),
+            Arc::new(SolidColor::new(Color::new(0.9, 0.2, 0.6))),
+            direction,
+        )
+    }
+
+    fn at(texture: &GradientTexture, u: f64, v: f64) -> Color {
+        texture.value(u, v, Point3::from(0.0))
+    }
+
+    #[test]
+    fn the_edges_are_exactly_the_end_colors() {
+        let (a, b) = (Color::new(0.1, 0.7, 0.3), Color::new(0.9, 0.2, 0.6));
+        let (along_u, along_v) = (gradient(GradientDir::U), gradient(GradientDir::V));
+        for across in [0.0, 0.25, 0.5, 1.0] {
+            assert_eq!(at(&along_u, 0.0, across), a);
+            assert_eq!(at(&along_u, 1.0, across), b);
+            assert_eq!(at(&along_v, across, 0.0), a);
+            assert_eq!(at(&along_v, across, 1.0), b);
+        }
+
+        let radial = gradient(GradientDir::Radial);
+        assert_eq!(at(&radial, 0.5, 0.5), a);
+        for (u, v) in [(1.0, 0.5), (0.5, 0.0), (0.0, 0.0), (1.0, 1.0)] {
+            assert_eq!(at(&radial, u, v), b);
+        }
+
+        let angle = gradient(GradientDir::Angle);
+        assert_eq!(at(&angle, 1.0, 0.5), a);
+        assert_vec3_approx_eq!(at(&angle, 1.0, 0.5 - 1e-9), b, 1e-6);
+    }
+
+    #[test]
+    fn halfway_is_the_average() {
+        let halfway = Color::new(0.5, 0.45, 0.45);
+        assert_vec3_approx_eq!(at(&gradient(GradientDir::U), 0.5, 0.9), halfway, 1e-12);
+        assert_vec3_approx_eq!(at(&gradient(GradientDir::V), 0.1, 0.5), halfway, 1e-12);
+        assert_vec3_approx_eq!(
+            at(&gradient(GradientDir::Radial), 0.75, 0.5),
+            halfway,
+            1e-12
+        );
+        assert_vec3_approx_eq!(at(&gradient(GradientDir::Angle), 0.0, 0.5), halfway, 1e-12);
+    }
+}
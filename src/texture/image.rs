@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use image::ImageError;
+
+use crate::{Color, Point3};
+
+use super::Texture;
+
+pub struct ImageTexture {
+    // Tightly packed 8-bit RGB rows, top row first.
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+}
+
+impl ImageTexture {
+    pub fn new(path: &Path) -> Result<Self, ImageError> {
+        let image = image::open(path)?.to_rgb8();
+        let (width, height) = image.dimensions();
+
+        Ok(Self {
+            data: image.into_raw(),
+            width,
+            height,
+        })
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        // Clamp to edge.
+        let x = x.clamp(0, self.width as i64 - 1) as usize;
+        let y = y.clamp(0, self.height as i64 - 1) as usize;
+        let i = 3 * (y * self.width as usize + x);
+        let scale = 1.0 / 255.0;
+
+        Color::new(
+            self.data[i] as f64 * scale,
+            self.data[i + 1] as f64 * scale,
+            self.data[i + 2] as f64 * scale,
+        )
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, u: f64, v: f64, _p: Point3) -> Color {
+        if self.width == 0 || self.height == 0 {
+            // Debugging aid: make a missing image obvious.
+            return Color::new(0.0, 1.0, 1.0);
+        }
+
+        // Image rows go top to bottom while v goes bottom to top. Texel
+        // centers sit at half-integer coordinates.
+        let x = u.clamp(0.0, 1.0) * self.width as f64 - 0.5;
+        let y = (1.0 - v.clamp(0.0, 1.0)) * self.height as f64 - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = Color::lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), tx);
+        let bottom = Color::lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), tx);
+        Color::lerp(top, bottom, ty)
+    }
+}
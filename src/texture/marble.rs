@@ -0,0 +1,46 @@
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, perlin::Perlin, Texture};
+
+// Veined stone: stripes across x, a vein every 2 pi / `scale` units,
+// pushed around by `turbulence_depth` octaves of turbulence so they wind
+// and branch. Like all noise textures it has no seams on any shape, since
+// it only depends on the hit point.
+pub struct MarbleTexture {
+    pub scale: f64,
+    pub turbulence_depth: u32,
+    pub vein_color: Color,
+    pub base_color: Color,
+    noise: Perlin,
+}
+
+impl MarbleTexture {
+    pub fn new(scale: f64, turbulence_depth: u32, vein_color: Color, base_color: Color) -> Self {
+        Self {
+            scale,
+            turbulence_depth,
+            vein_color,
+            base_color,
+            noise: Perlin::new(),
+        }
+    }
+}
+
+impl Texture for MarbleTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Color {
+        let turbulence = self.noise.turb(p, self.turbulence_depth as i32);
+        let phase = self.scale * (p.x() + turbulence);
+        // Narrow veins where the stripes peak, base color in between.
+        let vein = (0.5 * (1.0 + phase.sin())).powi(4);
+        Color::lerp(self.base_color, self.vein_color, vein)
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::Marble {
+            scale: self.scale,
+            turbulence_depth: self.turbulence_depth,
+            vein_color: self.vein_color,
+            base_color: self.base_color,
+        })
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fmt, sync::Arc};
+
+use crate::{Color, Point3};
+
+use self::def::TextureDef;
+
+pub mod checker;
+pub mod def;
+pub mod gradient;
+pub mod image;
+pub mod marble;
+pub mod noise;
+pub mod orbit_trap;
+pub mod perlin;
+pub mod solid_color;
+pub mod texture2d;
+pub mod transform;
+pub mod wood;
+
+pub trait Texture: Send + Sync {
+    // Color at surface coordinates (u, v) of hit point p.
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color;
+
+    // Description of the texture, or None if it can't be described by a
+    // `TextureDef` (e.g. images).
+    fn definition(&self) -> Option<TextureDef> {
+        None
+    }
+}
+
+// Lets textures be shared, e.g. wrapped by several transforms.
+impl<T: Texture + ?Sized> Texture for Arc<T> {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color {
+        (**self).value(u, v, p)
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        (**self).definition()
+    }
+}
+
+// Lets materials holding textures derive Debug. Built-in textures show
+// their definition.
+impl fmt::Debug for dyn Texture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.definition() {
+            Some(def) => def.fmt(f),
+            None => f.write_str("Texture"),
+        }
+    }
+}
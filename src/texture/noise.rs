@@ -0,0 +1,29 @@
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, perlin::Perlin, Texture};
+
+// Marble-like pattern: sine stripes along z, distorted by turbulence.
+pub struct NoiseTexture {
+    pub scale: f64,
+    noise: Perlin,
+}
+
+impl NoiseTexture {
+    pub fn new(scale: f64) -> Self {
+        Self {
+            scale,
+            noise: Perlin::new(),
+        }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Color {
+        let phase = self.scale * p.z() + 10.0 * self.noise.turb(p, 7);
+        Color::new(1.0, 1.0, 1.0) * 0.5 * (1.0 + f64::sin(phase))
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::Noise { scale: self.scale })
+    }
+}
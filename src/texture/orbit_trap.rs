@@ -0,0 +1,32 @@
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, Texture};
+
+// Colors fractals by their orbit trap, which `hittable::sdf::Mandelbulb`
+// stores in u: blends from `inner` for orbits passing through the origin to
+// `outer` for ones staying a unit away.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitTrapTexture {
+    pub inner: Color,
+    pub outer: Color,
+}
+
+impl OrbitTrapTexture {
+    pub fn new(inner: Color, outer: Color) -> Self {
+        Self { inner, outer }
+    }
+}
+
+impl Texture for OrbitTrapTexture {
+    fn value(&self, u: f64, _v: f64, _p: Point3) -> Color {
+        let t = u.clamp(0.0, 1.0);
+        self.inner * (1.0 - t) + self.outer * t
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::OrbitTrap {
+            inner: self.inner,
+            outer: self.outer,
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use rand::seq::SliceRandom;
+
+use crate::{rng, Point3, Vec3};
+
+const POINT_COUNT: usize = 256;
+
+// Ken Perlin's gradient noise: a lattice of random unit vectors, hashed
+// through three permutation tables and smoothly interpolated in between.
+pub struct Perlin {
+    ranvec: Vec<Vec3>,
+    perm_x: Vec<usize>,
+    perm_y: Vec<usize>,
+    perm_z: Vec<usize>,
+}
+
+impl Perlin {
+    pub fn new() -> Self {
+        Self {
+            ranvec: (0..POINT_COUNT)
+                .map(|_| Vec3::random_between(-1.0, 1.0).unit_vector())
+                .collect(),
+            perm_x: Self::generate_perm(),
+            perm_y: Self::generate_perm(),
+            perm_z: Self::generate_perm(),
+        }
+    }
+
+    // Returns a value in [-1, 1] that varies smoothly with p.
+    pub fn noise(&self, p: Point3) -> f64 {
+        let u = p.x() - p.x().floor();
+        let v = p.y() - p.y().floor();
+        let w = p.z() - p.z().floor();
+
+        let i = p.x().floor() as i64;
+        let j = p.y().floor() as i64;
+        let k = p.z().floor() as i64;
+
+        let mut c = [[[Vec3::new(0.0, 0.0, 0.0); 2]; 2]; 2];
+        for (di, plane) in c.iter_mut().enumerate() {
+            for (dj, row) in plane.iter_mut().enumerate() {
+                for (dk, corner) in row.iter_mut().enumerate() {
+                    *corner = self.ranvec[self.perm_x[((i + di as i64) & 255) as usize]
+                        ^ self.perm_y[((j + dj as i64) & 255) as usize]
+                        ^ self.perm_z[((k + dk as i64) & 255) as usize]];
+                }
+            }
+        }
+
+        Self::trilinear_interp(&c, u, v, w)
+    }
+
+    // Sum of `depth` octaves of noise, each at twice the frequency and half
+    // the amplitude of the previous one.
+    pub fn turb(&self, p: Point3, depth: i32) -> f64 {
+        let mut accum = 0.0;
+        let mut temp_p = p;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accum += weight * self.noise(temp_p);
+            weight *= 0.5;
+            temp_p *= 2.0;
+        }
+
+        accum.abs()
+    }
+
+    fn generate_perm() -> Vec<usize> {
+        let mut p: Vec<usize> = (0..POINT_COUNT).collect();
+        rng::with_rng(|rng| p.shuffle(rng));
+        p
+    }
+
+    fn trilinear_interp(c: &[[[Vec3; 2]; 2]; 2], u: f64, v: f64, w: f64) -> f64 {
+        // Hermite smoothing hides the lattice's grid artifacts.
+        let uu = u * u * (3.0 - 2.0 * u);
+        let vv = v * v * (3.0 - 2.0 * v);
+        let ww = w * w * (3.0 - 2.0 * w);
+
+        let mut accum = 0.0;
+        for (i, plane) in c.iter().enumerate() {
+            for (j, row) in plane.iter().enumerate() {
+                for (k, corner) in row.iter().enumerate() {
+                    let (fi, fj, fk) = (i as f64, j as f64, k as f64);
+                    let weight = Vec3::new(u - fi, v - fj, w - fk);
+                    accum += (fi * uu + (1.0 - fi) * (1.0 - uu))
+                        * (fj * vv + (1.0 - fj) * (1.0 - vv))
+                        * (fk * ww + (1.0 - fk) * (1.0 - ww))
+                        * corner.dot(weight);
+                }
+            }
+        }
+
+        accum
+    }
+}
+
+impl Default for Perlin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sampling;
+
+    #[test]
+    fn noise_stays_in_range_and_vanishes_on_the_lattice() {
+        rng::seed_thread(2);
+        let perlin = Perlin::new();
+        for _ in 0..10_000 {
+            let p = Vec3::random_between(-50.0, 50.0);
+            let noise = perlin.noise(p);
+            assert!((-1.0..=1.0).contains(&noise), "{p}: {noise}");
+            assert!(perlin.turb(p, 7) >= 0.0);
+        }
+
+        // Every lattice point's gradient is dotted with a zero offset.
+        for p in [Point3::new(0.0, 0.0, 0.0), Point3::new(3.0, -7.0, 12.0)] {
+            assert_eq!(perlin.noise(p), 0.0);
+        }
+    }
+
+    #[test]
+    fn nearby_points_have_similar_noise() {
+        rng::seed_thread(3);
+        let perlin = Perlin::new();
+        // The slope of the interpolated noise stays below this; about 1.6
+        // is the steepest seen.
+        let lipschitz = 3.0;
+        let step = 1e-3;
+        for _ in 0..10_000 {
+            let p = Vec3::random_between(-50.0, 50.0);
+            let q = p + sampling::uniform_sphere() * step;
+            let change = (perlin.noise(p) - perlin.noise(q)).abs();
+            assert!(change <= lipschitz * step, "{p}: {change}");
+        }
+    }
+}
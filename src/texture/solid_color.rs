@@ -0,0 +1,22 @@
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, Texture};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SolidColor(pub Color);
+
+impl SolidColor {
+    pub fn new(color: Color) -> Self {
+        Self(color)
+    }
+}
+
+impl Texture for SolidColor {
+    fn value(&self, _u: f64, _v: f64, _p: Point3) -> Color {
+        self.0
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::Solid { color: self.0 })
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{Color, Point3};
+
+use super::Texture;
+
+// An in-memory image of linear colors, e.g. a rendered frame or an
+// environment map. Rows are stored top row first. Lookups wrap around
+// horizontally and clamp vertically, which suits equirectangular maps.
+#[derive(Debug, Clone)]
+pub struct Texture2D {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<Color>,
+}
+
+impl Texture2D {
+    // A black image of the given size.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::new(0.0, 0.0, 0.0); (width * height) as usize],
+        }
+    }
+
+    pub fn get(&self, x: u32, y: u32) -> Color {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn set(&mut self, x: u32, y: u32, color: Color) {
+        self.pixels[(y * self.width + x) as usize] = color;
+    }
+
+    fn texel(&self, x: i64, y: i64) -> Color {
+        let x = x.rem_euclid(self.width as i64) as u32;
+        let y = y.clamp(0, self.height as i64 - 1) as u32;
+        self.get(x, y)
+    }
+
+    // Bilinearly filtered color at (u, v), with v = 0 at the bottom row.
+    pub fn sample(&self, u: f64, v: f64) -> Color {
+        // Texel centers sit at half-integer coordinates.
+        let x = u * self.width as f64 - 0.5;
+        let y = (1.0 - v.clamp(0.0, 1.0)) * self.height as f64 - 0.5;
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let tx = x - x0;
+        let ty = y - y0;
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = Color::lerp(self.texel(x0, y0), self.texel(x0 + 1, y0), tx);
+        let bottom = Color::lerp(self.texel(x0, y0 + 1), self.texel(x0 + 1, y0 + 1), tx);
+        Color::lerp(top, bottom, ty)
+    }
+}
+
+impl Texture for Texture2D {
+    fn value(&self, u: f64, v: f64, _p: Point3) -> Color {
+        if self.width == 0 || self.height == 0 {
+            // Debugging aid: make a missing image obvious.
+            return Color::new(0.0, 1.0, 1.0);
+        }
+
+        self.sample(u, v)
+    }
+}
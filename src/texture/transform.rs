@@ -0,0 +1,157 @@
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, Texture};
+
+// Tiles, moves and turns the UV coordinates `inner` is looked up with:
+// (u, v) is rotated by `rotation_radians` counterclockwise around the
+// origin, scaled, offset, and then wrapped into [0, 1), so the texture
+// repeats. Scale 4 fits four copies of `inner` across each direction.
+//
+// Only textures that read u and v, like images, change; 3D ones such as
+// the checker and noise textures only depend on the hit point.
+#[derive(Debug, Clone)]
+pub struct UvTransform<T: Texture> {
+    pub inner: T,
+    pub scale_u: f64,
+    pub scale_v: f64,
+    pub offset_u: f64,
+    pub offset_v: f64,
+    pub rotation_radians: f64,
+}
+
+impl<T: Texture> UvTransform<T> {
+    // `inner` unchanged, for the builder methods below to adjust.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            scale_u: 1.0,
+            scale_v: 1.0,
+            offset_u: 0.0,
+            offset_v: 0.0,
+            rotation_radians: 0.0,
+        }
+    }
+
+    pub fn with_scale(self, scale_u: f64, scale_v: f64) -> Self {
+        Self {
+            scale_u,
+            scale_v,
+            ..self
+        }
+    }
+
+    pub fn with_offset(self, offset_u: f64, offset_v: f64) -> Self {
+        Self {
+            offset_u,
+            offset_v,
+            ..self
+        }
+    }
+
+    pub fn with_rotation(self, rotation_radians: f64) -> Self {
+        Self {
+            rotation_radians,
+            ..self
+        }
+    }
+
+    // The coordinates `inner` is looked up at for (u, v).
+    pub fn transform(&self, u: f64, v: f64) -> (f64, f64) {
+        let (sin, cos) = self.rotation_radians.sin_cos();
+        let (ru, rv) = (cos * u - sin * v, sin * u + cos * v);
+        (
+            (self.scale_u * ru + self.offset_u).rem_euclid(1.0),
+            (self.scale_v * rv + self.offset_v).rem_euclid(1.0),
+        )
+    }
+}
+
+impl<T: Texture> Texture for UvTransform<T> {
+    fn value(&self, u: f64, v: f64, p: Point3) -> Color {
+        let (u, v) = self.transform(u, v);
+        self.inner.value(u, v, p)
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::UvTransform {
+            inner: Box::new(self.inner.definition()?),
+            scale_u: self.scale_u,
+            scale_v: self.scale_v,
+            offset_u: self.offset_u,
+            offset_v: self.offset_v,
+            rotation_radians: self.rotation_radians,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::{FRAC_PI_4, SQRT_2};
+
+    use super::*;
+
+    // Two by two checkerboard in UV space, white in the bottom left tile.
+    struct UvChecker;
+
+    impl Texture for UvChecker {
+        fn value(&self, u: f64, v: f64, _p: Point3) -> Color {
+            let tile = (2.0 * u).floor() + (2.0 * v).floor();
+            Color::from(if tile as i64 % 2 == 0 { 1.0 } else { 0.0 })
+        }
+    }
+
+    // Where the color changes along the scanline at `v`, to within a
+    // thousandth.
+    fn edges(texture: &impl Texture, v: f64) -> Vec<f64> {
+        let steps = 1000;
+        let color = |i: usize| {
+            let u = (i as f64 + 0.5) / steps as f64;
+            texture.value(u, v, Point3::from(0.0)).x()
+        };
+        (1..steps)
+            .filter(|&i| color(i) != color(i - 1))
+            .map(|i| i as f64 / steps as f64)
+            .collect()
+    }
+
+    fn assert_edges_at(found: Vec<f64>, expected: &[f64]) {
+        assert_eq!(found.len(), expected.len(), "{:?}", found);
+        for (found, expected) in found.iter().zip(expected) {
+            assert!((found - expected).abs() <= 1e-3, "{:?}", found);
+        }
+    }
+
+    #[test]
+    fn scaling_repeats_the_texture() {
+        assert_edges_at(edges(&UvChecker, 0.3), &[0.5]);
+
+        let tiled = UvTransform::new(UvChecker).with_scale(4.0, 4.0);
+        // Eight tiles where there were two.
+        let expected: Vec<f64> = (1..8).map(|i| i as f64 / 8.0).collect();
+        assert_edges_at(edges(&tiled, 0.3), &expected);
+    }
+
+    #[test]
+    fn rotating_by_45_degrees_makes_the_edges_diagonal() {
+        // Scaled so that the rotated tiles span whole half units again.
+        let turned = UvTransform::new(UvChecker)
+            .with_rotation(FRAC_PI_4)
+            .with_scale(SQRT_2, SQRT_2);
+
+        // Edges where u - v or u + v is a multiple of 0.5, which move by as
+        // much as the scanline does.
+        assert_edges_at(edges(&turned, 0.1), &[0.1, 0.4, 0.6, 0.9]);
+        assert_edges_at(edges(&turned, 0.2), &[0.2, 0.3, 0.7, 0.8]);
+    }
+
+    #[test]
+    fn offsets_move_the_texture_and_wrap_around() {
+        let moved = UvTransform::new(UvChecker).with_offset(0.25, 0.0);
+        assert_edges_at(edges(&moved, 0.3), &[0.25, 0.75]);
+
+        let (u, v) = UvTransform::new(UvChecker)
+            .with_offset(-0.25, 1.5)
+            .transform(0.1, 0.2);
+        assert!((u - 0.85).abs() < 1e-12 && (v - 0.7).abs() < 1e-12);
+    }
+}
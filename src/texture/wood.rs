@@ -0,0 +1,54 @@
+use crate::{Color, Point3};
+
+use super::{def::TextureDef, perlin::Perlin, Texture};
+
+// Growth rings around the y axis, `ring_frequency` of them to a unit of
+// length, shifted by `grain_depth` octaves of turbulence at `scale` so they
+// wobble like the grain of real wood. Each ring fades from `light`, the
+// wood grown in spring, to `dark`, where growth slowed down. Boxes cut
+// from it show rings on their ends and stripes along their sides.
+pub struct WoodTexture {
+    pub scale: f64,
+    pub ring_frequency: f64,
+    pub grain_depth: u32,
+    pub dark: Color,
+    pub light: Color,
+    noise: Perlin,
+}
+
+impl WoodTexture {
+    pub fn new(
+        scale: f64,
+        ring_frequency: f64,
+        grain_depth: u32,
+        dark: Color,
+        light: Color,
+    ) -> Self {
+        Self {
+            scale,
+            ring_frequency,
+            grain_depth,
+            dark,
+            light,
+            noise: Perlin::new(),
+        }
+    }
+}
+
+impl Texture for WoodTexture {
+    fn value(&self, _u: f64, _v: f64, p: Point3) -> Color {
+        let grain = self.noise.turb(p * self.scale, self.grain_depth as i32);
+        let rings = self.ring_frequency * p.x().hypot(p.z()) + grain;
+        Color::lerp(self.light, self.dark, rings.fract().powi(2))
+    }
+
+    fn definition(&self) -> Option<TextureDef> {
+        Some(TextureDef::Wood {
+            scale: self.scale,
+            ring_frequency: self.ring_frequency,
+            grain_depth: self.grain_depth,
+            dark: self.dark,
+            light: self.light,
+        })
+    }
+}
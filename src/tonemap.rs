@@ -0,0 +1,293 @@
+use std::{fmt, str::FromStr};
+
+use crate::Color;
+
+// Gamma the curve-based operators encode their result with.
+const DISPLAY_GAMMA: f64 = 2.2;
+
+// How linear radiance is turned into display values in [0, 1].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    // Values are passed through and clipped at 1.
+    Linear,
+    // Gamma encoding only, bright values are clipped.
+    Gamma(f64),
+    // c / (1 + c) per channel, followed by display gamma.
+    Reinhard,
+    // Reinhard applied to the luminance, with the color scaled along:
+    // keeps the hue and saturation that per-channel Reinhard washes out of
+    // bright colors, but very saturated ones can still clip.
+    ReinhardLuminance,
+    // Krzysztof Narkowicz's 2015 fit of the ACES filmic curve, including
+    // its exposure adjustment, followed by display gamma.
+    Aces,
+}
+
+impl Default for ToneMap {
+    // sqrt() was the only option before, so gamma 2.0 stays the default.
+    fn default() -> Self {
+        ToneMap::Gamma(2.0)
+    }
+}
+
+pub fn tone_map(color: Color, tm: ToneMap) -> Color {
+    let mapped = match tm {
+        ToneMap::Linear => color,
+        ToneMap::Gamma(gamma) => gamma_encode(color, gamma),
+        ToneMap::Reinhard => gamma_encode(map_channels(color, |c| c / (1.0 + c)), DISPLAY_GAMMA),
+        ToneMap::ReinhardLuminance => gamma_encode(reinhard_lum(color), DISPLAY_GAMMA),
+        ToneMap::Aces => gamma_encode(aces(color), DISPLAY_GAMMA),
+    };
+
+    mapped.clamp_components(0.0, 1.0)
+}
+
+// Relative luminance of a linear Rec. 709 / sRGB color.
+pub fn luminance(color: Color) -> f64 {
+    0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z()
+}
+
+// Scales `color` so its luminance L becomes L / (1 + L).
+pub fn reinhard_lum(color: Color) -> Color {
+    let l = luminance(color);
+    if l <= 0.0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    color * (1.0 / (1.0 + l))
+}
+
+// The fitted ACES curve per channel. Inputs are scaled by 0.6 first, as in
+// Narkowicz's fit, so mid grey (0.18) lands at 0.14 instead of 0.27. The
+// curve reaches 0.90 at an input of 3 and 0.99 at 10, and its tail, which
+// overshoots 1 slightly, is clamped.
+pub fn aces(color: Color) -> Color {
+    const EXPOSURE: f64 = 0.6;
+    map_channels(color * EXPOSURE, aces_fitted).clamp_components(0.0, 1.0)
+}
+
+fn map_channels(color: Color, f: impl Fn(f64) -> f64) -> Color {
+    Color::new(f(color.x()), f(color.y()), f(color.z()))
+}
+
+fn gamma_encode(color: Color, gamma: f64) -> Color {
+    // Negative values would turn into NaN.
+    map_channels(color, |c| c.max(0.0).powf(1.0 / gamma))
+}
+
+fn aces_fitted(x: f64) -> f64 {
+    const A: f64 = 2.51;
+    const B: f64 = 0.03;
+    const C: f64 = 2.43;
+    const D: f64 = 0.59;
+    const E: f64 = 0.14;
+    (x * (A * x + B)) / (x * (C * x + D) + E)
+}
+
+#[derive(Debug)]
+pub struct ParseToneMapError(String);
+
+impl fmt::Display for ParseToneMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown tone map '{}', expected linear, gamma[=G], reinhard, reinhard-lum or aces",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseToneMapError {}
+
+// Parses "linear", "gamma" (2.0), "gamma=G", "reinhard", "reinhard-lum" or
+// "aces".
+impl FromStr for ToneMap {
+    type Err = ParseToneMapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseToneMapError(s.to_string());
+        match s {
+            "linear" => Ok(ToneMap::Linear),
+            "gamma" => Ok(ToneMap::default()),
+            "reinhard" => Ok(ToneMap::Reinhard),
+            "reinhard-lum" => Ok(ToneMap::ReinhardLuminance),
+            "aces" => Ok(ToneMap::Aces),
+            _ => {
+                let gamma: f64 = s
+                    .strip_prefix("gamma=")
+                    .and_then(|g| g.parse().ok())
+                    .ok_or_else(error)?;
+                if gamma > 0.0 {
+                    Ok(ToneMap::Gamma(gamma))
+                } else {
+                    Err(error())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERATORS: [ToneMap; 6] = [
+        ToneMap::Linear,
+        ToneMap::Gamma(2.0),
+        ToneMap::Gamma(2.2),
+        ToneMap::Reinhard,
+        ToneMap::ReinhardLuminance,
+        ToneMap::Aces,
+    ];
+
+    // Quantizes like the PPM and PNG writers do.
+    fn to_u8(color: Color) -> [u8; 3] {
+        [color.x(), color.y(), color.z()].map(|c| (255.99 * c) as u8)
+    }
+
+    #[test]
+    fn white_maps_to_a_bright_neutral_grey() {
+        let white = Color::new(1.0, 1.0, 1.0);
+        for tm in OPERATORS {
+            let [r, g, b] = to_u8(tone_map(white, tm));
+            assert!(r == g && g == b, "{:?} tints white: {:?}", tm, [r, g, b]);
+            // The curves keep headroom for brighter values. Reinhard keeps
+            // the most, mapping white to one half before gamma.
+            assert!(r >= 180, "{:?} maps white to {}", tm, r);
+        }
+        assert_eq!(to_u8(tone_map(white, ToneMap::Linear)), [255; 3]);
+        assert_eq!(to_u8(tone_map(white, ToneMap::Gamma(2.0))), [255; 3]);
+    }
+
+    #[test]
+    fn outputs_stay_in_range_and_grow_with_the_input() {
+        for tm in OPERATORS {
+            let mut previous = 0.0;
+            for i in 0..=200 {
+                let c = i as f64 * 0.1;
+                let mapped = tone_map(Color::new(c, c, c), tm).x();
+                assert!(
+                    (0.0..=1.0).contains(&mapped),
+                    "{:?}({}) = {}",
+                    tm,
+                    c,
+                    mapped
+                );
+                assert!(mapped >= previous, "{:?} falls at {}", tm, c);
+                previous = mapped;
+            }
+        }
+        let black = Color::new(0.0, 0.0, 0.0);
+        for tm in OPERATORS {
+            assert_eq!(tone_map(black, tm).x(), 0.0);
+        }
+    }
+
+    #[test]
+    fn reinhard_is_c_over_one_plus_c() {
+        let mapped = tone_map(Color::new(0.5, 1.0, 3.0), ToneMap::Reinhard);
+        for (mapped, c) in [(mapped.x(), 0.5_f64), (mapped.y(), 1.0), (mapped.z(), 3.0)] {
+            let expected = (c / (1.0 + c)).powf(1.0 / DISPLAY_GAMMA);
+            assert!((mapped - expected).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn reinhard_on_luminance_keeps_the_hue() {
+        let color = Color::new(4.0, 2.0, 1.0);
+        let mapped = reinhard_lum(color);
+
+        assert!((mapped.x() / mapped.y() - 2.0).abs() < 1e-12);
+        assert!((mapped.y() / mapped.z() - 2.0).abs() < 1e-12);
+        let l = luminance(color);
+        assert!((luminance(mapped) - l / (1.0 + l)).abs() < 1e-12);
+    }
+
+    // Hue in degrees and saturation of a color, as in HSV.
+    fn hue_and_saturation(color: Color) -> (f64, f64) {
+        let (r, g, b) = (color.x(), color.y(), color.z());
+        let max = r.max(g).max(b);
+        let range = max - r.min(g).min(b);
+        let hue = if range == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / range).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / range + 2.0)
+        } else {
+            60.0 * ((r - g) / range + 4.0)
+        };
+
+        (hue, range / max)
+    }
+
+    #[test]
+    fn bright_lights_keep_their_color_better_on_luminance() {
+        // Lights saturated enough to clip on their brightest channel even
+        // after the luminance mapping, e.g. (0.5, 2, 6), can shift as much
+        // or more.
+        for light in [
+            Color::new(10.0, 10.0, 2.0),
+            Color::new(1.0, 3.0, 0.5),
+            Color::new(4.0, 1.0, 0.2),
+        ] {
+            let (hue, saturation) = hue_and_saturation(light);
+            let shift = |tm: ToneMap| {
+                let (h, s) = hue_and_saturation(tone_map(light, tm));
+                ((h - hue).abs(), (s - saturation).abs())
+            };
+            let (channel_hue, channel_saturation) = shift(ToneMap::Reinhard);
+            let (lum_hue, lum_saturation) = shift(ToneMap::ReinhardLuminance);
+
+            assert!(lum_hue <= channel_hue, "{:?} hue", light);
+            assert!(
+                lum_saturation < channel_saturation,
+                "{:?} saturation",
+                light
+            );
+        }
+    }
+
+    #[test]
+    fn aces_matches_the_fitted_curve() {
+        // Values quoted for the curve with its exposure adjustment.
+        let at = |c: f64| aces(Color::new(c, c, c)).x();
+        assert!((at(0.18) - 0.14).abs() < 0.01);
+        assert!((at(3.0) - 0.90).abs() < 0.01);
+        assert!((at(10.0) - 0.99).abs() < 0.01);
+        assert_eq!(at(1000.0), 1.0);
+    }
+
+    #[test]
+    fn tone_maps_parse_from_their_names() {
+        assert_eq!("linear".parse::<ToneMap>().unwrap(), ToneMap::Linear);
+        assert_eq!("gamma".parse::<ToneMap>().unwrap(), ToneMap::Gamma(2.0));
+        assert_eq!("gamma=2.4".parse::<ToneMap>().unwrap(), ToneMap::Gamma(2.4));
+        assert_eq!("reinhard".parse::<ToneMap>().unwrap(), ToneMap::Reinhard);
+        assert_eq!(
+            "reinhard-lum".parse::<ToneMap>().unwrap(),
+            ToneMap::ReinhardLuminance
+        );
+        assert_eq!("aces".parse::<ToneMap>().unwrap(), ToneMap::Aces);
+        for invalid in ["filmic", "gamma=", "gamma=0", "gamma=-1", "gamma=x"] {
+            assert!(invalid.parse::<ToneMap>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn aces_keeps_black_and_mid_grey_and_compresses_highlights() {
+        let at = |c: f64| aces(Color::new(c, c, c)).x();
+
+        assert_eq!(at(0.0), 0.0);
+        // The exposure puts mid grey a little darker, not far off.
+        assert!((at(0.18) - 0.18).abs() < 0.05);
+        for c in [1.0, 1.5, 2.0, 4.0, 8.0] {
+            assert!(at(c) < 1.0, "aces({}) = {}", c, at(c));
+        }
+        // Each channel follows the curve on its own.
+        let mapped = aces(Color::new(0.0, 0.18, 4.0));
+        assert_eq!(
+            (mapped.x(), mapped.y(), mapped.z()),
+            (at(0.0), at(0.18), at(4.0))
+        );
+    }
+}
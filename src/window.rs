@@ -0,0 +1,227 @@
+// The SFML window, which shows the image as it renders. Built with the
+// window feature; without it renders are always headless.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use sfml::{
+    graphics::{Color as SFMLColor, Font, RenderTarget, RenderWindow, Sprite, Text, Texture, Transformable},
+    window::{Event, Style},
+};
+use tracy::{config::RenderConfig, rng, tonemap::ToneMap};
+
+use crate::{
+    checkpoint::Checkpoint, checkpoint_settings, prepare_scene, start_render, to_rgba, Options,
+    Pixel, RenderJob, RenderMessage, Scene,
+};
+
+// Shows the image as it renders. If the scene is a file, it is watched and
+// rendered again whenever it changes, for editing it live.
+pub fn run_window(mut job: RenderJob, config: &RenderConfig, options: &Options) {
+    let (progressive, tm) = (options.progressive, options.tone_map);
+    let (width, height) = (config.width, config.height);
+    let mut watcher = options
+        .scene
+        .as_deref()
+        .filter(|path| path.is_file())
+        .map(SceneWatcher::new);
+
+    // UI
+
+    let mut window = RenderWindow::new(
+        (width, height),
+        "Tracy",
+        Style::CLOSE,
+        &Default::default(),
+    );
+
+    let mut texture = Texture::new().unwrap();
+    
+    if !texture.create(width, height) {
+        panic!("Unable to create texture");
+    }
+
+    texture.set_smooth(false);
+    // Unrendered tiles show the window background.
+    let background = [30u8, 30, 30, 255].repeat((width * height) as usize);
+    unsafe {
+        texture.update_from_pixels(&background, width, height, 0, 0);
+    }
+
+    // Load a font for rendering text (embedded open-source DejaVu Sans)
+    let font = unsafe {
+        Font::from_memory(include_bytes!("../DejaVuSans.ttf"))
+            .expect("Failed to load font")
+    };
+
+    let total_pixels = width * height;
+    let mut pixels_rendered = 0u32;
+    let mut rendering_complete = false;
+
+    while window.is_open() {
+        // Event processing
+        while let Some(event) = window.poll_event() {
+            // Request closing for the window
+            if event == Event::Closed {
+                window.close();
+            }
+        }
+
+        let changed = watcher.as_mut().is_some_and(|watcher| watcher.changed());
+        if let Some(scene) = changed.then(|| reload_scene(options, config)).flatten() {
+            eprintln!("Scene changed, rendering again");
+            let checkpoint = Checkpoint {
+                seed: rng::global_seed(),
+                settings: checkpoint_settings(options, &scene),
+                tiles: Vec::new(),
+            };
+            // Before the new render starts writing the checkpoint.
+            job.stop();
+            job = start_render(scene, checkpoint, progressive);
+
+            unsafe {
+                texture.update_from_pixels(&background, width, height, 0, 0);
+            }
+            pixels_rendered = 0;
+            rendering_complete = false;
+        }
+
+        // Process all pending messages without blocking
+        while let Ok(msg) = job.receiver.try_recv() {
+            match msg {
+                RenderMessage::Pixel(pixel) => {
+                    let rgba = to_rgba(pixel.color, pixel.sample_count, tm);
+                    unsafe {
+                        texture.update_from_pixels(&rgba, 1, 1, pixel.x, pixel.y);
+                    }
+                    pixels_rendered += 1;
+                }
+                RenderMessage::Tile(pixels) => {
+                    // One upload per tile instead of one per pixel.
+                    if let Some((x, y, tile_width, tile_height, rgba)) = tile_rgba(&pixels, tm) {
+                        unsafe {
+                            texture.update_from_pixels(&rgba, tile_width, tile_height, x, y);
+                        }
+                    }
+                    pixels_rendered += pixels.len() as u32;
+                }
+                RenderMessage::Progress(count) => {
+                    pixels_rendered = count;
+                }
+                RenderMessage::Done => {
+                    rendering_complete = true;
+                    eprintln!("Rendering complete!");
+                }
+            }
+        }
+
+        window.clear(SFMLColor::rgb(30, 30, 30));
+
+        // The tiles finished so far, or the current pass of a progressive
+        // render
+        let mut sprite = Sprite::new();
+        sprite.set_texture(&texture, true);
+        window.draw(&sprite);
+
+        if !rendering_complete && !progressive {
+            // Show progress text in the top left corner, over the image
+            let progress_text = format!(
+                "Beep Boop..Tracing.. {}/{} pixels",
+                pixels_rendered, total_pixels
+            );
+
+            let mut text = Text::new(&progress_text, &font, 14);
+            text.set_fill_color(SFMLColor::WHITE);
+            text.set_outline_color(SFMLColor::BLACK);
+            text.set_outline_thickness(1.0);
+            text.set_position((6.0, 4.0));
+
+            window.draw(&text);
+        }
+
+        window.display();
+    }
+}
+
+// Polls the modification time of a scene file.
+struct SceneWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl SceneWatcher {
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            modified: Self::modified(path),
+            last_check: Instant::now(),
+        }
+    }
+
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|meta| meta.modified()).ok()
+    }
+
+    // Whether the file was modified since the last call, checking at most
+    // every INTERVAL.
+    fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < Self::INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let modified = Self::modified(&self.path);
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
+
+// Loads the changed scene file for the window. Editors may save a file in
+// steps, so a scene that doesn't load is reported and the current render
+// goes on; the next save is picked up again.
+fn reload_scene(options: &Options, config: &RenderConfig) -> Option<Scene> {
+    let scene = match prepare_scene(options) {
+        Ok(scene) => scene,
+        Err(err) => {
+            eprintln!("{}", err);
+            return None;
+        }
+    };
+    if let Err(err) = scene.config.validate() {
+        eprintln!("{}", err);
+        return None;
+    }
+    if (scene.config.width, scene.config.height) != (config.width, config.height) {
+        eprintln!(
+            "The window can't change size, restart to render at {}x{}",
+            scene.config.width, scene.config.height
+        );
+        return None;
+    }
+
+    Some(scene)
+}
+
+// The RGBA data of a finished tile, rows top first, with the position and
+// size of the rectangle it covers. None if the tile has no pixels.
+fn tile_rgba(pixels: &[Pixel], tm: ToneMap) -> Option<(u32, u32, u32, u32, Vec<u8>)> {
+    let x = pixels.iter().map(|pixel| pixel.x).min()?;
+    let y = pixels.iter().map(|pixel| pixel.y).min()?;
+    let width = pixels.iter().map(|pixel| pixel.x).max()? - x + 1;
+    let height = pixels.iter().map(|pixel| pixel.y).max()? - y + 1;
+
+    let mut rgba = vec![0; (width * height * 4) as usize];
+    for pixel in pixels {
+        let index = (((pixel.y - y) * width + pixel.x - x) * 4) as usize;
+        rgba[index..index + 4].copy_from_slice(&to_rgba(pixel.color, pixel.sample_count, tm));
+    }
+
+    Some((x, y, width, height, rgba))
+}
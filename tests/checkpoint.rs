@@ -0,0 +1,126 @@
+// Interrupts headless renders of the binary and resumes them from their
+// checkpoint.
+#![cfg(feature = "serde")]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    thread,
+    time::Duration,
+};
+
+// Six 64 x 64 tiles.
+const ARGS: &[&str] = &[
+    "--headless",
+    "--width",
+    "192",
+    "--height",
+    "128",
+    "--samples",
+    "8",
+    "--seed",
+    "7",
+];
+
+// Bytes the pixels of a tile take in a checkpoint: 4096 pixels of 68
+// bytes each. Once the file is twice as long, at least one tile is saved
+// whole.
+const TILE_BYTES: u64 = 4096 * 68;
+
+fn work_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn tracy(dir: &Path, output: &str, extra: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_tracy"));
+    command
+        .current_dir(dir)
+        .args(ARGS)
+        .args(["--output", output])
+        .args(extra)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+    command
+}
+
+fn run(mut command: Command) -> Output {
+    let output = command.output().unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    output
+}
+
+// Starts a render and kills it once it has saved a tile.
+fn interrupted_render(dir: &Path) {
+    let mut child: Child = tracy(dir, "killed.ppm", &[]).spawn().unwrap();
+    let checkpoint = dir.join("out.checkpoint");
+    loop {
+        let saved = fs::metadata(&checkpoint).map_or(0, |meta| meta.len());
+        if saved > 2 * TILE_BYTES {
+            break;
+        }
+        assert!(
+            child.try_wait().unwrap().is_none(),
+            "the render finished before it could be interrupted"
+        );
+        thread::sleep(Duration::from_millis(5));
+    }
+    child.kill().unwrap();
+    child.wait().unwrap();
+    assert!(!dir.join("killed.ppm").exists());
+}
+
+// The number of saved tiles a render picked up.
+fn resumed_tiles(output: &Output) -> usize {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("Resuming with "))
+        .map_or(0, |rest| rest.split(' ').next().unwrap().parse().unwrap())
+}
+
+#[test]
+fn resumed_renders_match_uninterrupted_ones() {
+    let dir = work_dir("resume");
+    run(tracy(&dir, "full.ppm", &[]));
+
+    interrupted_render(&dir);
+    let resumed = run(tracy(&dir, "resumed.ppm", &["--resume"]));
+
+    assert!(resumed_tiles(&resumed) > 0);
+    assert_eq!(
+        fs::read(dir.join("resumed.ppm")).unwrap(),
+        fs::read(dir.join("full.ppm")).unwrap()
+    );
+    // Finished renders leave nothing to resume.
+    assert!(!dir.join("out.checkpoint").exists());
+}
+
+#[test]
+fn damaged_checkpoints_are_rendered_from_scratch() {
+    let dir = work_dir("damaged");
+    run(tracy(&dir, "full.ppm", &[]));
+    interrupted_render(&dir);
+    let saved = fs::read(dir.join("out.checkpoint")).unwrap();
+
+    // The header is the magic, the version, the seed and the settings, and
+    // the first tile's pixel count follows its index.
+    let settings_length = u64::from_le_bytes(saved[20..28].try_into().unwrap());
+    let pixel_count = 28 + settings_length as usize + 4;
+    for (count, damage) in [(u64::MAX, "huge"), (5, "short")] {
+        let mut damaged = saved.clone();
+        damaged[pixel_count..pixel_count + 8].copy_from_slice(&count.to_le_bytes());
+        fs::write(dir.join("out.checkpoint"), damaged).unwrap();
+
+        let output = format!("{}.ppm", damage);
+        let resumed = run(tracy(&dir, &output, &["--resume"]));
+        assert_eq!(resumed_tiles(&resumed), 0, "{} pixel count", damage);
+        assert_eq!(
+            fs::read(dir.join(&output)).unwrap(),
+            fs::read(dir.join("full.ppm")).unwrap()
+        );
+    }
+}